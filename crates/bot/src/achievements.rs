@@ -118,10 +118,11 @@ pub async fn check_and_unlock(
     user_id: i64,
     guild_id: i64,
     profile: &db::entities::user_profile::Model,
+    curve: &crate::leveling::Curve,
     now: i64,
     db: &DatabaseConnection,
 ) -> Vec<&'static Achievement> {
-    let level = crate::leveling::level_from_xp(profile.xp);
+    let level = crate::leveling::level_from_xp(profile.xp, curve);
     let mut newly_unlocked = Vec::new();
 
     // Skip achievements already recorded, so a maxed-out active user doesn't attempt
@@ -194,21 +195,21 @@ mod tests {
         let profile = db::entities::user_profile::Model {
             user_id: 42,
             guild_id: 1,
-            xp: crate::leveling::xp_for_level(10),
+            xp: crate::leveling::xp_for_level(10, &Default::default()),
             total_voice_seconds: 0,
             last_daily_at: None,
             streak: 0,
             longest_session_seconds: 0,
         };
 
-        let first = check_and_unlock(42, 1, &profile, 1_000, &db).await;
+        let first = check_and_unlock(42, 1, &profile, &Default::default(), 1_000, &db).await;
         assert_eq!(
             first.iter().map(|a| a.id).collect::<Vec<_>>(),
             vec!["level-10"]
         );
 
         // Calling again with the same stats should unlock nothing new.
-        let second = check_and_unlock(42, 1, &profile, 2_000, &db).await;
+        let second = check_and_unlock(42, 1, &profile, &Default::default(), 2_000, &db).await;
         assert!(second.is_empty());
     }
 
@@ -220,14 +221,14 @@ mod tests {
         let profile = db::entities::user_profile::Model {
             user_id: 42,
             guild_id: 1,
-            xp: crate::leveling::xp_for_level(10),
+            xp: crate::leveling::xp_for_level(10, &Default::default()),
             total_voice_seconds: 0,
             last_daily_at: None,
             streak: 7,
             longest_session_seconds: 2 * 3600,
         };
 
-        let unlocked = check_and_unlock(42, 1, &profile, 1_000, &db).await;
+        let unlocked = check_and_unlock(42, 1, &profile, &Default::default(), 1_000, &db).await;
         let mut ids: Vec<&str> = unlocked.iter().map(|a| a.id).collect();
         ids.sort_unstable();
         assert_eq!(ids, vec!["level-10", "session-2h", "streak-7"]);
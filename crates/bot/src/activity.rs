@@ -1,158 +1,592 @@
 use poise::serenity_prelude::{ActivityType, Context, Member};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use db::DatabaseConnection;
 
+use crate::template::{ActivityKind, Template, Vars};
+
 /// App-level fallback used when neither a guild override nor `DEFAULT_CHANNEL_NAME_TEMPLATE`
-/// is set. `{game}` is substituted with the majority game or `"General"`.
+/// is set. `{game}` is substituted with the winning activity or `"General"`.
 pub const DEFAULT_CHANNEL_NAME_TEMPLATE: &str = "[{game}]";
 
-/// Resolves the channel-name template for a guild: its DB override if one is set,
-/// otherwise the app-level default (env var or [`DEFAULT_CHANNEL_NAME_TEMPLATE`]).
+/// Resolves the channel-name template for a channel spawned from `primary_channel_id`:
+/// the trigger's override if one is set, then the guild's, then the app-level default
+/// (env var or [`DEFAULT_CHANNEL_NAME_TEMPLATE`]).
 pub async fn resolve_template(
     guild_id: i64,
+    primary_channel_id: Option<i64>,
     db: &DatabaseConnection,
     default_template: &str,
 ) -> String {
+    if let Some(trigger_id) = primary_channel_id {
+        if let Ok(Some(template)) = db::repositories::primary_channel::find(trigger_id, db)
+            .await
+            .map(|t| t.and_then(|t| t.name_template))
+        {
+            return template;
+        }
+    }
     match db::repositories::guild::channel_name_template(guild_id, db).await {
         Ok(Some(template)) => template,
         _ => default_template.to_owned(),
     }
 }
 
-/// Renders `template` by substituting `{game}` with `game`. Templates without the
-/// placeholder are returned unchanged, giving admins the option of a static name.
-pub fn render_channel_name(template: &str, game: &str) -> String {
-    if template.contains("{game}") {
-        template.replace("{game}", game)
-    } else {
-        template.to_owned()
+/// Discord's maximum channel-name length, in characters.
+const MAX_CHANNEL_NAME_CHARS: usize = 100;
+
+/// Renders `template` with `vars`, truncated to Discord's channel-name limit. Templates
+/// are validated when saved, but the app-level default comes from the environment
+/// unchecked, so one that fails to parse is used verbatim as a static name.
+pub fn render_channel_name(template: &str, vars: &Vars) -> String {
+    let rendered = match Template::parse(template) {
+        Ok(parsed) => parsed.render(vars),
+        Err(e) => {
+            tracing::warn!("Channel-name template `{template}` is invalid ({e}); using it as-is");
+            template.to_owned()
+        }
+    };
+    rendered.chars().take(MAX_CHANNEL_NAME_CHARS).collect()
+}
+
+/// Facts about a channel besides what its members are doing, available to the template.
+#[derive(Clone, Debug, Default)]
+pub struct ChannelFacts {
+    pub owner: Option<String>,
+    pub ordinal: i64,
+}
+
+/// How a guild's channels are named after their members' activities. Configured with
+/// `/config naming`; guilds that never set one use [`NamingPolicy::default`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NamingPolicy {
+    /// Name after the most common activity even without a majority.
+    pub plurality: bool,
+    /// Share of members, in percent, the top activity needs in majority mode.
+    pub threshold_percent: u8,
+    pub count_streaming: bool,
+    pub count_listening: bool,
+    pub count_competing: bool,
+    pub ignore_bots: bool,
+    /// Lowercased activity name → name to show instead, or `None` to ignore it.
+    pub aliases: HashMap<String, Option<String>>,
+}
+
+impl Default for NamingPolicy {
+    /// Only games count, and one needs at least half the channel.
+    fn default() -> Self {
+        Self {
+            plurality: false,
+            threshold_percent: 50,
+            count_streaming: false,
+            count_listening: false,
+            count_competing: false,
+            ignore_bots: false,
+            aliases: HashMap::new(),
+        }
     }
 }
 
-/// Renders `template` using the majority game if ≥ 50 % of members share it, else `"General"`.
-pub async fn suggested_name(members: &[Member], ctx: &Context, template: &str) -> String {
-    let total = members.len();
-    if total == 0 {
-        return render_channel_name(template, "General");
+impl NamingPolicy {
+    /// Loads the guild's policy and activity aliases; see [`crate::policy::load_or_default`].
+    pub async fn load(guild_id: i64, db: &DatabaseConnection) -> Self {
+        let get = db::repositories::naming_policy::get(guild_id, db);
+        let policy = crate::policy::load_or_default("naming policy", guild_id, get).await;
+        let aliases = db::repositories::activity_alias::list_by_guild(guild_id, db)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!("Failed to load activity aliases for guild {guild_id}: {e}");
+                Vec::new()
+            });
+        Self::from_rows(policy, aliases)
     }
 
-    let mut game_counts: HashMap<String, usize> = HashMap::new();
+    pub fn from_rows(
+        policy: Option<db::entities::naming_policy::Model>,
+        aliases: Vec<db::entities::activity_alias::Model>,
+    ) -> Self {
+        let mut this = match policy {
+            Some(p) => Self {
+                plurality: p.plurality,
+                threshold_percent: p.threshold_percent.clamp(1, 100) as u8,
+                count_streaming: p.count_streaming,
+                count_listening: p.count_listening,
+                count_competing: p.count_competing,
+                ignore_bots: p.ignore_bots,
+                aliases: HashMap::new(),
+            },
+            None => Self::default(),
+        };
+        this.aliases = aliases.into_iter().map(|a| (a.activity, a.alias)).collect();
+        this
+    }
+
+    fn counts(&self, kind: ActivityKind) -> bool {
+        match kind {
+            ActivityKind::Playing => true,
+            ActivityKind::Streaming => self.count_streaming,
+            ActivityKind::Listening => self.count_listening,
+            ActivityKind::Competing => self.count_competing,
+        }
+    }
+
+    /// Whether an activity shared by `count` of `total` members names the channel.
+    fn accepts(&self, count: usize, total: usize) -> bool {
+        count > 0 && (self.plurality || count * 100 >= usize::from(self.threshold_percent) * total)
+    }
+}
+
+/// What one member is doing, as far as naming is concerned.
+#[derive(Clone, Debug, Default)]
+pub struct MemberActivity {
+    pub bot: bool,
+    pub activities: Vec<(ActivityKind, String)>,
+}
+
+fn activity_kind(kind: ActivityType) -> Option<ActivityKind> {
+    match kind {
+        ActivityType::Playing => Some(ActivityKind::Playing),
+        ActivityType::Streaming => Some(ActivityKind::Streaming),
+        ActivityType::Listening => Some(ActivityKind::Listening),
+        ActivityType::Competing => Some(ActivityKind::Competing),
+        _ => None,
+    }
+}
+
+/// An automatic channel name and the activity it was picked after, if any.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Suggestion {
+    pub name: String,
+    pub game: Option<String>,
+}
+
+/// Renders `template` after the activity `policy` picks for `members`, else `"General"`.
+pub async fn suggested_name(
+    members: &[Member],
+    ctx: &Context,
+    template: &str,
+    facts: &ChannelFacts,
+    policy: &NamingPolicy,
+) -> Suggestion {
+    let mut observed: Vec<MemberActivity> = members
+        .iter()
+        .map(|m| MemberActivity {
+            bot: m.user.bot,
+            activities: Vec::new(),
+        })
+        .collect();
 
     // All members belong to the same guild, so the presence cache only needs fetching once.
     if let Some(guild) = members.first().and_then(|m| ctx.cache.guild(m.guild_id)) {
-        for member in members {
+        for (member, observed) in members.iter().zip(&mut observed) {
             let Some(presence) = guild.presences.get(&member.user.id) else {
                 continue;
             };
-            for activity in &presence.activities {
-                if activity.kind == ActivityType::Playing {
-                    *game_counts.entry(activity.name.clone()).or_insert(0) += 1;
-                }
-            }
+            observed.activities = presence
+                .activities
+                .iter()
+                .filter_map(|a| activity_kind(a.kind).map(|kind| (kind, a.name.clone())))
+                .collect();
         }
     }
 
-    pick_name(&game_counts, total, template)
+    pick_name(&observed, template, facts, policy)
 }
 
-/// Pure decision: the majority game (≥ 50 % of `total` members) rendered into `template`,
-/// else `"General"`. Split out from [`suggested_name`] so the threshold logic is testable
-/// without a live Discord cache.
-fn pick_name(game_counts: &HashMap<String, usize>, total: usize, template: &str) -> String {
-    if let Some((game, count)) = game_counts.iter().max_by_key(|(_, c)| **c) {
-        if count * 2 >= total {
-            return render_channel_name(template, game);
+/// Pure decision: tallies each member's activities under `policy` and renders `template`
+/// with the winner, or the template's fallback when nothing qualifies. Ties go to the
+/// alphabetically first name so the result is stable. Split out from [`suggested_name`]
+/// so the policy logic is testable without a live Discord cache.
+fn pick_name(
+    members: &[MemberActivity],
+    template: &str,
+    facts: &ChannelFacts,
+    policy: &NamingPolicy,
+) -> Suggestion {
+    let mut total = 0;
+    let mut tally: HashMap<&str, (usize, ActivityKind)> = HashMap::new();
+    for member in members {
+        if policy.ignore_bots && member.bot {
+            continue;
         }
+        total += 1;
+        // A member counts once per name, even if two of their activities alias to it.
+        let mut seen = HashSet::new();
+        for (kind, name) in &member.activities {
+            if !policy.counts(*kind) {
+                continue;
+            }
+            let name = match policy.aliases.get(&name.to_lowercase()) {
+                Some(None) => continue,
+                Some(Some(alias)) => alias.as_str(),
+                None => name.as_str(),
+            };
+            if seen.insert(name) {
+                tally.entry(name).or_insert((0, *kind)).0 += 1;
+            }
+        }
+    }
+
+    let winner = tally
+        .into_iter()
+        .max_by(|(a, (a_count, _)), (b, (b_count, _))| a_count.cmp(b_count).then(b.cmp(a)))
+        .filter(|(_, (count, _))| policy.accepts(*count, total));
+
+    let name = render_channel_name(
+        template,
+        &Vars {
+            game: winner.map(|(name, _)| name),
+            activity: winner.map(|(_, (_, kind))| kind),
+            owner: facts.owner.as_deref(),
+            count: members.len(),
+            ordinal: facts.ordinal,
+        },
+    );
+    Suggestion {
+        name,
+        game: winner.map(|(name, _)| name.to_owned()),
     }
-    render_channel_name(template, "General")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// One member per entry, playing that game; `""` means doing nothing.
+    fn playing(games: &[&str]) -> Vec<MemberActivity> {
+        games
+            .iter()
+            .map(|g| MemberActivity {
+                bot: false,
+                activities: if g.is_empty() {
+                    Vec::new()
+                } else {
+                    vec![(ActivityKind::Playing, (*g).to_owned())]
+                },
+            })
+            .collect()
+    }
+
+    fn name(members: &[MemberActivity], policy: &NamingPolicy) -> String {
+        pick_name(
+            members,
+            DEFAULT_CHANNEL_NAME_TEMPLATE,
+            &ChannelFacts::default(),
+            policy,
+        )
+        .name
+    }
+
     #[test]
     fn empty_counts_default_to_general() {
-        assert_eq!(
-            pick_name(&HashMap::new(), 5, DEFAULT_CHANNEL_NAME_TEMPLATE),
-            "[General]"
+        let members = playing(&["", "", "", "", ""]);
+        assert_eq!(name(&members, &NamingPolicy::default()), "[General]");
+    }
+
+    #[test]
+    fn the_winning_game_is_reported_with_the_name() {
+        let members = playing(&["Chess", "Chess", "Chess", "", ""]);
+        let picked = pick_name(
+            &members,
+            "{game|Hangout}",
+            &ChannelFacts::default(),
+            &NamingPolicy::default(),
+        );
+        assert_eq!(picked.game.as_deref(), Some("Chess"));
+
+        let none = pick_name(
+            &playing(&["", ""]),
+            "{game|Hangout}",
+            &ChannelFacts::default(),
+            &NamingPolicy::default(),
         );
+        assert_eq!(none.game, None);
     }
 
     #[test]
     fn majority_game_wins() {
-        let mut counts = HashMap::new();
-        counts.insert("Chess".to_owned(), 3);
+        let members = playing(&["Chess", "Chess", "Chess", "", ""]);
+        assert_eq!(name(&members, &NamingPolicy::default()), "[Chess]");
+    }
+
+    #[test]
+    fn exact_half_counts_as_majority() {
+        let members = playing(&["Chess", "Chess", "", ""]);
+        assert_eq!(name(&members, &NamingPolicy::default()), "[Chess]");
+    }
+
+    #[test]
+    fn below_half_falls_back_to_general() {
+        let members = playing(&["Chess", "", "", ""]);
+        assert_eq!(name(&members, &NamingPolicy::default()), "[General]");
+    }
+
+    #[test]
+    fn ties_pick_the_alphabetically_first_name() {
+        let members = playing(&["Go", "Go", "Chess", "Chess"]);
+        assert_eq!(name(&members, &NamingPolicy::default()), "[Chess]");
+    }
+
+    #[test]
+    fn custom_template_is_used_for_majority_game() {
+        let members = playing(&["Chess", "Chess", "Chess", "", ""]);
         assert_eq!(
-            pick_name(&counts, 5, DEFAULT_CHANNEL_NAME_TEMPLATE),
-            "[Chess]"
+            pick_name(
+                &members,
+                "🎮 {game}",
+                &ChannelFacts::default(),
+                &NamingPolicy::default()
+            )
+            .name,
+            "🎮 Chess"
         );
     }
 
     #[test]
-    fn exact_half_counts_as_majority() {
-        let mut counts = HashMap::new();
-        counts.insert("Chess".to_owned(), 2);
+    fn custom_template_is_used_for_general_fallback() {
         assert_eq!(
-            pick_name(&counts, 4, DEFAULT_CHANNEL_NAME_TEMPLATE),
-            "[Chess]"
+            pick_name(
+                &playing(&["", ""]),
+                "🎮 {game}",
+                &ChannelFacts::default(),
+                &NamingPolicy::default()
+            )
+            .name,
+            "🎮 General"
         );
     }
 
     #[test]
-    fn below_half_falls_back_to_general() {
-        let mut counts = HashMap::new();
-        counts.insert("Chess".to_owned(), 1);
+    fn owner_count_and_ordinal_are_available_to_the_template() {
+        let members = playing(&["Chess", "Chess", "Chess", "", ""]);
+        let facts = ChannelFacts {
+            owner: Some("Alice".to_owned()),
+            ordinal: 2,
+        };
+        assert_eq!(
+            pick_name(
+                &members,
+                "{emoji} {game} #{n} ({owner}, {count})",
+                &facts,
+                &NamingPolicy::default()
+            )
+            .name,
+            "🎮 Chess #2 (Alice, 5)"
+        );
+    }
+
+    #[test]
+    fn fallback_is_used_when_there_is_no_majority() {
+        assert_eq!(
+            pick_name(
+                &playing(&["Chess", "Go", ""]),
+                "{game|Hangout}",
+                &ChannelFacts::default(),
+                &NamingPolicy::default()
+            )
+            .name,
+            "Hangout"
+        );
+    }
+
+    #[test]
+    fn plurality_names_after_the_top_game_without_a_majority() {
+        let members = playing(&["Chess", "Chess", "Go", "", "", ""]);
+        let policy = NamingPolicy {
+            plurality: true,
+            ..Default::default()
+        };
+        assert_eq!(name(&members, &policy), "[Chess]");
+    }
+
+    #[test]
+    fn a_higher_threshold_rejects_a_bare_majority() {
+        let members = playing(&["Chess", "Chess", "", ""]);
+        let policy = NamingPolicy {
+            threshold_percent: 75,
+            ..Default::default()
+        };
+        assert_eq!(name(&members, &policy), "[General]");
+    }
+
+    #[test]
+    fn other_activity_kinds_only_count_when_enabled() {
+        let members = vec![
+            MemberActivity {
+                bot: false,
+                activities: vec![(ActivityKind::Listening, "Spotify".to_owned())],
+            };
+            2
+        ];
+        assert_eq!(name(&members, &NamingPolicy::default()), "[General]");
+
+        let policy = NamingPolicy {
+            count_listening: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            pick_name(
+                &members,
+                "{emoji} {activity} {game}",
+                &ChannelFacts::default(),
+                &policy
+            )
+            .name,
+            "🎧 Listening Spotify"
+        );
+    }
+
+    #[test]
+    fn ignored_bots_do_not_dilute_the_majority() {
+        let mut members = playing(&["Chess", "Chess", "", ""]);
+        members[2].bot = true;
+        members[3].bot = true;
+        let policy = NamingPolicy {
+            threshold_percent: 100,
+            ignore_bots: true,
+            ..Default::default()
+        };
+        assert_eq!(name(&members, &policy), "[Chess]");
         assert_eq!(
-            pick_name(&counts, 4, DEFAULT_CHANNEL_NAME_TEMPLATE),
+            name(
+                &members,
+                &NamingPolicy {
+                    ignore_bots: false,
+                    ..policy
+                }
+            ),
             "[General]"
         );
     }
 
     #[test]
-    fn ties_pick_a_consistent_winner_without_panicking() {
-        let mut counts = HashMap::new();
-        counts.insert("Chess".to_owned(), 2);
-        counts.insert("Go".to_owned(), 2);
-        let name = pick_name(&counts, 4, DEFAULT_CHANNEL_NAME_TEMPLATE);
-        assert!(name == "[Chess]" || name == "[Go]");
+    fn aliases_rename_and_merge_activities() {
+        let members = playing(&["Counter-Strike 2", "counter-strike 2", "Chess"]);
+        let policy = NamingPolicy {
+            aliases: HashMap::from([("counter-strike 2".to_owned(), Some("CS2".to_owned()))]),
+            ..Default::default()
+        };
+        assert_eq!(name(&members, &policy), "[CS2]");
     }
 
     #[test]
-    fn custom_template_is_used_for_majority_game() {
-        let mut counts = HashMap::new();
-        counts.insert("Chess".to_owned(), 3);
-        assert_eq!(pick_name(&counts, 5, "🎮 {game}"), "🎮 Chess");
+    fn ignored_activities_are_not_counted() {
+        let members = vec![
+            MemberActivity {
+                bot: false,
+                activities: vec![
+                    (ActivityKind::Listening, "Spotify".to_owned()),
+                    (ActivityKind::Playing, "Chess".to_owned()),
+                ],
+            },
+            MemberActivity {
+                bot: false,
+                activities: vec![(ActivityKind::Listening, "Spotify".to_owned())],
+            },
+        ];
+        let policy = NamingPolicy {
+            count_listening: true,
+            aliases: HashMap::from([("spotify".to_owned(), None)]),
+            ..Default::default()
+        };
+        assert_eq!(name(&members, &policy), "[Chess]");
     }
 
     #[test]
-    fn custom_template_is_used_for_general_fallback() {
-        assert_eq!(pick_name(&HashMap::new(), 5, "🎮 {game}"), "🎮 General");
+    fn a_member_counts_once_per_aliased_name() {
+        let members = vec![
+            MemberActivity {
+                bot: false,
+                activities: vec![
+                    (ActivityKind::Playing, "CS:GO".to_owned()),
+                    (ActivityKind::Playing, "Counter-Strike 2".to_owned()),
+                ],
+            },
+            MemberActivity::default(),
+            MemberActivity::default(),
+        ];
+        let policy = NamingPolicy {
+            aliases: HashMap::from([
+                ("cs:go".to_owned(), Some("CS".to_owned())),
+                ("counter-strike 2".to_owned(), Some("CS".to_owned())),
+            ]),
+            ..Default::default()
+        };
+        assert_eq!(name(&members, &policy), "[General]");
+    }
+
+    #[test]
+    fn from_rows_clamps_the_threshold_and_maps_aliases() {
+        let policy = NamingPolicy::from_rows(
+            Some(db::entities::naming_policy::Model {
+                guild_id: 1,
+                plurality: false,
+                threshold_percent: 250,
+                count_streaming: true,
+                count_listening: false,
+                count_competing: false,
+                ignore_bots: true,
+            }),
+            vec![db::entities::activity_alias::Model {
+                guild_id: 1,
+                activity: "spotify".to_owned(),
+                alias: None,
+            }],
+        );
+        assert_eq!(policy.threshold_percent, 100);
+        assert!(policy.count_streaming && policy.ignore_bots);
+        assert_eq!(policy.aliases.get("spotify"), Some(&None));
+    }
+
+    fn game(name: &str) -> Vars<'_> {
+        Vars {
+            game: Some(name),
+            ..Default::default()
+        }
     }
 
     #[test]
     fn render_channel_name_substitutes_the_placeholder() {
-        assert_eq!(render_channel_name("[{game}]", "Chess"), "[Chess]");
+        assert_eq!(render_channel_name("[{game}]", &game("Chess")), "[Chess]");
     }
 
     #[test]
     fn render_channel_name_without_placeholder_is_returned_unchanged() {
-        assert_eq!(render_channel_name("Voice Chat", "Chess"), "Voice Chat");
+        assert_eq!(
+            render_channel_name("Voice Chat", &game("Chess")),
+            "Voice Chat"
+        );
     }
 
     #[test]
     fn render_channel_name_substitutes_every_occurrence() {
         assert_eq!(
-            render_channel_name("{game} - {game}", "Chess"),
+            render_channel_name("{game} - {game}", &game("Chess")),
             "Chess - Chess"
         );
     }
 
+    #[test]
+    fn render_channel_name_uses_an_invalid_template_verbatim() {
+        assert_eq!(render_channel_name("[{game]", &game("Chess")), "[{game]");
+    }
+
+    #[test]
+    fn render_channel_name_truncates_to_discords_limit() {
+        let long = "x".repeat(150);
+        assert_eq!(
+            render_channel_name("{game}", &game(&long)).chars().count(),
+            MAX_CHANNEL_NAME_CHARS
+        );
+    }
+
     #[tokio::test]
     async fn resolve_template_falls_back_to_default_when_unset() {
         let db = db::connection::connect_in_memory_for_tests().await.unwrap();
-        assert_eq!(resolve_template(1, &db, "🎮 {game}").await, "🎮 {game}");
+        assert_eq!(
+            resolve_template(1, None, &db, "🎮 {game}").await,
+            "🎮 {game}"
+        );
     }
 
     #[tokio::test]
@@ -162,8 +596,50 @@ mod tests {
             .await
             .unwrap();
         assert_eq!(
-            resolve_template(1, &db, "🎮 {game}").await,
+            resolve_template(1, None, &db, "🎮 {game}").await,
             "[{game}]".to_string()
         );
     }
+
+    #[tokio::test]
+    async fn resolve_template_prefers_the_trigger_override() {
+        let db = db::connection::connect_in_memory_for_tests().await.unwrap();
+        db::repositories::guild::set_channel_name_template(1, Some("[{game}]".to_string()), &db)
+            .await
+            .unwrap();
+        db::repositories::primary_channel::insert(10, 1, &db)
+            .await
+            .unwrap();
+        db::repositories::primary_channel::update_settings(
+            db::entities::primary_channel::Model {
+                id: 10,
+                guild_id: 1,
+                name_template: Some("Squad {game}".to_string()),
+                user_limit: None,
+                bitrate: None,
+                category_id: None,
+                private: false,
+                rtc_region: None,
+                video_quality_mode: None,
+                max_channels: None,
+                fill_existing: false,
+            },
+            &db,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            resolve_template(1, Some(10), &db, "🎮 {game}").await,
+            "Squad {game}"
+        );
+        // Triggers without an override still use the guild's template.
+        db::repositories::primary_channel::insert(11, 1, &db)
+            .await
+            .unwrap();
+        assert_eq!(
+            resolve_template(1, Some(11), &db, "🎮 {game}").await,
+            "[{game}]"
+        );
+    }
 }
@@ -12,15 +12,33 @@ pub fn all_commands() -> Vec<poise::Command<Data, Error>> {
         crate::commands::admin::register(),
         crate::commands::admin::triggers(),
         crate::commands::admin::remove_trigger(),
+        crate::commands::admin::trigger(),
         crate::commands::admin::config(),
         crate::commands::profile::profile(),
         crate::commands::ranking::ranking(),
+        crate::commands::history::history(),
         crate::commands::stats::serverstats(),
         crate::commands::voice::rename(),
+        crate::commands::voice::autoname(),
         crate::commands::voice::limit(),
         crate::commands::voice::unlimit(),
+        crate::commands::voice::bitrate(),
+        crate::commands::voice::region(),
+        crate::commands::voice::video_quality(),
         crate::commands::voice::private(),
         crate::commands::voice::public(),
+        crate::commands::voice::hide(),
+        crate::commands::voice::unhide(),
+        crate::commands::voice::permit(),
+        crate::commands::voice::reject(),
+        crate::commands::voice::unlist(),
+        crate::commands::voice::transfer(),
+        crate::commands::voice::claim(),
+        crate::commands::voice::vkick(),
+        crate::commands::voice::vban(),
+        crate::commands::voice::vmute(),
+        crate::commands::voice::vdeafen(),
+        crate::commands::voice::preset(),
     ]
 }
 
@@ -43,7 +61,7 @@ pub async fn build_and_run(
             },
             ..Default::default()
         })
-        .setup(|ctx, _ready, framework| {
+        .setup(|ctx, ready, framework| {
             Box::pin(async move {
                 register_commands_on_startup(ctx, framework).await?;
                 bot_ctx
@@ -53,6 +71,24 @@ pub async fn build_and_run(
                         shard_manager: framework.shard_manager().clone(),
                     })
                     .ok();
+                crate::events::join_request::spawn_expiry_sweep(ctx.http.clone(), data.db.clone());
+                crate::events::grace::spawn_sweeper(
+                    ctx.cache.clone(),
+                    ctx.http.clone(),
+                    data.db.clone(),
+                    data.renames.clone(),
+                );
+                crate::events::idle::spawn_sweeper(
+                    ctx.cache.clone(),
+                    ctx.http.clone(),
+                    data.db.clone(),
+                );
+                if let Err(e) = crate::events::removal::mark_missing(&ready.guilds, &data.db).await
+                {
+                    tracing::warn!("Failed to check for guilds left while offline: {e}");
+                }
+                crate::events::removal::spawn_purge(data.db.clone());
+                crate::events::xp::spawn_checkpoints(ctx.cache.clone(), data.db.clone());
                 tracing::info!("Bot ready");
                 Ok(data)
             })
@@ -2,7 +2,13 @@ use poise::serenity_prelude as serenity;
 
 use crate::{
     context_ext::ContextExt,
+    events::{
+        grace, idle::Policy as IdlePolicy, join_rate::Policy as JoinRatePolicy,
+        join_request::Policy as JoinRequestPolicy, limits::Policy as LimitPolicy, removal,
+        xp::Policy as XpPolicy,
+    },
     permissions::{self, Category},
+    presets::{Region, VideoQuality},
     Context, Error,
 };
 
@@ -43,7 +49,7 @@ pub async fn permissions(ctx: Context<'_>) -> Result<(), Error> {
         let icon = match (has, entry.category) {
             (true, _) => "🟢",
             (false, Category::Core) => "🔴",
-            (false, Category::Privacy) => "🟠",
+            (false, Category::Privacy | Category::Moderation) => "🟠",
         };
         lines.push(format!("{icon} **{}** — {}", entry.name, entry.description));
     }
@@ -90,7 +96,10 @@ pub async fn triggers(ctx: Context<'_>) -> Result<(), Error> {
     } else {
         let lines: Vec<String> = channels
             .iter()
-            .map(|c| format!("• <#{}>", c.id as u64))
+            .map(|c| match describe_settings(c) {
+                Some(settings) => format!("• <#{}> — {settings}", c.id as u64),
+                None => format!("• <#{}>", c.id as u64),
+            })
             .collect();
         format!("**Auto-voice trigger channels:**\n{}", lines.join("\n"))
     };
@@ -162,18 +171,191 @@ pub async fn remove_trigger(
     Ok(())
 }
 
+/// Configure the channels created by a specific trigger.
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands("trigger_edit", "trigger_reset"),
+    check = "has_manage_channels"
+)]
+pub async fn trigger(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Change the defaults for channels created from a trigger. Omitted options are kept.
+#[allow(clippy::too_many_arguments)] // one per slash-command option
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "edit",
+    check = "has_manage_channels"
+)]
+pub async fn trigger_edit(
+    ctx: Context<'_>,
+    #[description = "The trigger channel to configure"]
+    #[channel_types("Voice")]
+    channel: serenity::GuildChannel,
+    #[description = "Name template overriding the server's, e.g. \"Squad {game}\""]
+    #[max_length = 100]
+    name_template: Option<String>,
+    #[description = "Default user limit (0 = unlimited)"]
+    #[min = 0_u32]
+    #[max = 99_u32]
+    user_limit: Option<u32>,
+    #[description = "Default bitrate in kbps"]
+    #[min = 8_u32]
+    #[max = 384_u32]
+    bitrate: Option<u32>,
+    #[description = "Category to create channels in (default: the trigger's own)"]
+    #[channel_types("Category")]
+    category: Option<serenity::GuildChannel>,
+    #[description = "Create channels already locked"] private: Option<bool>,
+    #[description = "Voice region for new channels"] region: Option<Region>,
+    #[description = "Video quality for new channels"] video_quality: Option<VideoQuality>,
+    #[description = "Most channels this trigger may have at once (0 = unlimited)"]
+    #[max = 500_u32]
+    max_channels: Option<u32>,
+    #[description = "Move joiners into an existing public channel with a free slot before creating one"]
+    fill_existing: Option<bool>,
+) -> Result<(), Error> {
+    let Some(mut settings) =
+        db::repositories::primary_channel::find(channel.id.get() as i64, &ctx.data().db).await?
+    else {
+        ctx.say_ephemeral(format!(
+            "<#{}> is not a registered trigger channel.",
+            channel.id
+        ))
+        .await?;
+        return Ok(());
+    };
+
+    if let Some(template) = name_template {
+        if !require_valid_template(ctx, &template).await? {
+            return Ok(());
+        }
+        settings.name_template = Some(template);
+    }
+    if let Some(limit) = user_limit {
+        settings.user_limit = (limit > 0).then_some(i64::from(limit));
+    }
+    if let Some(kbps) = bitrate {
+        let max_bitrate = ctx
+            .guild()
+            .map(|g| crate::presets::max_bitrate(g.premium_tier))
+            .unwrap_or(crate::presets::max_bitrate(serenity::PremiumTier::Tier0));
+        if kbps * 1000 > max_bitrate {
+            ctx.say_ephemeral(format!(
+                "This server's boost level allows at most **{} kbps**.",
+                max_bitrate / 1000
+            ))
+            .await?;
+            return Ok(());
+        }
+        settings.bitrate = Some(i64::from(kbps) * 1000);
+    }
+    if let Some(category) = category {
+        settings.category_id = Some(category.id.get() as i64);
+    }
+    if let Some(private) = private {
+        settings.private = private;
+    }
+    if let Some(region) = region {
+        settings.rtc_region = region.id().map(str::to_owned);
+    }
+    if let Some(quality) = video_quality {
+        settings.video_quality_mode = Some(i64::from(u8::from(quality.mode())));
+    }
+    if let Some(max) = max_channels {
+        settings.max_channels = (max > 0).then_some(i64::from(max));
+    }
+    if let Some(fill) = fill_existing {
+        settings.fill_existing = fill;
+    }
+
+    db::repositories::primary_channel::update_settings(settings.clone(), &ctx.data().db).await?;
+
+    ctx.say(format!(
+        "<#{}> settings: {}",
+        channel.id,
+        describe_settings(&settings).unwrap_or_else(|| "server defaults".to_string())
+    ))
+    .await?;
+    Ok(())
+}
+
+/// Clear every per-trigger setting so the trigger uses the server defaults again.
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "reset",
+    check = "has_manage_channels"
+)]
+pub async fn trigger_reset(
+    ctx: Context<'_>,
+    #[description = "The trigger channel to reset"]
+    #[channel_types("Voice")]
+    channel: serenity::GuildChannel,
+) -> Result<(), Error> {
+    let Some(settings) =
+        db::repositories::primary_channel::find(channel.id.get() as i64, &ctx.data().db).await?
+    else {
+        ctx.say_ephemeral(format!(
+            "<#{}> is not a registered trigger channel.",
+            channel.id
+        ))
+        .await?;
+        return Ok(());
+    };
+
+    db::repositories::primary_channel::update_settings(
+        db::entities::primary_channel::Model {
+            id: settings.id,
+            guild_id: settings.guild_id,
+            name_template: None,
+            user_limit: None,
+            bitrate: None,
+            category_id: None,
+            private: false,
+            rtc_region: None,
+            video_quality_mode: None,
+            max_channels: None,
+            fill_existing: false,
+        },
+        &ctx.data().db,
+    )
+    .await?;
+
+    ctx.say(format!("<#{}> now uses the server defaults.", channel.id))
+        .await?;
+    Ok(())
+}
+
 /// Configure per-server settings.
 #[poise::command(
     slash_command,
     guild_only,
-    subcommands("channel_name"),
+    subcommands(
+        "channel_name",
+        "naming",
+        "alias",
+        "ignore",
+        "unalias",
+        "join_requests",
+        "idle",
+        "empty_channels",
+        "channel_limits",
+        "join_rate",
+        "retention",
+        "history",
+        "xp"
+    ),
     check = "has_manage_channels"
 )]
 pub async fn config(_ctx: Context<'_>) -> Result<(), Error> {
     Ok(())
 }
 
-/// Set the temp-channel naming template for this server. Must contain `{game}`.
+/// Set the temp-channel naming template for this server, e.g. `{emoji} {game|Hangout}`.
 #[poise::command(
     slash_command,
     guild_only,
@@ -182,13 +364,11 @@ pub async fn config(_ctx: Context<'_>) -> Result<(), Error> {
 )]
 pub async fn channel_name(
     ctx: Context<'_>,
-    #[description = "Template containing {game}, e.g. \"[{game}]\" or \"🎮 {game}\""]
+    #[description = "Uses {game} {activity} {emoji} {owner} {count} {n}; {game|Hangout} sets a fallback"]
     #[max_length = 100]
     template: String,
 ) -> Result<(), Error> {
-    if !template.contains("{game}") {
-        ctx.say_ephemeral("Template must contain `{game}`, e.g. `[{game}]`.")
-            .await?;
+    if !require_valid_template(ctx, &template).await? {
         return Ok(());
     }
 
@@ -207,6 +387,595 @@ pub async fn channel_name(
     Ok(())
 }
 
+#[derive(Copy, Clone, Debug, poise::ChoiceParameter)]
+pub enum NamingMode {
+    #[name = "Majority (needs the threshold)"]
+    Majority,
+    #[name = "Plurality (most common wins)"]
+    Plurality,
+}
+
+/// Choose which activities name temp channels. Omitted options are kept.
+#[poise::command(slash_command, guild_only, check = "has_manage_channels")]
+pub async fn naming(
+    ctx: Context<'_>,
+    #[description = "Whether the top activity needs a majority"] mode: Option<NamingMode>,
+    #[description = "Share of members, in percent, needed in majority mode (default 50)"]
+    #[min = 1_u8]
+    #[max = 100_u8]
+    threshold: Option<u8>,
+    #[description = "Count streams"] streaming: Option<bool>,
+    #[description = "Count listening activities such as Spotify"] listening: Option<bool>,
+    #[description = "Count competing activities"] competing: Option<bool>,
+    #[description = "Leave bots out of the count"] ignore_bots: Option<bool>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap().get() as i64;
+    let db = &ctx.data().db;
+    let mut policy = db::repositories::naming_policy::get(guild_id, db)
+        .await?
+        .unwrap_or_else(|| {
+            let defaults = crate::activity::NamingPolicy::default();
+            db::entities::naming_policy::Model {
+                guild_id,
+                plurality: defaults.plurality,
+                threshold_percent: i64::from(defaults.threshold_percent),
+                count_streaming: defaults.count_streaming,
+                count_listening: defaults.count_listening,
+                count_competing: defaults.count_competing,
+                ignore_bots: defaults.ignore_bots,
+            }
+        });
+
+    if let Some(mode) = mode {
+        policy.plurality = matches!(mode, NamingMode::Plurality);
+    }
+    if let Some(threshold) = threshold {
+        policy.threshold_percent = i64::from(threshold);
+    }
+    if let Some(streaming) = streaming {
+        policy.count_streaming = streaming;
+    }
+    if let Some(listening) = listening {
+        policy.count_listening = listening;
+    }
+    if let Some(competing) = competing {
+        policy.count_competing = competing;
+    }
+    if let Some(ignore_bots) = ignore_bots {
+        policy.ignore_bots = ignore_bots;
+    }
+
+    db::repositories::guild::upsert(guild_id, db).await?;
+    db::repositories::naming_policy::save(policy.clone(), db).await?;
+    let aliases = db::repositories::activity_alias::list_by_guild(guild_id, db).await?;
+
+    ctx.say_ephemeral(describe_naming(&policy, &aliases)).await
+}
+
+/// Show an activity under a different name, e.g. "Counter-Strike 2" as "CS2".
+#[poise::command(slash_command, guild_only, check = "has_manage_channels")]
+pub async fn alias(
+    ctx: Context<'_>,
+    #[description = "Activity name as Discord shows it (case-insensitive)"]
+    #[max_length = 128]
+    activity: String,
+    #[description = "Name to use in channel names instead"]
+    #[max_length = 100]
+    name: String,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap().get() as i64;
+    db::repositories::guild::upsert(guild_id, &ctx.data().db).await?;
+    db::repositories::activity_alias::set(guild_id, &activity, Some(name.clone()), &ctx.data().db)
+        .await?;
+    ctx.say_ephemeral(format!("**{activity}** will be shown as **{name}**."))
+        .await
+}
+
+/// Never name channels after an activity, e.g. "Spotify".
+#[poise::command(slash_command, guild_only, check = "has_manage_channels")]
+pub async fn ignore(
+    ctx: Context<'_>,
+    #[description = "Activity name as Discord shows it (case-insensitive)"]
+    #[max_length = 128]
+    activity: String,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap().get() as i64;
+    db::repositories::guild::upsert(guild_id, &ctx.data().db).await?;
+    db::repositories::activity_alias::set(guild_id, &activity, None, &ctx.data().db).await?;
+    ctx.say_ephemeral(format!("**{activity}** will no longer name channels."))
+        .await
+}
+
+/// Remove an activity's alias or ignore entry.
+#[poise::command(slash_command, guild_only, check = "has_manage_channels")]
+pub async fn unalias(
+    ctx: Context<'_>,
+    #[description = "Activity name as Discord shows it (case-insensitive)"]
+    #[max_length = 128]
+    activity: String,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap().get() as i64;
+    let removed =
+        db::repositories::activity_alias::remove(guild_id, &activity, &ctx.data().db).await?;
+    let reply = if removed {
+        format!("**{activity}** is back to normal.")
+    } else {
+        format!("**{activity}** has no alias or ignore entry.")
+    };
+    ctx.say_ephemeral(reply).await
+}
+
+/// Configure the Allow/Deny prompts sent when someone asks to join a private channel.
+///
+/// Without options, shows the current settings.
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "join-requests",
+    check = "has_manage_channels"
+)]
+pub async fn join_requests(
+    ctx: Context<'_>,
+    #[description = "Seconds a prompt stays answerable (default 120)"]
+    #[min = 30_u32]
+    #[max = 86400_u32]
+    timeout: Option<u32>,
+    #[description = "Seconds before the same user can ask for the same channel again (default 60)"]
+    #[max = 86400_u32]
+    cooldown: Option<u32>,
+    #[description = "Denials after which a user's requests are refused outright; 0 disables (default 3)"]
+    #[max = 100_u32]
+    max_denials: Option<u32>,
+    #[description = "Minutes over which denials are counted (default 60)"]
+    #[min = 1_u32]
+    #[max = 10080_u32]
+    denial_window: Option<u32>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap().get() as i64;
+    let db = &ctx.data().db;
+    let mut policy = JoinRequestPolicy::load(guild_id, db).await;
+    if timeout.is_none() && cooldown.is_none() && max_denials.is_none() && denial_window.is_none() {
+        return ctx.say_ephemeral(describe_join_requests(&policy)).await;
+    }
+
+    if let Some(timeout) = timeout {
+        policy.timeout_secs = i64::from(timeout);
+    }
+    if let Some(cooldown) = cooldown {
+        policy.cooldown_secs = i64::from(cooldown);
+    }
+    if let Some(max_denials) = max_denials {
+        policy.max_denials = i64::from(max_denials);
+    }
+    if let Some(minutes) = denial_window {
+        policy.denial_window_secs = i64::from(minutes) * 60;
+    }
+    db::repositories::guild::upsert(guild_id, db).await?;
+    db::repositories::join_request_policy::save(
+        db::entities::join_request_policy::Model {
+            guild_id,
+            timeout_secs: policy.timeout_secs,
+            cooldown_secs: policy.cooldown_secs,
+            max_denials: policy.max_denials,
+            denial_window_secs: policy.denial_window_secs,
+        },
+        db,
+    )
+    .await?;
+    ctx.say_ephemeral(describe_join_requests(&policy)).await
+}
+
+/// Set how long an empty temp channel is kept before it's deleted.
+///
+/// Members who drop and rejoin within this time get their channel back as it was.
+/// Without options, shows the current setting.
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "empty-channels",
+    check = "has_manage_channels"
+)]
+pub async fn empty_channels(
+    ctx: Context<'_>,
+    #[description = "Seconds to keep an empty channel; 0 deletes it right away (default 30)"]
+    #[max = 3600_u32]
+    grace: Option<u32>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap().get() as i64;
+    let db = &ctx.data().db;
+    let secs = match grace {
+        Some(grace) => {
+            let secs = i64::from(grace);
+            db::repositories::guild::set_empty_grace_secs(guild_id, Some(secs), db).await?;
+            secs
+        }
+        None => grace::grace_secs(guild_id, db).await,
+    };
+    let reply = if secs == 0 {
+        "Empty channels are deleted as soon as the last member leaves.".to_string()
+    } else {
+        format!("Empty channels are kept for **{secs}** seconds in case someone rejoins.")
+    };
+    ctx.say_ephemeral(reply).await
+}
+
+/// Set how long this server's data is kept after the bot is removed from it.
+///
+/// Inviting the bot back within this time restores its triggers, settings and XP.
+/// Without options, shows the current setting.
+#[poise::command(slash_command, guild_only, check = "has_manage_channels")]
+pub async fn retention(
+    ctx: Context<'_>,
+    #[description = "Days to keep data after the bot is removed; 0 deletes it right away (default 30)"]
+    #[max = 365_u32]
+    days: Option<u32>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap().get() as i64;
+    let db = &ctx.data().db;
+    let days = match days {
+        Some(days) => {
+            let days = i64::from(days);
+            db::repositories::guild::set_retention_days(guild_id, Some(days), db).await?;
+            days
+        }
+        None => removal::retention_days(guild_id, db).await,
+    };
+    let reply = if days == 0 {
+        "This server's data is deleted as soon as the bot is removed.".to_string()
+    } else {
+        format!("This server's data is kept for **{days}** day(s) after the bot is removed.")
+    };
+    ctx.say_ephemeral(reply).await
+}
+
+/// Set how long finished voice sessions are kept for `/history`.
+///
+/// Without options, shows the current setting.
+#[poise::command(slash_command, guild_only, check = "has_manage_channels")]
+pub async fn history(
+    ctx: Context<'_>,
+    #[description = "Days to keep finished voice sessions (default 90)"]
+    #[min = 1_u32]
+    #[max = 365_u32]
+    days: Option<u32>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap().get() as i64;
+    let db = &ctx.data().db;
+    let days = match days {
+        Some(days) => {
+            let days = i64::from(days);
+            db::repositories::guild::set_history_days(guild_id, Some(days), db).await?;
+            days
+        }
+        None => removal::history_days(guild_id, db).await,
+    };
+    ctx.say_ephemeral(format!(
+        "Finished voice sessions are kept in `/history` for **{days}** day(s)."
+    ))
+    .await
+}
+
+/// Tune how members earn XP and level up in this server.
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands("xp_earning", "xp_daily", "xp_curve"),
+    check = "has_manage_channels"
+)]
+pub async fn xp(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Set how much XP voice time earns. Without options, shows the current XP settings.
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "earning",
+    check = "has_manage_channels"
+)]
+pub async fn xp_earning(
+    ctx: Context<'_>,
+    #[description = "XP per second of active voice time; 0 disables (default 1)"]
+    #[min = 0.0]
+    #[max = 100.0]
+    per_second: Option<f64>,
+    #[description = "Seconds a session must last to earn anything (default 60)"]
+    #[max = 3600_u32]
+    min_session: Option<u32>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap().get() as i64;
+    let mut policy = XpPolicy::load(guild_id, &ctx.data().db).await;
+    if per_second.is_none() && min_session.is_none() {
+        return ctx.say_ephemeral(describe_xp(&policy)).await;
+    }
+
+    if let Some(rate) = per_second {
+        policy.xp_per_second = rate;
+    }
+    if let Some(secs) = min_session {
+        policy.min_session_secs = i64::from(secs);
+    }
+    save_xp(ctx, guild_id, &policy).await
+}
+
+/// Set the daily bonus and its window. Without options, shows the current XP settings.
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "daily",
+    check = "has_manage_channels"
+)]
+pub async fn xp_daily(
+    ctx: Context<'_>,
+    #[description = "XP for the first join of the day (default 3600)"]
+    #[max = 100_000_u32]
+    bonus: Option<u32>,
+    #[description = "Hours after the last bonus before the next one can be earned (default 22)"]
+    #[min = 1_u32]
+    #[max = 24_u32]
+    opens: Option<u32>,
+    #[description = "Hours after the last bonus before the streak breaks (default 26)"]
+    #[min = 24_u32]
+    #[max = 48_u32]
+    closes: Option<u32>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap().get() as i64;
+    let mut policy = XpPolicy::load(guild_id, &ctx.data().db).await;
+    if bonus.is_none() && opens.is_none() && closes.is_none() {
+        return ctx.say_ephemeral(describe_xp(&policy)).await;
+    }
+
+    if let Some(bonus) = bonus {
+        policy.daily_bonus_xp = i64::from(bonus);
+    }
+    if let Some(hours) = opens {
+        policy.daily_early_secs = i64::from(hours) * 3600;
+    }
+    if let Some(hours) = closes {
+        policy.daily_late_secs = i64::from(hours) * 3600;
+    }
+    save_xp(ctx, guild_id, &policy).await
+}
+
+/// Set the level curve. Without options, shows the current XP settings.
+///
+/// Levels are worked out from total XP, so a new curve applies to everyone right away.
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "curve",
+    check = "has_manage_channels"
+)]
+pub async fn xp_curve(
+    ctx: Context<'_>,
+    #[description = "XP from level 1 to 2 (default 3600)"]
+    #[min = 60_u32]
+    #[max = 86_400_u32]
+    base: Option<u32>,
+    #[description = "How much more each level up to 100 costs than the one before (default 1.047)"]
+    #[min = 1.001]
+    #[max = 1.2]
+    growth: Option<f64>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap().get() as i64;
+    let mut policy = XpPolicy::load(guild_id, &ctx.data().db).await;
+    if base.is_none() && growth.is_none() {
+        return ctx.say_ephemeral(describe_xp(&policy)).await;
+    }
+
+    if let Some(base) = base {
+        policy.curve.base = f64::from(base);
+    }
+    if let Some(growth) = growth {
+        policy.curve.growth = growth;
+    }
+    save_xp(ctx, guild_id, &policy).await
+}
+
+/// Saves a policy changed by one of the `/config xp` commands, unless it's invalid.
+async fn save_xp(ctx: Context<'_>, guild_id: i64, policy: &XpPolicy) -> Result<(), Error> {
+    if let Err(problem) = policy.validate() {
+        return ctx.say_ephemeral(problem).await;
+    }
+    let db = &ctx.data().db;
+    db::repositories::guild::upsert(guild_id, db).await?;
+    db::repositories::xp_policy::save(
+        db::entities::xp_policy::Model {
+            guild_id,
+            xp_per_second: policy.xp_per_second,
+            min_session_secs: policy.min_session_secs,
+            daily_bonus_xp: policy.daily_bonus_xp,
+            daily_early_secs: policy.daily_early_secs,
+            daily_late_secs: policy.daily_late_secs,
+            level_base: policy.curve.base as i64,
+            level_growth: policy.curve.growth,
+        },
+        db,
+    )
+    .await?;
+    ctx.say_ephemeral(describe_xp(policy)).await
+}
+
+#[derive(Copy, Clone, Debug, poise::ChoiceParameter)]
+pub enum WhenFull {
+    #[name = "Move into an existing channel"]
+    Move,
+    #[name = "Turn away"]
+    Refuse,
+}
+
+/// Cap how many temp channels the server and each member can have at once.
+///
+/// Per-trigger caps are set with `/trigger edit`. Without options, shows the current settings.
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "channel-limits",
+    check = "has_manage_channels"
+)]
+pub async fn channel_limits(
+    ctx: Context<'_>,
+    #[description = "Most temp channels in this server at once (0 = unlimited)"]
+    #[max = 500_u32]
+    per_server: Option<u32>,
+    #[description = "Most temp channels one member can have created at once (0 = unlimited)"]
+    #[max = 50_u32]
+    per_member: Option<u32>,
+    #[description = "What happens to a member who hits a cap (default: move into an existing channel)"]
+    when_full: Option<WhenFull>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap().get() as i64;
+    let db = &ctx.data().db;
+    let mut policy = LimitPolicy::load(guild_id, db).await;
+    if per_server.is_none() && per_member.is_none() && when_full.is_none() {
+        return ctx.say_ephemeral(describe_limits(&policy)).await;
+    }
+
+    if let Some(max) = per_server {
+        policy.max_per_guild = (max > 0).then_some(i64::from(max));
+    }
+    if let Some(max) = per_member {
+        policy.max_per_user = (max > 0).then_some(i64::from(max));
+    }
+    if let Some(when_full) = when_full {
+        policy.move_when_full = matches!(when_full, WhenFull::Move);
+    }
+    db::repositories::guild::upsert(guild_id, db).await?;
+    db::repositories::channel_limit_policy::save(
+        db::entities::channel_limit_policy::Model {
+            guild_id,
+            max_per_guild: policy.max_per_guild,
+            max_per_user: policy.max_per_user,
+            move_when_full: policy.move_when_full,
+        },
+        db,
+    )
+    .await?;
+    ctx.say_ephemeral(describe_limits(&policy)).await
+}
+
+/// Limit how fast one member can create temp channels by hopping in and out of triggers.
+///
+/// Without options, shows the current settings.
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "join-rate",
+    check = "has_manage_channels"
+)]
+pub async fn join_rate(
+    ctx: Context<'_>,
+    #[description = "Channels a member can create back to back; 0 disables the limit (default 3)"]
+    #[max = 50_u32]
+    burst: Option<u32>,
+    #[description = "Seconds to earn back one more channel (default 60)"]
+    #[min = 1_u32]
+    #[max = 3600_u32]
+    refill: Option<u32>,
+    #[description = "Minutes a member who runs out can't create channels; 0 only refuses (default 5)"]
+    #[max = 1440_u32]
+    ban: Option<u32>,
+    #[description = "Lift this member's ban early"] pardon: Option<serenity::User>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap().get() as i64;
+    let db = &ctx.data().db;
+    let mut policy = JoinRatePolicy::load(guild_id, db).await;
+
+    if let Some(user) = pardon {
+        let lifted =
+            db::repositories::trigger_ban::lift(guild_id, user.id.get() as i64, db).await?;
+        // Otherwise their still-empty bucket bans them again on the next join.
+        ctx.data().creations.reset(ctx.guild_id().unwrap(), user.id);
+        if burst.is_none() && refill.is_none() && ban.is_none() {
+            let reply = if lifted {
+                format!("<@{}> can create channels again.", user.id)
+            } else {
+                format!("<@{}> wasn't banned from creating channels.", user.id)
+            };
+            return ctx.say_ephemeral(reply).await;
+        }
+    }
+    if burst.is_none() && refill.is_none() && ban.is_none() {
+        return ctx.say_ephemeral(describe_join_rate(&policy)).await;
+    }
+
+    if let Some(burst) = burst {
+        policy.burst = i64::from(burst);
+    }
+    if let Some(refill) = refill {
+        policy.refill_secs = i64::from(refill);
+    }
+    if let Some(minutes) = ban {
+        policy.ban_secs = i64::from(minutes) * 60;
+    }
+    db::repositories::guild::upsert(guild_id, db).await?;
+    db::repositories::join_rate_policy::save(
+        db::entities::join_rate_policy::Model {
+            guild_id,
+            burst: policy.burst,
+            refill_secs: policy.refill_secs,
+            ban_secs: policy.ban_secs,
+        },
+        db,
+    )
+    .await?;
+    ctx.say_ephemeral(describe_join_rate(&policy)).await
+}
+
+#[derive(Copy, Clone, Debug, poise::ChoiceParameter)]
+pub enum IdleAction {
+    #[name = "Move to the AFK channel"]
+    MoveToAfk,
+    #[name = "Disconnect"]
+    Disconnect,
+}
+
+/// Configure how channels where everyone is deafened or away get cleared out.
+///
+/// Without options, shows the current settings.
+#[poise::command(slash_command, guild_only, check = "has_manage_channels")]
+pub async fn idle(
+    ctx: Context<'_>,
+    #[description = "Minutes everyone must be idle before a warning; 0 disables (default 30)"]
+    #[max = 1440_u32]
+    minutes: Option<u32>,
+    #[description = "Minutes between the warning and clearing the channel (default 2)"]
+    #[min = 1_u32]
+    #[max = 60_u32]
+    warning: Option<u32>,
+    #[description = "What happens to idle members (default: move to the AFK channel)"]
+    action: Option<IdleAction>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap().get() as i64;
+    let db = &ctx.data().db;
+    let mut policy = IdlePolicy::load(guild_id, db).await;
+    if minutes.is_none() && warning.is_none() && action.is_none() {
+        return ctx.say_ephemeral(describe_idle(&policy)).await;
+    }
+
+    if let Some(minutes) = minutes {
+        policy.threshold_secs = i64::from(minutes) * 60;
+    }
+    if let Some(warning) = warning {
+        policy.warning_secs = i64::from(warning) * 60;
+    }
+    if let Some(action) = action {
+        policy.disconnect = matches!(action, IdleAction::Disconnect);
+    }
+    db::repositories::guild::upsert(guild_id, db).await?;
+    db::repositories::idle_policy::save(
+        db::entities::idle_policy::Model {
+            guild_id,
+            threshold_secs: policy.threshold_secs,
+            warning_secs: policy.warning_secs,
+            disconnect: policy.disconnect,
+        },
+        db,
+    )
+    .await?;
+    ctx.say_ephemeral(describe_idle(&policy)).await
+}
+
 /// Sends an ephemeral "please select a voice channel" reply and returns `false` if
 /// `channel` is not a voice channel, `true` otherwise.
 async fn require_voice_channel(
@@ -220,6 +989,171 @@ async fn require_voice_channel(
     Ok(true)
 }
 
+/// Sends an ephemeral explanation and returns `false` if `template` is not a usable
+/// channel-name template, `true` otherwise.
+async fn require_valid_template(ctx: Context<'_>, template: &str) -> Result<bool, Error> {
+    if let Err(e) = crate::template::Template::parse(template) {
+        ctx.say_ephemeral(format!("Invalid template: {e}.")).await?;
+        return Ok(false);
+    }
+    Ok(true)
+}
+
+/// One-line summary of a trigger's overrides, or `None` if it has none.
+fn describe_settings(trigger: &db::entities::primary_channel::Model) -> Option<String> {
+    let mut parts = Vec::new();
+    if let Some(template) = &trigger.name_template {
+        parts.push(format!("name `{template}`"));
+    }
+    if let Some(limit) = trigger.user_limit {
+        parts.push(format!("limit {limit}"));
+    }
+    if let Some(bitrate) = trigger.bitrate {
+        parts.push(format!("{} kbps", bitrate / 1000));
+    }
+    if let Some(category) = trigger.category_id {
+        parts.push(format!("in <#{}>", category as u64));
+    }
+    if trigger.private {
+        parts.push("private".to_string());
+    }
+    if let Some(region) = &trigger.rtc_region {
+        parts.push(format!("region {region}"));
+    }
+    if trigger.video_quality_mode == Some(2) {
+        parts.push("full video quality".to_string());
+    }
+    if let Some(max) = trigger.max_channels {
+        parts.push(format!("at most {max} channels"));
+    }
+    if trigger.fill_existing {
+        parts.push("fills existing channels first".to_string());
+    }
+    (!parts.is_empty()).then(|| parts.join(", "))
+}
+
+fn describe_join_rate(policy: &JoinRatePolicy) -> String {
+    if policy.burst == 0 {
+        return "Members can create channels as fast as they like.".to_string();
+    }
+    let penalty = if policy.ban_secs == 0 {
+        "Further attempts are refused until they earn one back.".to_string()
+    } else {
+        format!(
+            "Running out bars them from creating channels for **{}** minutes.",
+            policy.ban_secs / 60
+        )
+    };
+    format!(
+        "Members can create **{}** channels back to back and earn one more every **{}** seconds. {penalty}",
+        policy.burst, policy.refill_secs
+    )
+}
+
+fn describe_limits(policy: &LimitPolicy) -> String {
+    let cap = |max: Option<i64>| max.map_or("no limit".to_string(), |max| format!("**{max}**"));
+    let when_full = if policy.move_when_full {
+        "moved into an existing channel from the same trigger when one has room, otherwise turned away"
+    } else {
+        "turned away"
+    };
+    format!(
+        "Temp channels: {} per server, {} per member. Members who hit a cap are {when_full}.",
+        cap(policy.max_per_guild),
+        cap(policy.max_per_user)
+    )
+}
+
+fn describe_xp(policy: &XpPolicy) -> String {
+    format!(
+        "Members earn **{}** XP per second in voice, for sessions of at least **{}** seconds. \
+         Joining a temp channel **{}–{}** hours after their last daily bonus earns **{}** XP \
+         and keeps their streak. Level 2 costs **{}** XP, and each level up to 100 costs \
+         **{}×** the one before.",
+        policy.xp_per_second,
+        policy.min_session_secs,
+        policy.daily_early_secs / 3600,
+        policy.daily_late_secs / 3600,
+        policy.daily_bonus_xp,
+        policy.curve.base,
+        policy.curve.growth
+    )
+}
+
+fn describe_idle(policy: &IdlePolicy) -> String {
+    if policy.threshold_secs == 0 {
+        return "Idle channels are never cleared. Idle time still earns no XP.".to_string();
+    }
+    let action = if policy.disconnect {
+        "disconnected"
+    } else {
+        "moved to the AFK channel (or disconnected if there is none)"
+    };
+    format!(
+        "When everyone in a channel has been deafened or away for **{}** minutes, they're warned, \
+         then {action} **{}** minute(s) later unless someone comes back.",
+        policy.threshold_secs / 60,
+        policy.warning_secs / 60
+    )
+}
+
+fn describe_join_requests(policy: &JoinRequestPolicy) -> String {
+    let auto_deny = if policy.max_denials == 0 {
+        "Repeatedly denied users are never refused automatically.".to_string()
+    } else {
+        format!(
+            "Users denied **{}** times within **{}** minutes are refused without a prompt.",
+            policy.max_denials,
+            policy.denial_window_secs / 60
+        )
+    };
+    format!(
+        "Join requests expire after **{}** seconds, and the same user can ask again after **{}** seconds. {auto_deny}",
+        policy.timeout_secs, policy.cooldown_secs
+    )
+}
+
+/// Multi-line summary of a guild's naming policy and alias list.
+fn describe_naming(
+    policy: &db::entities::naming_policy::Model,
+    aliases: &[db::entities::activity_alias::Model],
+) -> String {
+    let mode = if policy.plurality {
+        "the most common activity".to_string()
+    } else {
+        format!(
+            "an activity shared by at least {}% of members",
+            policy.threshold_percent
+        )
+    };
+    let mut kinds = vec!["games"];
+    if policy.count_streaming {
+        kinds.push("streams");
+    }
+    if policy.count_listening {
+        kinds.push("listening");
+    }
+    if policy.count_competing {
+        kinds.push("competing");
+    }
+    let mut lines = vec![format!(
+        "Channels are named after {mode}. Counting: {}{}.",
+        kinds.join(", "),
+        if policy.ignore_bots {
+            " (bots ignored)"
+        } else {
+            ""
+        }
+    )];
+    for alias in aliases {
+        lines.push(match &alias.alias {
+            Some(name) => format!("• {} → **{name}**", alias.activity),
+            None => format!("• {} — ignored", alias.activity),
+        });
+    }
+    lines.join("\n")
+}
+
 async fn is_owner(ctx: Context<'_>) -> Result<bool, Error> {
     Ok(ctx.data().owner_id == Some(ctx.author().id))
 }
@@ -0,0 +1,81 @@
+use poise::serenity_prelude::{self as serenity, CreateEmbed, CreateEmbedFooter};
+
+use super::pagination;
+use crate::{context_ext::ContextExt, leveling, Context, Error};
+
+const PAGE_SIZE: u64 = 10;
+
+/// Show your past voice sessions, or another user's.
+#[poise::command(slash_command, guild_only)]
+pub async fn history(
+    ctx: Context<'_>,
+    #[description = "User to view (default: yourself)"] user: Option<serenity::User>,
+) -> Result<(), Error> {
+    let target = user.as_ref().unwrap_or_else(|| ctx.author());
+    let gid = ctx.guild_id().unwrap().get() as i64;
+    let uid = target.id.get() as i64;
+    let db = &ctx.data().db;
+
+    let total = db::repositories::voice_session_history::count_by_member(gid, uid, db).await?;
+    if total == 0 {
+        ctx.say_ephemeral(format!(
+            "No finished voice sessions for <@{}> yet.",
+            target.id
+        ))
+        .await?;
+        return Ok(());
+    }
+
+    let total_pages = total.div_ceil(PAGE_SIZE) as usize;
+    pagination::paginate(ctx, total_pages, |page| {
+        Box::pin(async move {
+            let sessions = db::repositories::voice_session_history::list_by_member(
+                gid,
+                uid,
+                page as u64,
+                PAGE_SIZE,
+                db,
+            )
+            .await?;
+            Ok(build_embed(target, &sessions, page, total_pages, total))
+        })
+    })
+    .await
+}
+
+fn build_embed(
+    target: &serenity::User,
+    sessions: &[db::entities::voice_session_history::Model],
+    page: usize,
+    total_pages: usize,
+    total: u64,
+) -> CreateEmbed {
+    let description = sessions
+        .iter()
+        .map(|s| {
+            let mut line = format!(
+                "<t:{}:f> · **{}**",
+                s.started_at,
+                leveling::format_duration(s.duration_secs)
+            );
+            if let Some(game) = &s.game {
+                line.push_str(&format!(" · {game}"));
+            }
+            if let Some(trigger) = s.primary_channel_id {
+                line.push_str(&format!(" · via <#{}>", trigger as u64));
+            }
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    CreateEmbed::new()
+        .title(format!("🕘 Voice history of {}", target.name))
+        .description(description)
+        .colour(0x5865F2u32)
+        .footer(CreateEmbedFooter::new(format!(
+            "Page {}/{} · {total} session(s)",
+            page + 1,
+            total_pages
+        )))
+}
@@ -1,4 +1,6 @@
 pub mod admin;
+pub mod history;
+mod pagination;
 pub mod profile;
 pub mod ranking;
 pub mod stats;
@@ -0,0 +1,103 @@
+use std::time::Duration;
+
+use poise::serenity_prelude::{self as serenity, CreateEmbed};
+use serenity::futures::{future::BoxFuture, StreamExt as _};
+
+use crate::{Context, Error};
+
+/// How long the buttons keep working after the last reply.
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Sends page 0 of `total_pages` with ◀/▶ buttons when there's more than one, and
+/// re-renders on clicks until the collector times out, then disables the buttons.
+/// `render` builds the embed for a 0-based page, so pages can be loaded on demand.
+pub async fn paginate<'a>(
+    ctx: Context<'_>,
+    total_pages: usize,
+    render: impl Fn(usize) -> BoxFuture<'a, Result<CreateEmbed, Error>>,
+) -> Result<(), Error> {
+    let mut page = 0usize;
+
+    let components = if total_pages > 1 {
+        page_buttons(page, total_pages)
+    } else {
+        vec![]
+    };
+
+    let reply = ctx
+        .send(
+            poise::CreateReply::default()
+                .embed(render(page).await?)
+                .components(components),
+        )
+        .await?;
+
+    if total_pages <= 1 {
+        return Ok(());
+    }
+
+    let msg = reply.message().await?;
+    let msg_id = msg.id;
+
+    let mut stream =
+        serenity::collector::ComponentInteractionCollector::new(ctx.serenity_context())
+            .filter(move |i| i.message.id == msg_id)
+            .timeout(TIMEOUT)
+            .stream();
+
+    while let Some(interaction) = stream.next().await {
+        match interaction.data.custom_id.as_str() {
+            "page_prev" => page = page.saturating_sub(1),
+            "page_next" => page = (page + 1).min(total_pages - 1),
+            _ => continue,
+        }
+        let _ = interaction
+            .create_response(
+                ctx.serenity_context(),
+                serenity::builder::CreateInteractionResponse::UpdateMessage(
+                    serenity::builder::CreateInteractionResponseMessage::new()
+                        .embed(render(page).await?)
+                        .components(page_buttons(page, total_pages)),
+                ),
+            )
+            .await;
+    }
+
+    // Disable buttons after the collector times out.
+    let _ = reply
+        .edit(
+            ctx,
+            poise::CreateReply::default()
+                .embed(render(page).await?)
+                .components(page_buttons_disabled()),
+        )
+        .await;
+
+    Ok(())
+}
+
+fn page_buttons(page: usize, total_pages: usize) -> Vec<serenity::builder::CreateActionRow> {
+    vec![serenity::builder::CreateActionRow::Buttons(vec![
+        serenity::builder::CreateButton::new("page_prev")
+            .label("◀")
+            .style(serenity::ButtonStyle::Secondary)
+            .disabled(page == 0),
+        serenity::builder::CreateButton::new("page_next")
+            .label("▶")
+            .style(serenity::ButtonStyle::Secondary)
+            .disabled(page + 1 >= total_pages),
+    ])]
+}
+
+fn page_buttons_disabled() -> Vec<serenity::builder::CreateActionRow> {
+    vec![serenity::builder::CreateActionRow::Buttons(vec![
+        serenity::builder::CreateButton::new("page_prev")
+            .label("◀")
+            .style(serenity::ButtonStyle::Secondary)
+            .disabled(true),
+        serenity::builder::CreateButton::new("page_next")
+            .label("▶")
+            .style(serenity::ButtonStyle::Secondary)
+            .disabled(true),
+    ])]
+}
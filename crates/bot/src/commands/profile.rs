@@ -15,18 +15,19 @@ pub async fn profile(
     let gid = guild_id.get() as i64;
 
     let profile = db::repositories::user_profile::get(uid, gid, &ctx.data().db).await?;
+    let policy = crate::events::xp::Policy::load(gid, &ctx.data().db).await;
     let now = crate::time::now_unix();
 
     let xp = profile.as_ref().map(|p| p.xp).unwrap_or(0);
     let total_seconds = profile.as_ref().map(|p| p.total_voice_seconds).unwrap_or(0);
     let streak = profile.as_ref().map_or(0, |p| match p.last_daily_at {
-        Some(t) if now - t <= crate::events::xp::DAILY_LATE_SECS => p.streak,
+        Some(t) if now - t <= policy.daily_late_secs => p.streak,
         _ => 0,
     });
 
-    let level = leveling::level_from_xp(xp);
-    let xp_in_level = leveling::xp_in_level(xp);
-    let xp_to_next = leveling::xp_to_next_level(xp);
+    let level = leveling::level_from_xp(xp, &policy.curve);
+    let xp_in_level = leveling::xp_in_level(xp, &policy.curve);
+    let xp_to_next = leveling::xp_to_next_level(xp, &policy.curve);
 
     let member = guild_id
         .member(ctx.serenity_context(), target.id)
@@ -1,97 +1,161 @@
-use std::time::Duration;
+use poise::serenity_prelude::{CreateEmbed, CreateEmbedFooter};
 
-use poise::serenity_prelude::{self as serenity, CreateEmbed, CreateEmbedFooter};
-use serenity::futures::StreamExt as _;
+use db::repositories::leaderboard::{self, Metric};
 
+use super::pagination;
 use crate::{context_ext::ContextExt, leveling, Context, Error};
 
-const PAGE_SIZE: usize = 10;
-
-/// Show the top voice XP earners in this server.
-#[poise::command(slash_command, guild_only)]
-pub async fn ranking(ctx: Context<'_>) -> Result<(), Error> {
-    let guild_id = ctx.guild_id().unwrap();
-    let gid = guild_id.get() as i64;
-
-    let profiles = db::repositories::user_profile::list_top_by_guild(gid, &ctx.data().db).await?;
+const PAGE_SIZE: u64 = 10;
+const DAY_SECS: i64 = 24 * 60 * 60;
+
+#[derive(Copy, Clone, Debug, Default, poise::ChoiceParameter)]
+pub enum Period {
+    #[name = "Today"]
+    Day,
+    #[name = "Last 7 days"]
+    Week,
+    #[name = "Last 30 days"]
+    Month,
+    #[default]
+    #[name = "All time"]
+    AllTime,
+}
 
-    if profiles.is_empty() {
-        ctx.say_ephemeral("No one has earned XP yet. Join a voice channel to get started!")
-            .await?;
-        return Ok(());
+impl Period {
+    /// Start of the window: midnight UTC of its first day, so it lines up with the
+    /// per-day totals it's summed from. `None` for all time.
+    fn since(self, now: i64) -> Option<i64> {
+        let days = match self {
+            Period::Day => 1,
+            Period::Week => 7,
+            Period::Month => 30,
+            Period::AllTime => return None,
+        };
+        Some((now.div_euclid(DAY_SECS) - (days - 1)) * DAY_SECS)
     }
 
-    let total_pages = profiles.len().div_ceil(PAGE_SIZE);
-    let mut page = 0usize;
-
-    let components = if total_pages > 1 {
-        page_buttons(page, total_pages)
-    } else {
-        vec![]
-    };
+    fn label(self) -> &'static str {
+        match self {
+            Period::Day => "today",
+            Period::Week => "last 7 days",
+            Period::Month => "last 30 days",
+            Period::AllTime => "all time",
+        }
+    }
+}
 
-    let reply = ctx
-        .send(
-            poise::CreateReply::default()
-                .embed(build_embed(&profiles, page, total_pages))
-                .components(components),
-        )
-        .await?;
+#[derive(Copy, Clone, Debug, Default, poise::ChoiceParameter)]
+pub enum RankBy {
+    #[default]
+    #[name = "XP"]
+    Xp,
+    #[name = "Voice time"]
+    VoiceTime,
+    #[name = "Streak"]
+    Streak,
+    #[name = "Longest session"]
+    LongestSession,
+}
 
-    if total_pages <= 1 {
-        return Ok(());
+impl RankBy {
+    fn metric(self) -> Metric {
+        match self {
+            RankBy::Xp => Metric::Xp,
+            RankBy::VoiceTime => Metric::VoiceSeconds,
+            RankBy::Streak => Metric::Streak,
+            RankBy::LongestSession => Metric::LongestSession,
+        }
     }
 
-    let msg = reply.message().await?;
-    let msg_id = msg.id;
+    fn title(self) -> &'static str {
+        match self {
+            RankBy::Xp => "🏆 Voice Rankings",
+            RankBy::VoiceTime => "🎙️ Voice Time Rankings",
+            RankBy::Streak => "🔥 Streak Rankings",
+            RankBy::LongestSession => "⏱️ Longest Session Rankings",
+        }
+    }
 
-    let mut stream =
-        serenity::collector::ComponentInteractionCollector::new(ctx.serenity_context())
-            .filter(move |i| i.message.id == msg_id)
-            .timeout(Duration::from_secs(60))
-            .stream();
+    fn format(self, value: i64, all_time: bool, curve: &leveling::Curve) -> String {
+        match self {
+            RankBy::Xp if all_time => {
+                format!("Lv.{} · {value} XP", leveling::level_from_xp(value, curve))
+            }
+            RankBy::Xp => format!("+{value} XP"),
+            RankBy::VoiceTime | RankBy::LongestSession => leveling::format_duration(value),
+            RankBy::Streak => format!("🔥 {value}"),
+        }
+    }
+}
 
-    while let Some(interaction) = stream.next().await {
-        match interaction.data.custom_id.as_str() {
-            "rank_prev" => page = page.saturating_sub(1),
-            "rank_next" => page = (page + 1).min(total_pages - 1),
-            _ => continue,
+/// Show the top members in this server by XP, voice time, streak or longest session.
+#[poise::command(slash_command, guild_only)]
+pub async fn ranking(
+    ctx: Context<'_>,
+    #[description = "Time window (default: all time)"] period: Option<Period>,
+    #[description = "What to rank by (default: XP)"] metric: Option<RankBy>,
+) -> Result<(), Error> {
+    let gid = ctx.guild_id().unwrap().get() as i64;
+    let db = &ctx.data().db;
+    let metric = metric.unwrap_or_default();
+    let now = crate::time::now_unix();
+    let policy = crate::events::xp::Policy::load(gid, db).await;
+    // Streaks are always current; instead of a window, the cutoff drops broken ones.
+    let (period, since) = match metric {
+        RankBy::Streak => (Period::AllTime, Some(now - policy.daily_late_secs)),
+        _ => {
+            let period = period.unwrap_or_default();
+            (period, period.since(now))
         }
-        let _ = interaction
-            .create_response(
-                ctx.serenity_context(),
-                serenity::builder::CreateInteractionResponse::UpdateMessage(
-                    serenity::builder::CreateInteractionResponseMessage::new()
-                        .embed(build_embed(&profiles, page, total_pages))
-                        .components(page_buttons(page, total_pages)),
-                ),
-            )
-            .await;
+    };
+
+    let total = leaderboard::count(gid, metric.metric(), since, db).await?;
+    if total == 0 {
+        let reply = match period {
+            Period::AllTime => {
+                "No one has earned XP yet. Join a voice channel to get started!".to_string()
+            }
+            _ => format!("No one has been ranked for the {} yet.", period.label()),
+        };
+        ctx.say_ephemeral(reply).await?;
+        return Ok(());
     }
 
-    // Disable buttons after the collector times out.
-    let _ = reply
-        .edit(
-            ctx,
-            poise::CreateReply::default()
-                .embed(build_embed(&profiles, page, total_pages))
-                .components(page_buttons_disabled()),
-        )
-        .await;
-
-    Ok(())
+    let total_pages = total.div_ceil(PAGE_SIZE) as usize;
+    let curve = policy.curve;
+    pagination::paginate(ctx, total_pages, |page| {
+        Box::pin(async move {
+            let entries =
+                leaderboard::page(gid, metric.metric(), since, page as u64, PAGE_SIZE, db).await?;
+            Ok(build_embed(
+                &entries,
+                metric,
+                period,
+                &curve,
+                page,
+                total_pages,
+                total,
+            ))
+        })
+    })
+    .await
 }
 
 fn build_embed(
-    profiles: &[db::entities::user_profile::Model],
+    entries: &[leaderboard::Entry],
+    metric: RankBy,
+    period: Period,
+    curve: &leveling::Curve,
     page: usize,
     total_pages: usize,
+    total: u64,
 ) -> CreateEmbed {
-    let start = page * PAGE_SIZE;
-    let description = profiles[start..(start + PAGE_SIZE).min(profiles.len())]
+    let start = page * PAGE_SIZE as usize;
+    let all_time = matches!(period, Period::AllTime);
+    let description = entries
         .iter()
         .enumerate()
-        .map(|(i, p)| {
+        .map(|(i, e)| {
             let rank = start + i + 1;
             let medal = match rank {
                 1 => "🥇",
@@ -99,54 +163,39 @@ fn build_embed(
                 3 => "🥉",
                 _ => "  ",
             };
-            let level = leveling::level_from_xp(p.xp);
-            let time = if p.total_voice_seconds == 0 {
-                "—".to_string()
-            } else {
-                leveling::format_duration(p.total_voice_seconds)
-            };
             format!(
-                "{medal} **#{rank}** <@{}> — Lv.{level} · {time}",
-                p.user_id as u64
+                "{medal} **#{rank}** <@{}> — {}",
+                e.user_id as u64,
+                metric.format(e.value, all_time, curve)
             )
         })
         .collect::<Vec<_>>()
         .join("\n");
 
+    let window = match metric {
+        RankBy::Streak => "current streaks",
+        _ => period.label(),
+    };
     CreateEmbed::new()
-        .title("🏆 Voice Rankings")
+        .title(metric.title())
         .description(description)
         .colour(0xFFD700u32)
         .footer(CreateEmbedFooter::new(format!(
-            "Page {}/{} · {} members ranked",
+            "Page {}/{} · {total} members ranked · {window}",
             page + 1,
-            total_pages,
-            profiles.len()
+            total_pages
         )))
 }
 
-fn page_buttons(page: usize, total_pages: usize) -> Vec<serenity::builder::CreateActionRow> {
-    vec![serenity::builder::CreateActionRow::Buttons(vec![
-        serenity::builder::CreateButton::new("rank_prev")
-            .label("◀")
-            .style(serenity::ButtonStyle::Secondary)
-            .disabled(page == 0),
-        serenity::builder::CreateButton::new("rank_next")
-            .label("▶")
-            .style(serenity::ButtonStyle::Secondary)
-            .disabled(page + 1 >= total_pages),
-    ])]
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-fn page_buttons_disabled() -> Vec<serenity::builder::CreateActionRow> {
-    vec![serenity::builder::CreateActionRow::Buttons(vec![
-        serenity::builder::CreateButton::new("rank_prev")
-            .label("◀")
-            .style(serenity::ButtonStyle::Secondary)
-            .disabled(true),
-        serenity::builder::CreateButton::new("rank_next")
-            .label("▶")
-            .style(serenity::ButtonStyle::Secondary)
-            .disabled(true),
-    ])]
+    #[test]
+    fn windows_start_at_midnight_of_their_first_day() {
+        let now = 10 * DAY_SECS + 3_600;
+        assert_eq!(Period::Day.since(now), Some(10 * DAY_SECS));
+        assert_eq!(Period::Week.since(now), Some(4 * DAY_SECS));
+        assert_eq!(Period::AllTime.since(now), None);
+    }
 }
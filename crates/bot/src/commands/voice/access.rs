@@ -0,0 +1,297 @@
+use poise::serenity_prelude::{self as serenity, Permissions};
+
+use crate::{
+    permissions::PermissionResultExt,
+    privacy::{access_overwrite, set_member_allow, set_member_deny, AccessTarget},
+    Context, Error,
+};
+
+/// Let a user or role join your channel, even while it's private.
+#[poise::command(slash_command, guild_only)]
+pub async fn permit(
+    ctx: Context<'_>,
+    #[description = "User to permit"] user: Option<serenity::User>,
+    #[description = "Role to permit"] role: Option<serenity::Role>,
+) -> Result<(), Error> {
+    let Some(channel_id) = super::require_owned_channel(ctx).await? else {
+        return Ok(());
+    };
+    let Some(target) = require_target(ctx, user, role).await? else {
+        return Ok(());
+    };
+
+    set_access(ctx, channel_id, target, true).await?;
+    ctx.say(format!(
+        "{} can now join your channels, even while they're private.",
+        target.mention()
+    ))
+    .await?;
+    Ok(())
+}
+
+/// Keep a user or role out of your channel, disconnecting them if they're inside.
+#[poise::command(slash_command, guild_only)]
+pub async fn reject(
+    ctx: Context<'_>,
+    #[description = "User to reject"] user: Option<serenity::User>,
+    #[description = "Role to reject"] role: Option<serenity::Role>,
+) -> Result<(), Error> {
+    let Some(channel_id) = super::require_owned_channel(ctx).await? else {
+        return Ok(());
+    };
+    let Some(target) = require_target(ctx, user, role).await? else {
+        return Ok(());
+    };
+    let bot_id = ctx.serenity_context().cache.current_user().id;
+    if target == AccessTarget::User(ctx.author().id) || target == AccessTarget::User(bot_id) {
+        ctx.say("You can't reject yourself or the bot.").await?;
+        return Ok(());
+    }
+
+    set_access(ctx, channel_id, target, false).await?;
+
+    // Members the owner permitted by name keep their access: Discord applies member
+    // overwrites over role ones, so they could simply rejoin.
+    let guild_id = ctx.guild_id().unwrap();
+    let permitted: Vec<serenity::UserId> = db::repositories::access_rule::list(
+        ctx.author().id.get() as i64,
+        guild_id.get() as i64,
+        &ctx.data().db,
+    )
+    .await?
+    .iter()
+    .filter(|rule| rule.allowed)
+    .filter_map(|rule| match AccessTarget::from_rule(rule) {
+        AccessTarget::User(id) => Some(id),
+        AccessTarget::Role(_) => None,
+    })
+    .collect();
+    let to_disconnect: Vec<serenity::UserId> = ctx
+        .guild()
+        .map(|g| {
+            g.voice_states
+                .values()
+                .filter(|vs| vs.channel_id == Some(channel_id))
+                .map(|vs| vs.user_id)
+                .filter(|&id| id != ctx.author().id && !permitted.contains(&id))
+                .filter(|id| match target {
+                    AccessTarget::User(user_id) => *id == user_id,
+                    AccessTarget::Role(role_id) => g
+                        .members
+                        .get(id)
+                        .is_some_and(|m| m.roles.contains(&role_id)),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    for user_id in &to_disconnect {
+        guild_id
+            .disconnect_member(ctx, *user_id)
+            .await
+            .requires(&[Permissions::MOVE_MEMBERS])?;
+    }
+
+    let kicked = match to_disconnect.len() {
+        0 => String::new(),
+        1 => " They were disconnected.".to_string(),
+        n => format!(" {n} members were disconnected."),
+    };
+    ctx.say(format!(
+        "{} can no longer join your channels.{kicked}",
+        target.mention()
+    ))
+    .await?;
+    Ok(())
+}
+
+/// Remove a user or role from your permit and reject lists.
+///
+/// If you're in a channel you own, their entry is also lifted there.
+#[poise::command(slash_command, guild_only)]
+pub async fn unlist(
+    ctx: Context<'_>,
+    #[description = "User to remove"] user: Option<serenity::User>,
+    #[description = "Role to remove"] role: Option<serenity::Role>,
+) -> Result<(), Error> {
+    let Some(target) = require_target(ctx, user, role).await? else {
+        return Ok(());
+    };
+    let target_id = match target {
+        AccessTarget::User(id) => id.get(),
+        AccessTarget::Role(id) => id.get(),
+    };
+    let Some(rule) = db::repositories::access_rule::remove(
+        ctx.author().id.get() as i64,
+        ctx.guild_id().unwrap().get() as i64,
+        target_id as i64,
+        &ctx.data().db,
+    )
+    .await?
+    else {
+        ctx.say(format!(
+            "{} isn't on your permit or reject list.",
+            target.mention()
+        ))
+        .await?;
+        return Ok(());
+    };
+
+    if let Some(channel_id) = owned_channel(ctx).await? {
+        lift_access(ctx, channel_id, target, rule.allowed).await?;
+    }
+    let list = if rule.allowed { "permit" } else { "reject" };
+    ctx.say(format!(
+        "{} was removed from your {list} list.",
+        target.mention()
+    ))
+    .await?;
+    Ok(())
+}
+
+/// The temp channel the caller is in, if they own it. Unlike
+/// [`super::require_owned_channel`], says nothing when they don't.
+async fn owned_channel(ctx: Context<'_>) -> Result<Option<serenity::ChannelId>, Error> {
+    let Some(channel_id) = ctx.guild().and_then(|g| {
+        g.voice_states
+            .get(&ctx.author().id)
+            .and_then(|vs| vs.channel_id)
+    }) else {
+        return Ok(None);
+    };
+    let owner_id =
+        db::repositories::temporary_channel::find(channel_id.get() as i64, &ctx.data().db)
+            .await?
+            .and_then(|record| record.owner_id);
+    Ok((owner_id == Some(ctx.author().id.get() as i64)).then_some(channel_id))
+}
+
+/// Takes a permit (`allowed`) or reject entry's bits back off `channel_id`. A member's
+/// other bits, like a `/vmute`, are kept; a permitted member who's inside keeps seeing
+/// the channel so a hidden one doesn't drop them.
+async fn lift_access(
+    ctx: Context<'_>,
+    channel_id: serenity::ChannelId,
+    target: AccessTarget,
+    allowed: bool,
+) -> Result<(), Error> {
+    let user_id = match target {
+        AccessTarget::Role(role_id) => {
+            channel_id
+                .delete_permission(ctx, serenity::PermissionOverwriteType::Role(role_id))
+                .await
+                .requires(&[Permissions::MANAGE_ROLES])?;
+            return Ok(());
+        }
+        AccessTarget::User(user_id) => user_id,
+    };
+    let existing = super::member_overwrite(ctx, channel_id, user_id);
+    if allowed {
+        let bits = if super::is_in_channel(ctx, user_id, channel_id) {
+            Permissions::CONNECT
+        } else {
+            Permissions::CONNECT | Permissions::VIEW_CHANNEL
+        };
+        set_member_allow(
+            ctx.http(),
+            channel_id,
+            existing.as_ref(),
+            user_id,
+            bits,
+            false,
+        )
+        .await
+    } else {
+        set_member_deny(
+            ctx.http(),
+            channel_id,
+            existing.as_ref(),
+            user_id,
+            Permissions::CONNECT,
+            false,
+        )
+        .await
+    }
+}
+
+/// Writes the overwrite on the current channel and records the entry on the owner's list.
+/// A user's entry is merged into their overwrite so a `/vmute` or `/hide` grant on it
+/// survives.
+async fn set_access(
+    ctx: Context<'_>,
+    channel_id: serenity::ChannelId,
+    target: AccessTarget,
+    allowed: bool,
+) -> Result<(), Error> {
+    let overwrite = access_overwrite(target, allowed);
+    match target {
+        AccessTarget::User(user_id) => {
+            let existing = super::member_overwrite(ctx, channel_id, user_id);
+            if allowed {
+                set_member_allow(
+                    ctx.http(),
+                    channel_id,
+                    existing.as_ref(),
+                    user_id,
+                    overwrite.allow,
+                    true,
+                )
+                .await?;
+            } else {
+                set_member_deny(
+                    ctx.http(),
+                    channel_id,
+                    existing.as_ref(),
+                    user_id,
+                    overwrite.deny,
+                    true,
+                )
+                .await?;
+            }
+        }
+        AccessTarget::Role(_) => {
+            channel_id
+                .create_permission(ctx, overwrite)
+                .await
+                .requires(&[Permissions::MANAGE_ROLES])?;
+        }
+    }
+
+    let (target_id, is_role) = match target {
+        AccessTarget::User(id) => (id.get(), false),
+        AccessTarget::Role(id) => (id.get(), true),
+    };
+    db::repositories::access_rule::set(
+        db::entities::access_rule::Model {
+            user_id: ctx.author().id.get() as i64,
+            guild_id: ctx.guild_id().unwrap().get() as i64,
+            target_id: target_id as i64,
+            is_role,
+            allowed,
+        },
+        &ctx.data().db,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Resolves the user-or-role option pair, replying and returning `None` unless exactly
+/// one was given. @everyone is refused in favour of `/private` and `/public`.
+async fn require_target(
+    ctx: Context<'_>,
+    user: Option<serenity::User>,
+    role: Option<serenity::Role>,
+) -> Result<Option<AccessTarget>, Error> {
+    match (user, role) {
+        (Some(user), None) => Ok(Some(AccessTarget::User(user.id))),
+        (None, Some(role)) if role.id == ctx.guild_id().unwrap().everyone_role() => {
+            ctx.say("Use `/private` or `/public` to change access for everyone.")
+                .await?;
+            Ok(None)
+        }
+        (None, Some(role)) => Ok(Some(AccessTarget::Role(role.id))),
+        _ => {
+            ctx.say("Pick either a user or a role.").await?;
+            Ok(None)
+        }
+    }
+}
@@ -11,7 +11,7 @@ pub async fn limit(
     #[max = 99_u32]
     count: u32,
 ) -> Result<(), Error> {
-    let Some(channel_id) = super::require_temp_channel(ctx).await? else {
+    let Some(channel_id) = super::require_owned_channel(ctx).await? else {
         return Ok(());
     };
     channel_id
@@ -28,7 +28,7 @@ pub async fn limit(
 /// Remove the user limit from your dynamic voice channel.
 #[poise::command(slash_command, guild_only)]
 pub async fn unlimit(ctx: Context<'_>) -> Result<(), Error> {
-    let Some(channel_id) = super::require_temp_channel(ctx).await? else {
+    let Some(channel_id) = super::require_owned_channel(ctx).await? else {
         return Ok(());
     };
     channel_id
@@ -0,0 +1,88 @@
+use poise::{
+    serenity_prelude::{self as serenity, Permissions},
+    ChoiceParameter,
+};
+
+use crate::{
+    permissions::PermissionResultExt,
+    presets::{Region, VideoQuality},
+    Context, Error,
+};
+
+/// Set your channel's audio bitrate, up to what the server's boost level allows.
+#[poise::command(slash_command, guild_only)]
+pub async fn bitrate(
+    ctx: Context<'_>,
+    #[description = "Bitrate in kbps (8–384, depending on boosts)"]
+    #[min = 8_u32]
+    #[max = 384_u32]
+    kbps: u32,
+) -> Result<(), Error> {
+    let Some(channel_id) = super::require_owned_channel(ctx).await? else {
+        return Ok(());
+    };
+    let max_bitrate = ctx
+        .guild()
+        .map(|g| crate::presets::max_bitrate(g.premium_tier))
+        .unwrap_or(crate::presets::max_bitrate(serenity::PremiumTier::Tier0));
+    if kbps * 1000 > max_bitrate {
+        ctx.say(format!(
+            "This server's boost level allows at most **{} kbps**.",
+            max_bitrate / 1000
+        ))
+        .await?;
+        return Ok(());
+    }
+
+    channel_id
+        .edit(
+            ctx,
+            serenity::builder::EditChannel::new().bitrate(kbps * 1000),
+        )
+        .await
+        .requires(&[Permissions::MANAGE_CHANNELS])?;
+    ctx.say(format!("Bitrate set to **{kbps} kbps**.")).await?;
+    Ok(())
+}
+
+/// Choose the voice server region for your channel.
+#[poise::command(slash_command, guild_only)]
+pub async fn region(
+    ctx: Context<'_>,
+    #[description = "Region, or Automatic to let Discord choose"] region: Region,
+) -> Result<(), Error> {
+    let Some(channel_id) = super::require_owned_channel(ctx).await? else {
+        return Ok(());
+    };
+    channel_id
+        .edit(
+            ctx,
+            serenity::builder::EditChannel::new().voice_region(region.id().map(str::to_owned)),
+        )
+        .await
+        .requires(&[Permissions::MANAGE_CHANNELS])?;
+    ctx.say(format!("Region set to **{}**.", region.name()))
+        .await?;
+    Ok(())
+}
+
+/// Choose the camera video quality for your channel.
+#[poise::command(slash_command, guild_only, rename = "video-quality")]
+pub async fn video_quality(
+    ctx: Context<'_>,
+    #[description = "Auto, or Full for 720p"] quality: VideoQuality,
+) -> Result<(), Error> {
+    let Some(channel_id) = super::require_owned_channel(ctx).await? else {
+        return Ok(());
+    };
+    channel_id
+        .edit(
+            ctx,
+            serenity::builder::EditChannel::new().video_quality_mode(quality.mode()),
+        )
+        .await
+        .requires(&[Permissions::MANAGE_CHANNELS])?;
+    ctx.say(format!("Video quality set to **{}**.", quality.name()))
+        .await?;
+    Ok(())
+}
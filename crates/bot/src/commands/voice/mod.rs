@@ -1,12 +1,24 @@
+mod access;
 mod limit;
+mod media;
+mod moderation;
+mod ownership;
+mod preset;
 mod privacy;
 mod rename;
 
+pub use access::{permit, reject, unlist};
 pub use limit::{limit, unlimit};
-pub use privacy::{private, public};
-pub use rename::rename;
+pub use media::{bitrate, region, video_quality};
+pub use moderation::{vban, vdeafen, vkick, vmute};
+pub(crate) use ownership::transfer_channel;
+pub use ownership::{claim, transfer};
+pub use preset::preset;
+pub use privacy::{hide, private, public, unhide};
+pub(crate) use rename::rename_channel;
+pub use rename::{autoname, rename};
 
-use poise::serenity_prelude::ChannelId;
+use poise::serenity_prelude::{ChannelId, PermissionOverwrite, PermissionOverwriteType, UserId};
 
 use crate::{ChannelLocks, Context, Error};
 
@@ -35,9 +47,60 @@ async fn require_temp_channel(ctx: Context<'_>) -> Result<Option<ChannelId>, Err
     Ok(Some(channel_id))
 }
 
+/// Like [`require_temp_channel`], but also requires the caller to own the channel.
+/// Sends a reply naming the owner (or pointing at `/claim` if there is none) and
+/// returns `None` otherwise.
+async fn require_owned_channel(ctx: Context<'_>) -> Result<Option<ChannelId>, Error> {
+    let Some(channel_id) = require_temp_channel(ctx).await? else {
+        return Ok(None);
+    };
+
+    let owner_id =
+        db::repositories::temporary_channel::find(channel_id.get() as i64, &ctx.data().db)
+            .await?
+            .and_then(|record| record.owner_id);
+
+    match owner_id {
+        Some(id) if id == ctx.author().id.get() as i64 => Ok(Some(channel_id)),
+        Some(id) => {
+            ctx.say(format!("Only the channel owner (<@{id}>) can do that."))
+                .await?;
+            Ok(None)
+        }
+        None => {
+            ctx.say("This channel has no owner — use `/claim` to take it over.")
+                .await?;
+            Ok(None)
+        }
+    }
+}
+
+/// True if `user_id` is currently connected to `channel_id`, per the cache.
+fn is_in_channel(ctx: Context<'_>, user_id: UserId, channel_id: ChannelId) -> bool {
+    ctx.guild()
+        .and_then(|g| g.voice_states.get(&user_id).and_then(|vs| vs.channel_id))
+        == Some(channel_id)
+}
+
+/// `user_id`'s current overwrite on `channel_id`, per the cache.
+fn member_overwrite(
+    ctx: Context<'_>,
+    channel_id: ChannelId,
+    user_id: UserId,
+) -> Option<PermissionOverwrite> {
+    let guild = ctx.guild()?;
+    guild
+        .channels
+        .get(&channel_id)?
+        .permission_overwrites
+        .iter()
+        .find(|o| o.kind == PermissionOverwriteType::Member(user_id))
+        .cloned()
+}
+
 /// Held for the duration of an exclusive `/private`/`/public` operation on a channel;
 /// releases the channel on drop so a later call can claim it again.
-struct ChannelLockGuard<'a> {
+pub(crate) struct ChannelLockGuard<'a> {
     locks: &'a ChannelLocks,
     channel_id: ChannelId,
 }
@@ -56,7 +119,10 @@ impl Drop for ChannelLockGuard<'_> {
 /// `None` if another `/private`/`/public` invocation on the same channel is already
 /// in flight — callers should reply and bail out rather than proceed, to avoid racing
 /// on companion-channel creation/deletion.
-fn try_lock_channel(locks: &ChannelLocks, channel_id: ChannelId) -> Option<ChannelLockGuard<'_>> {
+pub(crate) fn try_lock_channel(
+    locks: &ChannelLocks,
+    channel_id: ChannelId,
+) -> Option<ChannelLockGuard<'_>> {
     let mut set = locks
         .lock()
         .unwrap_or_else(|poisoned| poisoned.into_inner());
@@ -0,0 +1,173 @@
+use poise::serenity_prelude::{self as serenity, Permissions};
+
+use crate::{permissions::PermissionResultExt, privacy::set_member_deny, Context, Error};
+
+/// Disconnect a member from your channel. They can rejoin.
+#[poise::command(slash_command, guild_only)]
+pub async fn vkick(
+    ctx: Context<'_>,
+    #[description = "Member to disconnect"] user: serenity::User,
+) -> Result<(), Error> {
+    let Some(channel_id) = super::require_owned_channel(ctx).await? else {
+        return Ok(());
+    };
+    if !require_other_member(ctx, &user).await? {
+        return Ok(());
+    }
+    if !super::is_in_channel(ctx, user.id, channel_id) {
+        ctx.say(format!("<@{}> isn't in your channel.", user.id))
+            .await?;
+        return Ok(());
+    }
+
+    ctx.guild_id()
+        .unwrap()
+        .disconnect_member(ctx, user.id)
+        .await
+        .requires(&[Permissions::MOVE_MEMBERS])?;
+    ctx.say(format!("<@{}> was disconnected.", user.id)).await?;
+    Ok(())
+}
+
+/// Disconnect a member and keep them out of your channel until it's deleted.
+#[poise::command(slash_command, guild_only)]
+pub async fn vban(
+    ctx: Context<'_>,
+    #[description = "Member to ban from this channel"] user: serenity::User,
+) -> Result<(), Error> {
+    let Some(channel_id) = super::require_owned_channel(ctx).await? else {
+        return Ok(());
+    };
+    if !require_other_member(ctx, &user).await? {
+        return Ok(());
+    }
+
+    let existing = super::member_overwrite(ctx, channel_id, user.id);
+    set_member_deny(
+        ctx.http(),
+        channel_id,
+        existing.as_ref(),
+        user.id,
+        Permissions::CONNECT,
+        true,
+    )
+    .await?;
+    if super::is_in_channel(ctx, user.id, channel_id) {
+        ctx.guild_id()
+            .unwrap()
+            .disconnect_member(ctx, user.id)
+            .await
+            .requires(&[Permissions::MOVE_MEMBERS])?;
+    }
+
+    ctx.say(format!("<@{}> can no longer join this channel.", user.id))
+        .await?;
+    Ok(())
+}
+
+/// Mute a member in your channel, or unmute them if they already are.
+#[poise::command(slash_command, guild_only)]
+pub async fn vmute(
+    ctx: Context<'_>,
+    #[description = "Member to mute or unmute"] user: serenity::User,
+) -> Result<(), Error> {
+    let Some(channel_id) = super::require_owned_channel(ctx).await? else {
+        return Ok(());
+    };
+    if !require_other_member(ctx, &user).await? {
+        return Ok(());
+    }
+
+    let existing = super::member_overwrite(ctx, channel_id, user.id);
+    let mute = !existing
+        .as_ref()
+        .is_some_and(|o| o.deny.contains(Permissions::SPEAK));
+    set_member_deny(
+        ctx.http(),
+        channel_id,
+        existing.as_ref(),
+        user.id,
+        Permissions::SPEAK,
+        mute,
+    )
+    .await?;
+
+    let reply = if mute {
+        format!("🔇 <@{}> can no longer speak in this channel.", user.id)
+    } else {
+        format!("🔊 <@{}> can speak again.", user.id)
+    };
+    ctx.say(reply).await?;
+    Ok(())
+}
+
+/// Server-deafen a member until they leave your channel, or undeafen them if they are.
+#[poise::command(slash_command, guild_only)]
+pub async fn vdeafen(
+    ctx: Context<'_>,
+    #[description = "Member to deafen or undeafen"] user: serenity::User,
+) -> Result<(), Error> {
+    let Some(channel_id) = super::require_owned_channel(ctx).await? else {
+        return Ok(());
+    };
+    if !require_other_member(ctx, &user).await? {
+        return Ok(());
+    }
+    // Discord has no per-channel deafen permission, so this is a server deafen that the
+    // voice-state handler lifts when they move out of the channel, or when they next
+    // connect if they left voice altogether.
+    if !super::is_in_channel(ctx, user.id, channel_id) {
+        ctx.say(format!("<@{}> isn't in your channel.", user.id))
+            .await?;
+        return Ok(());
+    }
+
+    let guild_id = ctx.guild_id().unwrap();
+    let db = &ctx.data().db;
+    let deafen =
+        db::repositories::voice_deafen::find(guild_id.get() as i64, user.id.get() as i64, db)
+            .await?
+            .is_none_or(|record| record.channel_id != channel_id.get() as i64);
+
+    guild_id
+        .edit_member(
+            ctx,
+            user.id,
+            serenity::builder::EditMember::new().deafen(deafen),
+        )
+        .await
+        .requires(&[Permissions::DEAFEN_MEMBERS])?;
+    if deafen {
+        db::repositories::voice_deafen::set(
+            guild_id.get() as i64,
+            user.id.get() as i64,
+            channel_id.get() as i64,
+            db,
+        )
+        .await?;
+        ctx.say(format!(
+            "🔇 <@{}> is server-deafened until they leave this channel.",
+            user.id
+        ))
+        .await?;
+    } else {
+        db::repositories::voice_deafen::delete(guild_id.get() as i64, user.id.get() as i64, db)
+            .await?;
+        ctx.say(format!("🔊 <@{}> can hear again.", user.id))
+            .await?;
+    }
+    Ok(())
+}
+
+/// Replies and returns `false` if `user` is the caller or a bot.
+async fn require_other_member(ctx: Context<'_>, user: &serenity::User) -> Result<bool, Error> {
+    if user.id == ctx.author().id {
+        ctx.say("You can't use that on yourself.").await?;
+        return Ok(false);
+    }
+    if user.bot {
+        ctx.say("You can't use that on a bot.").await?;
+        return Ok(false);
+    }
+    Ok(true)
+}
@@ -0,0 +1,121 @@
+use poise::serenity_prelude as serenity;
+
+use crate::{Context, Data, Error};
+
+/// Hand ownership of your dynamic voice channel to another member in it.
+#[poise::command(slash_command, guild_only)]
+pub async fn transfer(
+    ctx: Context<'_>,
+    #[description = "Member in your channel who should become its owner"] user: serenity::User,
+) -> Result<(), Error> {
+    let Some(channel_id) = super::require_owned_channel(ctx).await? else {
+        return Ok(());
+    };
+
+    let reply = transfer_channel(
+        ctx.serenity_context(),
+        ctx.guild_id().unwrap(),
+        channel_id,
+        ctx.author().id,
+        user.id,
+        ctx.data(),
+    )
+    .await?;
+    ctx.say(reply).await?;
+    Ok(())
+}
+
+/// Hands `channel_id` from `owner_id` to `new_owner` if they're another human member in
+/// the channel, returning the reply to show. Shared by `/transfer` and the control panel.
+pub(crate) async fn transfer_channel(
+    ctx: &serenity::Context,
+    guild_id: serenity::GuildId,
+    channel_id: serenity::ChannelId,
+    owner_id: serenity::UserId,
+    new_owner: serenity::UserId,
+    data: &Data,
+) -> Result<String, Error> {
+    if new_owner == owner_id {
+        return Ok("You already own this channel.".to_string());
+    }
+    let (in_channel, is_bot) = ctx
+        .cache
+        .guild(guild_id)
+        .map(|g| {
+            (
+                g.voice_states.get(&new_owner).and_then(|vs| vs.channel_id) == Some(channel_id),
+                g.members.get(&new_owner).is_some_and(|m| m.user.bot),
+            )
+        })
+        .unwrap_or_default();
+    if is_bot {
+        return Ok("Bots can't own a channel.".to_string());
+    }
+    if !in_channel {
+        return Ok(format!(
+            "<@{new_owner}> must be in this channel to receive it."
+        ));
+    }
+    db::repositories::temporary_channel::set_owner(
+        channel_id.get() as i64,
+        Some(new_owner.get() as i64),
+        &data.db,
+    )
+    .await?;
+    refresh_name(ctx, guild_id, channel_id, data).await;
+    Ok(format!("👑 <@{new_owner}> now owns this channel."))
+}
+
+/// Take ownership of your dynamic voice channel after its owner has left.
+#[poise::command(slash_command, guild_only)]
+pub async fn claim(ctx: Context<'_>) -> Result<(), Error> {
+    let Some(channel_id) = super::require_temp_channel(ctx).await? else {
+        return Ok(());
+    };
+
+    let owner_id =
+        db::repositories::temporary_channel::find(channel_id.get() as i64, &ctx.data().db)
+            .await?
+            .and_then(|record| record.owner_id);
+
+    match owner_id {
+        Some(id) if id == ctx.author().id.get() as i64 => {
+            ctx.say("You already own this channel.").await?;
+        }
+        Some(id) if super::is_in_channel(ctx, serenity::UserId::new(id as u64), channel_id) => {
+            ctx.say(format!(
+                "<@{id}> still owns this channel — ask them to `/transfer` it to you."
+            ))
+            .await?;
+        }
+        _ => {
+            db::repositories::temporary_channel::set_owner(
+                channel_id.get() as i64,
+                Some(ctx.author().id.get() as i64),
+                &ctx.data().db,
+            )
+            .await?;
+            refresh_name(
+                ctx.serenity_context(),
+                ctx.guild_id().unwrap(),
+                channel_id,
+                ctx.data(),
+            )
+            .await;
+            ctx.say("👑 You now own this channel.").await?;
+        }
+    }
+    Ok(())
+}
+
+/// Re-renders the channel's automatic name, which may show its owner via `{owner}`.
+async fn refresh_name(
+    ctx: &serenity::Context,
+    guild_id: serenity::GuildId,
+    channel_id: serenity::ChannelId,
+    data: &Data,
+) {
+    if let Err(e) = crate::events::recalculate_name(ctx, channel_id, guild_id, data).await {
+        tracing::warn!("Failed to rename {channel_id} after an ownership change: {e}");
+    }
+}
@@ -0,0 +1,91 @@
+use crate::{context_ext::ContextExt, Context, Error};
+
+/// Save or clear the settings restored whenever you create a channel.
+#[poise::command(slash_command, guild_only, subcommands("save", "clear"))]
+pub async fn preset(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Save your current channel's name, limit, privacy, bitrate, region and video quality.
+#[poise::command(slash_command, guild_only)]
+pub async fn save(ctx: Context<'_>) -> Result<(), Error> {
+    let Some(channel_id) = super::require_owned_channel(ctx).await? else {
+        return Ok(());
+    };
+
+    let Some((name, user_limit, bitrate, rtc_region, video_quality)) = ctx.guild().and_then(|g| {
+        g.channels.get(&channel_id).map(|c| {
+            (
+                c.name.clone(),
+                c.user_limit,
+                c.bitrate,
+                c.rtc_region.clone(),
+                c.video_quality_mode,
+            )
+        })
+    }) else {
+        ctx.say_ephemeral("Couldn't read your channel's settings — try again in a moment.")
+            .await?;
+        return Ok(());
+    };
+
+    let record =
+        db::repositories::temporary_channel::find(channel_id.get() as i64, &ctx.data().db).await?;
+    let private = record
+        .as_ref()
+        .is_some_and(|record| record.join_channel_id.is_some());
+    // An automatic name only fits the moment it was picked for, so only a name set with
+    // `/rename` is worth restoring.
+    let name = record
+        .is_some_and(|record| record.name_locked)
+        .then_some(name);
+
+    db::repositories::channel_preset::save(
+        db::entities::channel_preset::Model {
+            user_id: ctx.author().id.get() as i64,
+            guild_id: ctx.guild_id().unwrap().get() as i64,
+            name: name.clone(),
+            user_limit: user_limit.filter(|&n| n > 0).map(i64::from),
+            private,
+            bitrate: bitrate.map(i64::from),
+            rtc_region,
+            video_quality_mode: video_quality.map(|mode| i64::from(u8::from(mode))),
+        },
+        &ctx.data().db,
+    )
+    .await?;
+
+    let limit = match user_limit.filter(|&n| n > 0) {
+        Some(n) => n.to_string(),
+        None => "none".to_string(),
+    };
+    let privacy = if private { "private" } else { "public" };
+    let name = match name {
+        Some(name) => format!("**{name}**"),
+        None => "automatic name".to_string(),
+    };
+    ctx.say_ephemeral(format!(
+        "Preset saved: {name}, limit {limit}, {privacy}. \
+         Your next channel will be created with these settings."
+    ))
+    .await
+}
+
+/// Delete your saved preset. Your `/permit` and `/reject` lists are kept.
+#[poise::command(slash_command, guild_only)]
+pub async fn clear(ctx: Context<'_>) -> Result<(), Error> {
+    let user_id = ctx.author().id.get() as i64;
+    let guild_id = ctx.guild_id().unwrap().get() as i64;
+    let removed =
+        db::repositories::channel_preset::clear(user_id, guild_id, &ctx.data().db).await?;
+
+    if removed {
+        ctx.say_ephemeral(
+            "Preset cleared. New channels will use the server defaults; \
+             use `/unlist` to remove permit/reject entries.",
+        )
+        .await
+    } else {
+        ctx.say_ephemeral("You don't have a saved preset.").await
+    }
+}
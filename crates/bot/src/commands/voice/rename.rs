@@ -1,8 +1,8 @@
-use poise::serenity_prelude::Permissions;
+use poise::serenity_prelude::{self as serenity, Permissions};
 
-use crate::{permissions::PermissionResultExt, Context, Error};
+use crate::{permissions::PermissionResultExt, Context, Data, Error};
 
-/// Rename your current dynamic voice channel.
+/// Rename your current dynamic voice channel. Stops automatic naming until `/autoname`.
 #[poise::command(slash_command, guild_only)]
 pub async fn rename(
     ctx: Context<'_>,
@@ -10,17 +10,89 @@ pub async fn rename(
     #[max_length = 100]
     name: String,
 ) -> Result<(), Error> {
-    let Some(channel_id) = super::require_temp_channel(ctx).await? else {
+    let Some(channel_id) = super::require_owned_channel(ctx).await? else {
         return Ok(());
     };
 
-    channel_id
-        .edit(
-            ctx,
-            poise::serenity_prelude::builder::EditChannel::new().name(&name),
+    let reply = rename_channel(ctx.serenity_context(), channel_id, &name, ctx.data()).await?;
+    ctx.say(reply).await?;
+    Ok(())
+}
+
+/// Renames `channel_id` by hand and locks its name, returning the reply to show. Shared
+/// by `/rename` and the control panel.
+pub(crate) async fn rename_channel(
+    ctx: &serenity::Context,
+    channel_id: serenity::ChannelId,
+    name: &str,
+    data: &Data,
+) -> Result<String, Error> {
+    if name.is_empty() {
+        return Ok("The name can't be empty.".to_string());
+    }
+    let charged = match data.renames.try_rename_now(channel_id) {
+        Ok(charged) => charged,
+        Err(wait) => {
+            return Ok(format!(
+                "Discord only allows {} renames per channel every {} minutes. Try again in {} minute(s).",
+                crate::renamer::RENAMES_PER_WINDOW,
+                crate::renamer::RENAME_WINDOW.as_secs() / 60,
+                wait.as_secs().div_ceil(60)
+            ));
+        }
+    };
+
+    // Lock first, so automatic naming stops before the edit: the flusher re-checks the
+    // lock under the edit lock, and presence updates no longer queue names.
+    let was_locked = db::repositories::temporary_channel::find(channel_id.get() as i64, &data.db)
+        .await?
+        .is_some_and(|record| record.name_locked);
+    db::repositories::temporary_channel::set_name_locked(channel_id.get() as i64, true, &data.db)
+        .await?;
+    let edit = data.renames.edit_lock(channel_id);
+    let result = {
+        let _guard = edit.lock().await;
+        channel_id
+            .edit(ctx, serenity::builder::EditChannel::new().name(name))
+            .await
+            .requires(&[Permissions::MANAGE_CHANNELS])
+    };
+    if let Err(e) = result {
+        // Nothing changed, so the attempt mustn't use up the budget or stop automatic names.
+        data.renames.refund(channel_id, charged);
+        db::repositories::temporary_channel::set_name_locked(
+            channel_id.get() as i64,
+            was_locked,
+            &data.db,
         )
-        .await
-        .requires(&[Permissions::MANAGE_CHANNELS])?;
-    ctx.say(format!("Channel renamed to **{name}**.")).await?;
+        .await?;
+        return Err(e.into());
+    }
+    Ok(format!(
+        "Channel renamed to **{name}**. Use `/autoname` to go back to automatic names."
+    ))
+}
+
+/// Resume naming your channel after what its members are playing.
+#[poise::command(slash_command, guild_only)]
+pub async fn autoname(ctx: Context<'_>) -> Result<(), Error> {
+    let Some(channel_id) = super::require_owned_channel(ctx).await? else {
+        return Ok(());
+    };
+
+    db::repositories::temporary_channel::set_name_locked(
+        channel_id.get() as i64,
+        false,
+        &ctx.data().db,
+    )
+    .await?;
+    crate::events::recalculate_name(
+        ctx.serenity_context(),
+        channel_id,
+        ctx.guild_id().unwrap(),
+        ctx.data(),
+    )
+    .await?;
+    ctx.say("Automatic naming is back on.").await?;
     Ok(())
 }
@@ -0,0 +1,195 @@
+//! Grace period before an empty temp channel is deleted.
+//!
+//! When the last member leaves, the channel is kept and `temporary_channels.delete_at`
+//! is set, so a member who drops for a moment comes back to the same name, limit and
+//! privacy. Rejoining clears it; otherwise the sweeper deletes the channel once it's
+//! due. The deadline lives in the db, so [`super::startup_cleanup`] keeps pending
+//! channels around after a restart instead of deleting them straight away. A failed
+//! delete keeps the channel due and is retried with a growing delay.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use poise::serenity_prelude::{self as serenity, Permissions};
+
+use db::DatabaseConnection;
+
+use crate::{permissions::PermissionResultExt, renamer::RenameScheduler};
+
+/// Grace period for guilds that never ran `/config empty-channels`.
+pub const DEFAULT_GRACE_SECS: i64 = 30;
+
+/// How often the sweeper looks for channels whose grace period ran out.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Delay before retrying a failed delete, doubled on each further failure up to
+/// [`MAX_RETRY_SECS`].
+const FIRST_RETRY_SECS: i64 = 30;
+const MAX_RETRY_SECS: i64 = 60 * 60;
+
+/// The guild's grace period in seconds, falling back to the default on a missing
+/// setting or db error. 0 deletes empty channels immediately.
+pub async fn grace_secs(guild_id: i64, db: &DatabaseConnection) -> i64 {
+    match db::repositories::guild::empty_grace_secs(guild_id, db).await {
+        Ok(secs) => secs.unwrap_or(DEFAULT_GRACE_SECS),
+        Err(e) => {
+            tracing::warn!("Failed to load empty-channel grace period for guild {guild_id}: {e}");
+            DEFAULT_GRACE_SECS
+        }
+    }
+}
+
+/// Cancels a pending deletion of `channel_id` because someone joined it.
+pub async fn cancel(channel_id: serenity::ChannelId, db: &DatabaseConnection) {
+    let id = channel_id.get() as i64;
+    let pending = match db::repositories::temporary_channel::find(id, db).await {
+        Ok(record) => record.is_some_and(|r| r.delete_at.is_some()),
+        Err(e) => {
+            tracing::warn!("Failed to look up {channel_id} for a pending deletion: {e}");
+            return;
+        }
+    };
+    if !pending {
+        return;
+    }
+    match db::repositories::temporary_channel::set_delete_at(id, None, db).await {
+        Ok(()) => tracing::debug!("Cancelled deletion of {channel_id}; someone rejoined"),
+        Err(e) => tracing::warn!("Failed to cancel deletion of {channel_id}: {e}"),
+    }
+}
+
+/// Deletes the temp channel, its join-request channel if any, and its db row. Also
+/// drops the trigger's row if the trigger was deleted while this channel was in use
+/// (see [`super::removal`]).
+pub(crate) async fn delete(
+    cache: &serenity::Cache,
+    http: &serenity::Http,
+    record: &db::entities::temporary_channel::Model,
+    db: &DatabaseConnection,
+    renames: &RenameScheduler,
+) -> Result<(), crate::Error> {
+    let channel_id = serenity::ChannelId::new(record.id as u64);
+    crate::delete_join_channel_if_present(http, record.join_channel_id).await;
+    channel_id
+        .delete(http)
+        .await
+        .requires(&[Permissions::MANAGE_CHANNELS])?;
+    if db::repositories::temporary_channel::delete(record.id, db).await? {
+        crate::metrics::temp_channel_deleted();
+    }
+    renames.forget(channel_id);
+    tracing::debug!("Deleted empty temp channel {channel_id}");
+    super::removal::reap_trigger_if_deleted(
+        cache,
+        serenity::GuildId::new(record.guild_id as u64),
+        record.primary_channel_id,
+        db,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Periodically deletes channels whose grace period ran out, unless someone is back
+/// in them per the cache.
+pub fn spawn_sweeper(
+    cache: Arc<serenity::Cache>,
+    http: Arc<serenity::Http>,
+    db: DatabaseConnection,
+    renames: Arc<RenameScheduler>,
+) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(SWEEP_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        // Channel id → failed deletes in a row. Lost on restart, which only restarts
+        // the backoff.
+        let mut failures = HashMap::new();
+        loop {
+            interval.tick().await;
+            if let Err(e) = sweep(&cache, &http, &db, &renames, &mut failures).await {
+                tracing::warn!("Empty-channel sweep failed: {e}");
+            }
+        }
+    });
+}
+
+async fn sweep(
+    cache: &serenity::Cache,
+    http: &serenity::Http,
+    db: &DatabaseConnection,
+    renames: &RenameScheduler,
+    failures: &mut HashMap<i64, u32>,
+) -> Result<(), crate::Error> {
+    let now = crate::time::now_unix();
+    // Forget channels deleted some other way or rejoined since they last failed.
+    let mut settled = Vec::new();
+    for &id in failures.keys() {
+        let pending = db::repositories::temporary_channel::find(id, db)
+            .await?
+            .is_some_and(|r| r.delete_at.is_some());
+        if !pending {
+            settled.push(id);
+        }
+    }
+    for id in settled {
+        failures.remove(&id);
+    }
+    for record in db::repositories::temporary_channel::list_due(now, db).await? {
+        let channel_id = serenity::ChannelId::new(record.id as u64);
+        let guild_id = serenity::GuildId::new(record.guild_id as u64);
+        // A guild missing from the cache is still loading; try again next round.
+        let Some(occupied) = cache.guild(guild_id).map(|g| {
+            g.voice_states
+                .values()
+                .any(|vs| vs.channel_id == Some(channel_id))
+        }) else {
+            continue;
+        };
+        if occupied {
+            // The join was missed (e.g. during a reconnect); keep the channel.
+            failures.remove(&record.id);
+            db::repositories::temporary_channel::set_delete_at(record.id, None, db).await?;
+            continue;
+        }
+        match delete(cache, http, &record, db, renames).await {
+            Ok(()) => {
+                failures.remove(&record.id);
+            }
+            Err(e) => {
+                let failed = failures.entry(record.id).or_default();
+                *failed += 1;
+                let delay = retry_delay(*failed);
+                tracing::warn!(
+                    "Failed to delete empty temp channel {channel_id}, retrying in {delay}s: {e}"
+                );
+                db::repositories::temporary_channel::set_delete_at(
+                    record.id,
+                    Some(now + delay),
+                    db,
+                )
+                .await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Pure: seconds to wait after the `failures`-th failed delete in a row.
+fn retry_delay(failures: u32) -> i64 {
+    let doublings = failures.saturating_sub(1).min(16);
+    (FIRST_RETRY_SECS << doublings).min(MAX_RETRY_SECS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retries_back_off_up_to_an_hour() {
+        assert_eq!(retry_delay(1), 30);
+        assert_eq!(retry_delay(2), 60);
+        assert_eq!(retry_delay(4), 240);
+        assert_eq!(retry_delay(8), MAX_RETRY_SECS);
+        assert_eq!(retry_delay(u32::MAX), MAX_RETRY_SECS);
+    }
+}
@@ -0,0 +1,285 @@
+//! Idle members and the sweeper that clears out channels nobody is really using.
+//!
+//! A member counts as idle while they're self-deafened or their status is Idle. Idle
+//! stretches are recorded on the open `voice_sessions` row so they earn no XP (see
+//! [`super::xp::active_secs`]). Once everyone in a temp channel has been idle longer
+//! than the guild's threshold, the sweeper warns them in the channel and, if nobody
+//! becomes active within the warning period, moves them to the AFK channel or
+//! disconnects them, which lets the empty channel be deleted.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use poise::serenity_prelude::{self as serenity, Permissions};
+
+use db::DatabaseConnection;
+
+use crate::{permissions::PermissionResultExt, Data};
+
+pub const DEFAULT_THRESHOLD_SECS: i64 = 30 * 60;
+pub const DEFAULT_WARNING_SECS: i64 = 2 * 60;
+
+/// How often the sweeper looks for idle channels.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A guild's idle settings, set with `/config idle`; guilds that never ran it use
+/// [`Policy::default`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Policy {
+    /// 0 turns the sweeper off; idle time still earns no XP.
+    pub threshold_secs: i64,
+    pub warning_secs: i64,
+    pub disconnect: bool,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self {
+            threshold_secs: DEFAULT_THRESHOLD_SECS,
+            warning_secs: DEFAULT_WARNING_SECS,
+            disconnect: false,
+        }
+    }
+}
+
+impl From<db::entities::idle_policy::Model> for Policy {
+    fn from(p: db::entities::idle_policy::Model) -> Self {
+        Self {
+            threshold_secs: p.threshold_secs,
+            warning_secs: p.warning_secs,
+            disconnect: p.disconnect,
+        }
+    }
+}
+
+impl Policy {
+    pub async fn load(guild_id: i64, db: &DatabaseConnection) -> Self {
+        let get = db::repositories::idle_policy::get(guild_id, db);
+        crate::policy::load_or_default("idle policy", guild_id, get).await
+    }
+}
+
+/// Whether `user_id` is idle right now, per the cache.
+fn is_idle(guild: &serenity::Guild, user_id: serenity::UserId) -> bool {
+    guild
+        .voice_states
+        .get(&user_id)
+        .is_some_and(|vs| vs.self_deaf)
+        || guild
+            .presences
+            .get(&user_id)
+            .is_some_and(|p| p.status == serenity::OnlineStatus::Idle)
+}
+
+/// Records whether the member is idle on their open session. Called on every voice-state
+/// and presence update, since either can flip the state.
+pub async fn sync(
+    ctx: &serenity::Context,
+    guild_id: serenity::GuildId,
+    user_id: serenity::UserId,
+    data: &Data,
+) {
+    let Some(idle) = ctx.cache.guild(guild_id).map(|g| is_idle(&g, user_id)) else {
+        return;
+    };
+    if let Err(e) = db::repositories::voice_session::set_idle(
+        user_id.get() as i64,
+        guild_id.get() as i64,
+        idle,
+        crate::time::now_unix(),
+        &data.db,
+    )
+    .await
+    {
+        tracing::warn!("Failed to record idle state for {user_id}: {e}");
+    }
+}
+
+/// What the sweeper should do with a channel this round.
+#[derive(Debug, PartialEq, Eq)]
+enum Action {
+    Nothing,
+    /// Someone became active after a warning; forget it.
+    Reset,
+    Warn,
+    Evict,
+}
+
+/// Pure decision for one channel. `idle_since` holds, per member, when they went idle
+/// (`None` while active); `warned_at` is when the channel was last warned.
+fn decide(idle_since: &[Option<i64>], policy: &Policy, warned_at: Option<i64>, now: i64) -> Action {
+    let all_idle = !idle_since.is_empty()
+        && idle_since
+            .iter()
+            .all(|since| since.is_some_and(|since| now - since >= policy.threshold_secs));
+    match (all_idle, warned_at) {
+        (false, Some(_)) => Action::Reset,
+        (false, None) => Action::Nothing,
+        (true, None) => Action::Warn,
+        (true, Some(at)) if now - at >= policy.warning_secs => Action::Evict,
+        (true, Some(_)) => Action::Nothing,
+    }
+}
+
+/// Periodically warns and then clears temp channels whose members are all idle.
+pub fn spawn_sweeper(
+    cache: Arc<serenity::Cache>,
+    http: Arc<serenity::Http>,
+    db: DatabaseConnection,
+) {
+    tokio::spawn(async move {
+        // Channel → when its members were warned. Lost on restart, which only means a
+        // channel is warned again before anyone is moved.
+        let mut warned: HashMap<serenity::ChannelId, i64> = HashMap::new();
+        let mut interval = tokio::time::interval(SWEEP_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            let mut seen = HashSet::new();
+            for guild_id in cache.guilds() {
+                if let Err(e) =
+                    sweep_guild(&cache, &http, &db, guild_id, &mut warned, &mut seen).await
+                {
+                    tracing::warn!("Idle sweep failed for guild {guild_id}: {e}");
+                }
+            }
+            warned.retain(|channel_id, _| seen.contains(channel_id));
+        }
+    });
+}
+
+async fn sweep_guild(
+    cache: &serenity::Cache,
+    http: &serenity::Http,
+    db: &DatabaseConnection,
+    guild_id: serenity::GuildId,
+    warned: &mut HashMap<serenity::ChannelId, i64>,
+    seen: &mut HashSet<serenity::ChannelId>,
+) -> Result<(), crate::Error> {
+    let gid = guild_id.get() as i64;
+    let policy = Policy::load(gid, db).await;
+    if policy.threshold_secs == 0 {
+        return Ok(());
+    }
+    let now = crate::time::now_unix();
+    let channels = db::repositories::temporary_channel::list_by_guild(gid, db).await?;
+    let idle_since: HashMap<i64, Option<i64>> =
+        db::repositories::voice_session::list_by_guild(gid, db)
+            .await?
+            .into_iter()
+            .map(|s| (s.user_id, s.idle_since))
+            .collect();
+
+    // Read everything needed from the cache up front; the guard can't be held across awaits.
+    let Some((members, afk_channel)) = cache.guild(guild_id).map(|g| {
+        let members: HashMap<serenity::ChannelId, Vec<serenity::UserId>> = channels
+            .iter()
+            .map(|record| {
+                let channel_id = serenity::ChannelId::new(record.id as u64);
+                let members = g
+                    .voice_states
+                    .values()
+                    .filter(|vs| vs.channel_id == Some(channel_id))
+                    .filter(|vs| !g.members.get(&vs.user_id).is_some_and(|m| m.user.bot))
+                    .map(|vs| vs.user_id)
+                    .collect();
+                (channel_id, members)
+            })
+            .collect();
+        (members, g.afk_metadata.as_ref().map(|m| m.afk_channel_id))
+    }) else {
+        return Ok(());
+    };
+
+    for (channel_id, members) in members {
+        seen.insert(channel_id);
+        let since: Vec<Option<i64>> = members
+            .iter()
+            .map(|m| idle_since.get(&(m.get() as i64)).copied().flatten())
+            .collect();
+        match decide(&since, &policy, warned.get(&channel_id).copied(), now) {
+            Action::Nothing => {}
+            Action::Reset => {
+                warned.remove(&channel_id);
+            }
+            Action::Warn => {
+                let target = match (policy.disconnect, afk_channel) {
+                    (false, Some(afk)) => format!("moved to <#{afk}>"),
+                    _ => "disconnected".to_string(),
+                };
+                let _ = channel_id
+                    .say(
+                        http,
+                        format!(
+                            "💤 Everyone here has been idle for over {} minutes. Unless someone \
+                             undeafens or comes back, you'll be {target} in {} minute(s).",
+                            policy.threshold_secs / 60,
+                            (policy.warning_secs as u64).div_ceil(60)
+                        ),
+                    )
+                    .await;
+                warned.insert(channel_id, now);
+            }
+            Action::Evict => {
+                warned.remove(&channel_id);
+                for user_id in members {
+                    let result = match (policy.disconnect, afk_channel) {
+                        (false, Some(afk)) => guild_id.move_member(http, user_id, afk).await,
+                        _ => guild_id.disconnect_member(http, user_id).await,
+                    };
+                    // One member leaving first, or failing to move, shouldn't keep the rest.
+                    if let Err(e) = result.requires(&[Permissions::MOVE_MEMBERS]) {
+                        tracing::warn!(
+                            "Failed to clear idle member {user_id} out of {channel_id}: {e}"
+                        );
+                    }
+                }
+                tracing::debug!("Cleared idle members out of {channel_id}");
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> Policy {
+        Policy {
+            threshold_secs: 600,
+            warning_secs: 120,
+            disconnect: false,
+        }
+    }
+
+    #[test]
+    fn warns_once_everyone_is_idle_past_the_threshold() {
+        let idle = [Some(1_000), Some(1_200)];
+        assert_eq!(decide(&idle, &policy(), None, 1_799), Action::Nothing);
+        assert_eq!(decide(&idle, &policy(), None, 1_800), Action::Warn);
+    }
+
+    #[test]
+    fn one_active_member_keeps_the_channel() {
+        let idle = [Some(0), None];
+        assert_eq!(decide(&idle, &policy(), None, 10_000), Action::Nothing);
+        assert_eq!(decide(&idle, &policy(), Some(9_000), 10_000), Action::Reset);
+    }
+
+    #[test]
+    fn evicts_after_the_warning_period() {
+        let idle = [Some(0)];
+        assert_eq!(
+            decide(&idle, &policy(), Some(1_000), 1_119),
+            Action::Nothing
+        );
+        assert_eq!(decide(&idle, &policy(), Some(1_000), 1_120), Action::Evict);
+    }
+
+    #[test]
+    fn empty_channels_are_left_alone() {
+        assert_eq!(decide(&[], &policy(), None, 10_000), Action::Nothing);
+    }
+}
@@ -0,0 +1,142 @@
+//! Rate limit on creating temp channels, against members hopping in and out of a trigger.
+//!
+//! Each trigger join that would create a channel takes a token from the member's
+//! [`crate::throttle::CreationLimiter`] bucket. A member who runs out is refused and,
+//! if the guild's policy says so, banned from creating channels for a while. The ban
+//! is stored in `trigger_bans` so it outlasts a restart, unlike the buckets.
+
+use std::time::Duration;
+
+use poise::serenity_prelude as serenity;
+
+use db::DatabaseConnection;
+
+use crate::Data;
+
+pub const DEFAULT_BURST: i64 = 3;
+pub const DEFAULT_REFILL_SECS: i64 = 60;
+pub const DEFAULT_BAN_SECS: i64 = 5 * 60;
+
+/// A guild's join-rate settings, set with `/config join-rate`; guilds that never ran it
+/// use [`Policy::default`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Policy {
+    /// 0 turns the limit off.
+    pub burst: i64,
+    pub refill_secs: i64,
+    /// 0 refuses the attempt without banning.
+    pub ban_secs: i64,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self {
+            burst: DEFAULT_BURST,
+            refill_secs: DEFAULT_REFILL_SECS,
+            ban_secs: DEFAULT_BAN_SECS,
+        }
+    }
+}
+
+impl From<db::entities::join_rate_policy::Model> for Policy {
+    fn from(p: db::entities::join_rate_policy::Model) -> Self {
+        Self {
+            burst: p.burst,
+            refill_secs: p.refill_secs,
+            ban_secs: p.ban_secs,
+        }
+    }
+}
+
+impl Policy {
+    pub async fn load(guild_id: i64, db: &DatabaseConnection) -> Self {
+        let get = db::repositories::join_rate_policy::get(guild_id, db);
+        crate::policy::load_or_default("join-rate policy", guild_id, get).await
+    }
+}
+
+/// Whole minutes left until `until`, rounded up so "0 minutes" is never shown.
+pub(super) fn minutes_left(until: i64, now: i64) -> i64 {
+    ((until - now).max(1) + 59) / 60
+}
+
+/// Returns `true` if `user_id` may create a channel from `trigger` now. Otherwise the
+/// member has been refused (see [`super::limits::refuse`]) and, on running out of
+/// tokens, possibly banned.
+pub async fn admit(
+    ctx: &serenity::Context,
+    trigger: &db::entities::primary_channel::Model,
+    guild_id: serenity::GuildId,
+    user_id: serenity::UserId,
+    data: &Data,
+) -> Result<bool, crate::Error> {
+    let gid = guild_id.get() as i64;
+    let uid = user_id.get() as i64;
+    let now = crate::time::now_unix();
+
+    if let Some(until) =
+        db::repositories::trigger_ban::active_until(gid, uid, now, &data.db).await?
+    {
+        crate::metrics::trigger_join_rejected("banned");
+        let notice = format!(
+            "You're creating channels too quickly. Try again in {} minute(s).",
+            minutes_left(until, now)
+        );
+        super::limits::refuse(
+            ctx,
+            serenity::ChannelId::new(trigger.id as u64),
+            guild_id,
+            user_id,
+            &notice,
+        )
+        .await?;
+        return Ok(false);
+    }
+
+    let policy = Policy::load(gid, &data.db).await;
+    if data.creations.try_acquire(
+        guild_id,
+        user_id,
+        policy.burst.clamp(0, u32::MAX as i64) as u32,
+        Duration::from_secs(policy.refill_secs.max(0) as u64),
+    ) {
+        return Ok(true);
+    }
+
+    crate::metrics::trigger_join_rejected("rate_limited");
+    let notice = if policy.ban_secs > 0 {
+        let until = now + policy.ban_secs;
+        db::repositories::trigger_ban::ban(gid, uid, until, &data.db).await?;
+        format!(
+            "You're creating channels too quickly, so you can't create another for {} minute(s).",
+            minutes_left(until, now)
+        )
+    } else {
+        format!(
+            "You're creating channels too quickly. Try again in {} minute(s).",
+            minutes_left(now + policy.refill_secs, now)
+        )
+    };
+    super::limits::refuse(
+        ctx,
+        serenity::ChannelId::new(trigger.id as u64),
+        guild_id,
+        user_id,
+        &notice,
+    )
+    .await?;
+    tracing::debug!("{user_id} ran out of channel creations in guild {guild_id}");
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn minutes_left_rounds_up() {
+        assert_eq!(minutes_left(1_060, 1_000), 1);
+        assert_eq!(minutes_left(1_061, 1_000), 2);
+        assert_eq!(minutes_left(1_000, 1_000), 1);
+    }
+}
@@ -0,0 +1,472 @@
+//! Allow/Deny prompts for people asking to join a private channel.
+//!
+//! Each prompt is a row in `join_requests`, and its buttons carry the channel and
+//! requester in their custom ids, so they keep working across restarts: clicks arrive
+//! through the global interaction handler rather than a per-message collector, and a
+//! background sweep expires prompts nobody answered.
+//!
+//! To keep someone from flooding the channel by bouncing through `[join ↑]`, a requester
+//! gets at most one open prompt per channel, must wait out a cooldown before the next,
+//! and is turned away without a prompt after too many recent denials. Anyone turned away
+//! is told why and when to try again. The counters live in `join_request_stats`.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use poise::serenity_prelude::{self as serenity, Context, Permissions};
+
+use db::DatabaseConnection;
+
+use crate::{permissions::PermissionResultExt, Data};
+
+/// How long a prompt stays answerable when the guild hasn't configured a timeout.
+pub const DEFAULT_TIMEOUT_SECS: i64 = 120;
+pub const DEFAULT_COOLDOWN_SECS: i64 = 60;
+pub const DEFAULT_MAX_DENIALS: i64 = 3;
+pub const DEFAULT_DENIAL_WINDOW_SECS: i64 = 60 * 60;
+
+/// How often the sweep looks for expired prompts.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(15);
+
+pub const ALLOW_PREFIX: &str = "join_allow";
+pub const DENY_PREFIX: &str = "join_deny";
+
+/// A guild's join-request settings, set with `/config join-requests`; guilds that never
+/// ran it use [`Policy::default`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Policy {
+    pub timeout_secs: i64,
+    pub cooldown_secs: i64,
+    /// Denials within `denial_window_secs` that get further requests refused outright;
+    /// 0 disables auto-denial.
+    pub max_denials: i64,
+    pub denial_window_secs: i64,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self {
+            timeout_secs: DEFAULT_TIMEOUT_SECS,
+            cooldown_secs: DEFAULT_COOLDOWN_SECS,
+            max_denials: DEFAULT_MAX_DENIALS,
+            denial_window_secs: DEFAULT_DENIAL_WINDOW_SECS,
+        }
+    }
+}
+
+impl From<db::entities::join_request_policy::Model> for Policy {
+    fn from(p: db::entities::join_request_policy::Model) -> Self {
+        Self {
+            timeout_secs: p.timeout_secs,
+            cooldown_secs: p.cooldown_secs,
+            max_denials: p.max_denials,
+            denial_window_secs: p.denial_window_secs,
+        }
+    }
+}
+
+impl Policy {
+    pub async fn load(guild_id: i64, db: &DatabaseConnection) -> Self {
+        let get = db::repositories::join_request_policy::get(guild_id, db);
+        crate::policy::load_or_default("join-request policy", guild_id, get).await
+    }
+}
+
+/// What to do when someone enters a `[join ↑]` channel. Every variant but `Open` turns
+/// the requester away, with the time they can try again.
+#[derive(Debug, PartialEq, Eq)]
+enum Gate {
+    /// Post a new prompt.
+    Open,
+    /// A prompt for this requester is still open until `until`; leave it be rather than
+    /// post another.
+    Pending { until: i64 },
+    /// The requester's last prompt was too recent.
+    CoolingDown { until: i64 },
+    /// The requester was denied too often lately; disconnect them without asking.
+    AutoDeny { until: i64 },
+}
+
+impl Gate {
+    /// What to tell a requester who was turned away, or `None` for `Open`.
+    fn notice(&self, now: i64) -> Option<String> {
+        let minutes = |until| super::join_rate::minutes_left(until, now);
+        Some(match *self {
+            Gate::Open => return None,
+            Gate::Pending { until } => format!(
+                "Your request to join is still waiting for an answer. If nobody responds, \
+                 you can ask again in {} minute(s).",
+                minutes(until)
+            ),
+            Gate::CoolingDown { until } => format!(
+                "You asked to join recently. Try again in {} minute(s).",
+                minutes(until)
+            ),
+            Gate::AutoDeny { until } => format!(
+                "Your requests to join were denied too often. Try again in {} minute(s).",
+                minutes(until)
+            ),
+        })
+    }
+}
+
+/// `pending` is the expiry of the requester's open prompt, if they have one.
+fn gate(
+    policy: &Policy,
+    stat: Option<&db::entities::join_request_stat::Model>,
+    pending: Option<i64>,
+    now: i64,
+) -> Gate {
+    if let Some(stat) = stat {
+        let until = stat.first_denied_at + policy.denial_window_secs;
+        if policy.max_denials > 0 && stat.denials >= policy.max_denials && now < until {
+            return Gate::AutoDeny { until };
+        }
+    }
+    if let Some(until) = pending {
+        return Gate::Pending { until };
+    }
+    match stat {
+        Some(stat) if now - stat.last_requested_at < policy.cooldown_secs => Gate::CoolingDown {
+            until: stat.last_requested_at + policy.cooldown_secs,
+        },
+        _ => Gate::Open,
+    }
+}
+
+/// Handles someone entering a private channel's `[join ↑]` channel: posts an Allow/Deny
+/// prompt in the channel's text area and records it, unless the spam limits say otherwise.
+pub async fn open(
+    ctx: &Context,
+    join_channel_id: serenity::ChannelId,
+    temp_record: db::entities::temporary_channel::Model,
+    requester_id: serenity::UserId,
+    guild_id: serenity::GuildId,
+    data: &Data,
+) -> Result<(), crate::Error> {
+    let private_channel_id = serenity::ChannelId::new(temp_record.id as u64);
+    let requester = requester_id.get() as i64;
+    let policy = Policy::load(guild_id.get() as i64, &data.db).await;
+    let now = crate::time::now_unix();
+
+    let stat =
+        db::repositories::join_request_stat::get(temp_record.id, requester, &data.db).await?;
+    let pending = db::repositories::join_request::find(temp_record.id, requester, &data.db)
+        .await?
+        .map(|request| request.expires_at)
+        .filter(|&expires_at| expires_at > now);
+    let gate = gate(&policy, stat.as_ref(), pending, now);
+    if let Some(notice) = gate.notice(now) {
+        super::limits::refuse(ctx, join_channel_id, guild_id, requester_id, &notice).await?;
+        tracing::debug!("Join request via {join_channel_id} by {requester_id} refused: {gate:?}");
+        return Ok(());
+    }
+
+    let msg = private_channel_id
+        .send_message(
+            ctx,
+            serenity::builder::CreateMessage::new()
+                .content(format!("<@{}> wants to join. Allow or deny?", requester_id))
+                .components(vec![serenity::builder::CreateActionRow::Buttons(vec![
+                    serenity::builder::CreateButton::new(custom_id(
+                        true,
+                        private_channel_id,
+                        requester_id,
+                    ))
+                    .label("Allow")
+                    .style(serenity::ButtonStyle::Success),
+                    serenity::builder::CreateButton::new(custom_id(
+                        false,
+                        private_channel_id,
+                        requester_id,
+                    ))
+                    .label("Deny")
+                    .style(serenity::ButtonStyle::Danger),
+                ])]),
+        )
+        .await
+        .requires(&[Permissions::SEND_MESSAGES])?;
+
+    db::repositories::join_request::upsert(
+        db::entities::join_request::Model {
+            channel_id: temp_record.id,
+            requester_id: requester,
+            message_id: msg.id.get() as i64,
+            expires_at: now + policy.timeout_secs,
+        },
+        &data.db,
+    )
+    .await?;
+    db::repositories::join_request_stat::record_request(temp_record.id, requester, now, &data.db)
+        .await?;
+
+    tracing::debug!("Join request via {join_channel_id} opened for user {requester_id}");
+    Ok(())
+}
+
+fn custom_id(
+    allow: bool,
+    channel_id: serenity::ChannelId,
+    requester_id: serenity::UserId,
+) -> String {
+    let prefix = if allow { ALLOW_PREFIX } else { DENY_PREFIX };
+    format!("{prefix}:{channel_id}:{requester_id}")
+}
+
+/// Parses a button's custom id into (allow, private channel, requester), or `None` if
+/// it isn't a join-request button.
+fn parse_custom_id(id: &str) -> Option<(bool, serenity::ChannelId, serenity::UserId)> {
+    let mut parts = id.split(':');
+    let allow = match parts.next()? {
+        ALLOW_PREFIX => true,
+        DENY_PREFIX => false,
+        _ => return None,
+    };
+    let channel_id = parts.next()?.parse().ok().filter(|&id| id != 0)?;
+    let requester_id = parts.next()?.parse().ok().filter(|&id| id != 0)?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((
+        allow,
+        serenity::ChannelId::new(channel_id),
+        serenity::UserId::new(requester_id),
+    ))
+}
+
+/// Handles an Allow/Deny click.
+pub async fn handle_component(
+    ctx: &Context,
+    interaction: &serenity::ComponentInteraction,
+    data: &Data,
+) -> Result<(), crate::Error> {
+    let Some((allow, channel_id, requester_id)) = parse_custom_id(&interaction.data.custom_id)
+    else {
+        return Ok(());
+    };
+    let Some(guild_id) = interaction.guild_id else {
+        return Ok(());
+    };
+
+    // Only members currently inside the private channel may respond.
+    let in_channel = ctx.cache.guild(guild_id).and_then(|g| {
+        g.voice_states
+            .get(&interaction.user.id)
+            .and_then(|vs| vs.channel_id)
+    }) == Some(channel_id);
+    if !in_channel {
+        interaction
+            .create_response(
+                ctx,
+                serenity::builder::CreateInteractionResponse::Message(
+                    serenity::builder::CreateInteractionResponseMessage::new()
+                        .content("You must be inside the private channel to respond.")
+                        .ephemeral(true),
+                ),
+            )
+            .await?;
+        return Ok(());
+    }
+
+    // Deleting the row claims the request, so two members clicking at once can't both act.
+    let claimed = db::repositories::join_request::delete(
+        channel_id.get() as i64,
+        requester_id.get() as i64,
+        &data.db,
+    )
+    .await?;
+    let content = if !claimed {
+        format!("~~<@{requester_id}> wants to join.~~ Request expired.")
+    } else if allow {
+        if let Err(e) = crate::privacy::show_if_hidden(
+            &ctx.cache,
+            &ctx.http,
+            guild_id,
+            channel_id,
+            requester_id,
+            &data.db,
+        )
+        .await
+        {
+            tracing::warn!("Failed to show hidden {channel_id} to {requester_id}: {e}");
+        }
+        let _ = guild_id.move_member(ctx, requester_id, channel_id).await;
+        format!(
+            "✅ <@{requester_id}> was allowed in by <@{}>.",
+            interaction.user.id
+        )
+    } else {
+        let _ = guild_id.disconnect_member(ctx, requester_id).await;
+        let policy = Policy::load(guild_id.get() as i64, &data.db).await;
+        db::repositories::join_request_stat::record_denial(
+            channel_id.get() as i64,
+            requester_id.get() as i64,
+            crate::time::now_unix(),
+            policy.denial_window_secs,
+            &data.db,
+        )
+        .await?;
+        format!(
+            "❌ <@{requester_id}> was denied by <@{}>.",
+            interaction.user.id
+        )
+    };
+
+    interaction
+        .create_response(
+            ctx,
+            serenity::builder::CreateInteractionResponse::UpdateMessage(
+                serenity::builder::CreateInteractionResponseMessage::new()
+                    .content(content)
+                    .components(vec![]),
+            ),
+        )
+        .await?;
+
+    tracing::debug!("Join request for {channel_id} resolved for user {requester_id}");
+    Ok(())
+}
+
+/// Periodically expires prompts past their deadline, including ones left over from
+/// before a restart.
+pub fn spawn_expiry_sweep(http: Arc<serenity::Http>, db: DatabaseConnection) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(SWEEP_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            if let Err(e) = expire_due(&http, &db).await {
+                tracing::warn!("Join-request sweep failed: {e}");
+            }
+        }
+    });
+}
+
+async fn expire_due(http: &serenity::Http, db: &DatabaseConnection) -> Result<(), crate::Error> {
+    for request in db::repositories::join_request::list_expired(crate::time::now_unix(), db).await?
+    {
+        if !db::repositories::join_request::delete(request.channel_id, request.requester_id, db)
+            .await?
+        {
+            continue; // answered in the meantime
+        }
+        let _ = serenity::ChannelId::new(request.channel_id as u64)
+            .edit_message(
+                http,
+                serenity::MessageId::new(request.message_id as u64),
+                serenity::builder::EditMessage::new()
+                    .content(format!(
+                        "~~<@{}> wants to join.~~ Request expired.",
+                        request.requester_id
+                    ))
+                    .components(vec![]),
+            )
+            .await;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_ids_round_trip() {
+        let channel = serenity::ChannelId::new(123);
+        let user = serenity::UserId::new(456);
+        assert_eq!(
+            parse_custom_id(&custom_id(true, channel, user)),
+            Some((true, channel, user))
+        );
+        assert_eq!(
+            parse_custom_id(&custom_id(false, channel, user)),
+            Some((false, channel, user))
+        );
+    }
+
+    fn stat(
+        last_requested_at: i64,
+        denials: i64,
+        first_denied_at: i64,
+    ) -> db::entities::join_request_stat::Model {
+        db::entities::join_request_stat::Model {
+            channel_id: 1,
+            requester_id: 2,
+            last_requested_at,
+            denials,
+            first_denied_at,
+        }
+    }
+
+    #[test]
+    fn first_request_opens_a_prompt() {
+        assert_eq!(gate(&Policy::default(), None, None, 1_000), Gate::Open);
+    }
+
+    #[test]
+    fn open_prompt_absorbs_repeat_requests() {
+        let s = stat(1_000, 0, 0);
+        assert_eq!(
+            gate(&Policy::default(), Some(&s), Some(5_300), 5_000),
+            Gate::Pending { until: 5_300 }
+        );
+    }
+
+    #[test]
+    fn cooldown_runs_from_the_last_prompt() {
+        let policy = Policy::default();
+        let s = stat(1_000, 0, 0);
+        assert_eq!(
+            gate(&policy, Some(&s), None, 1_059),
+            Gate::CoolingDown { until: 1_060 }
+        );
+        assert_eq!(gate(&policy, Some(&s), None, 1_060), Gate::Open);
+    }
+
+    #[test]
+    fn repeated_denials_auto_deny_until_the_window_ends() {
+        let policy = Policy::default();
+        let s = stat(1_000, 3, 1_000);
+        assert_eq!(
+            gate(&policy, Some(&s), None, 2_000),
+            Gate::AutoDeny { until: 4_600 }
+        );
+        // Auto-denial wins even over an open prompt.
+        assert_eq!(
+            gate(&policy, Some(&s), Some(2_300), 2_000),
+            Gate::AutoDeny { until: 4_600 }
+        );
+        assert_eq!(gate(&policy, Some(&s), None, 1_000 + 3600), Gate::Open);
+
+        let below = stat(1_000, 2, 1_000);
+        assert_eq!(gate(&policy, Some(&below), None, 2_000), Gate::Open);
+    }
+
+    #[test]
+    fn turned_away_requesters_are_told_when_to_retry() {
+        assert_eq!(Gate::Open.notice(1_000), None);
+        let notice = Gate::CoolingDown { until: 1_061 }.notice(1_000).unwrap();
+        assert!(notice.contains("2 minute(s)"), "{notice}");
+        let notice = Gate::AutoDeny { until: 4_600 }.notice(1_000).unwrap();
+        assert!(notice.contains("60 minute(s)"), "{notice}");
+    }
+
+    #[test]
+    fn zero_max_denials_disables_auto_deny() {
+        let policy = Policy {
+            max_denials: 0,
+            ..Policy::default()
+        };
+        let s = stat(0, 10, 1_000);
+        assert_eq!(gate(&policy, Some(&s), None, 1_001), Gate::Open);
+    }
+
+    #[test]
+    fn foreign_or_malformed_ids_are_not_join_requests() {
+        assert_eq!(parse_custom_id("panel:rename:1"), None);
+        assert_eq!(parse_custom_id("join_allow:123"), None);
+        assert_eq!(parse_custom_id("join_allow:abc:456"), None);
+        assert_eq!(parse_custom_id("join_allow:0:456"), None);
+        assert_eq!(parse_custom_id("join_deny:123:456:789"), None);
+    }
+}
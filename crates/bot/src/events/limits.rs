@@ -0,0 +1,400 @@
+//! Caps on how many temp channels a guild, a trigger and a member can have at once.
+//!
+//! Checked in `on_join` before a channel is created. At a cap the member is moved into
+//! an existing channel from the same trigger when the guild allows it and one has room,
+//! otherwise told why by DM (or in the trigger's text chat if their DMs are closed) and
+//! disconnected, so they don't sit in the trigger retrying. Fill-first triggers use the
+//! same channel picking ([`busiest_open`]) before a channel would be created at all.
+
+use poise::serenity_prelude::{self as serenity, Permissions};
+
+use db::DatabaseConnection;
+
+use crate::{permissions::PermissionResultExt, Data};
+
+/// A guild's caps, set with `/config channel-limits`; guilds that never ran it use
+/// [`Policy::default`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Policy {
+    pub max_per_guild: Option<i64>,
+    pub max_per_user: Option<i64>,
+    pub move_when_full: bool,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self {
+            max_per_guild: None,
+            max_per_user: None,
+            move_when_full: true,
+        }
+    }
+}
+
+impl From<db::entities::channel_limit_policy::Model> for Policy {
+    fn from(p: db::entities::channel_limit_policy::Model) -> Self {
+        Self {
+            max_per_guild: p.max_per_guild,
+            max_per_user: p.max_per_user,
+            move_when_full: p.move_when_full,
+        }
+    }
+}
+
+impl Policy {
+    pub async fn load(guild_id: i64, db: &DatabaseConnection) -> Self {
+        let get = db::repositories::channel_limit_policy::get(guild_id, db);
+        crate::policy::load_or_default("channel limits", guild_id, get).await
+    }
+}
+
+/// The cap a new channel would exceed, with its value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cap {
+    Guild(i64),
+    Trigger(i64),
+    User(i64),
+}
+
+impl Cap {
+    fn explain(self) -> String {
+        match self {
+            Cap::Guild(max) => {
+                format!("This server already has its maximum of {max} temp channels.")
+            }
+            Cap::Trigger(max) => format!("This trigger already has its maximum of {max} channels."),
+            Cap::User(max) => {
+                format!("You already created {max} temp channel(s), the most allowed here.")
+            }
+        }
+    }
+}
+
+/// Live channel counts a new channel would add to.
+#[derive(Clone, Copy, Debug, Default)]
+struct Counts {
+    guild: u64,
+    trigger: u64,
+    user: u64,
+}
+
+/// Pure check: the first cap already reached, widest first.
+fn exceeded(counts: Counts, policy: &Policy, trigger_max: Option<i64>) -> Option<Cap> {
+    let reached = |max: Option<i64>, count: u64| max.filter(|&max| count as i64 >= max);
+    reached(policy.max_per_guild, counts.guild)
+        .map(Cap::Guild)
+        .or_else(|| reached(trigger_max, counts.trigger).map(Cap::Trigger))
+        .or_else(|| reached(policy.max_per_user, counts.user).map(Cap::User))
+}
+
+/// Checks every cap for a channel `user_id` would create from `trigger`.
+pub async fn check(
+    trigger: &db::entities::primary_channel::Model,
+    user_id: serenity::UserId,
+    db: &DatabaseConnection,
+) -> Result<Option<Cap>, crate::Error> {
+    let policy = Policy::load(trigger.guild_id, db).await;
+    let counts = Counts {
+        guild: db::repositories::temporary_channel::count_by_guild(trigger.guild_id, db).await?,
+        trigger: db::repositories::temporary_channel::count_by_primary_channel(trigger.id, db)
+            .await?,
+        user: db::repositories::temporary_channel::count_by_creator(
+            trigger.guild_id,
+            user_id.get() as i64,
+            db,
+        )
+        .await?,
+    };
+    Ok(exceeded(counts, &policy, trigger.max_channels))
+}
+
+/// An existing channel from the trigger, as seen from the member being placed.
+#[derive(Clone, Debug)]
+pub(crate) struct Candidate {
+    channel_id: serenity::ChannelId,
+    owned: bool,
+    /// Neither private nor hidden, so anyone may be moved in.
+    open: bool,
+    /// The channel denies the member CONNECT, e.g. after `/vban` or `/reject`. Moving
+    /// them in would skip that check, so such channels are never picked.
+    barred: bool,
+    members: usize,
+    user_limit: Option<u32>,
+}
+
+impl Candidate {
+    /// The member may be moved in: the channel doesn't bar them and has a free slot.
+    /// Channels without a user limit always have room.
+    fn admits(&self) -> bool {
+        !self.barred
+            && self
+                .user_limit
+                .is_none_or(|limit| self.members < limit as usize)
+    }
+}
+
+/// Pure pick: the open channel with a free slot that has the most people in it, so
+/// channels fill up one at a time. Channels that bar the member are skipped.
+pub(crate) fn busiest_open(candidates: &[Candidate]) -> Option<serenity::ChannelId> {
+    candidates
+        .iter()
+        .filter(|c| c.open && c.admits())
+        .max_by_key(|c| c.members)
+        .map(|c| c.channel_id)
+}
+
+/// Pure pick: the member's own channel first if it has room (they may just have
+/// dropped out of it), then [`busiest_open`].
+fn pick_existing(candidates: &[Candidate]) -> Option<serenity::ChannelId> {
+    candidates
+        .iter()
+        .find(|c| c.owned && c.admits())
+        .map(|c| c.channel_id)
+        .or_else(|| busiest_open(candidates))
+}
+
+/// `trigger`'s live channels as placement candidates for `user_id`, per the cache.
+pub(crate) async fn candidates(
+    ctx: &serenity::Context,
+    trigger: &db::entities::primary_channel::Model,
+    guild_id: serenity::GuildId,
+    user_id: serenity::UserId,
+    db: &DatabaseConnection,
+) -> Result<Vec<Candidate>, crate::Error> {
+    let records =
+        db::repositories::temporary_channel::list_by_primary_channel(trigger.id, db).await?;
+    Ok(ctx
+        .cache
+        .guild(guild_id)
+        .map(|g| {
+            let roles = g
+                .members
+                .get(&user_id)
+                .map(|m| m.roles.as_slice())
+                .unwrap_or_default();
+            records
+                .iter()
+                .filter_map(|record| {
+                    let channel_id = serenity::ChannelId::new(record.id as u64);
+                    let channel = g.channels.get(&channel_id)?;
+                    Some(Candidate {
+                        channel_id,
+                        owned: record.owner_id == Some(user_id.get() as i64),
+                        open: record.join_channel_id.is_none() && !record.hidden,
+                        barred: crate::privacy::connect_denied(
+                            &channel.permission_overwrites,
+                            user_id,
+                            roles,
+                        ),
+                        members: g
+                            .voice_states
+                            .values()
+                            .filter(|vs| vs.channel_id == Some(channel_id))
+                            .count(),
+                        user_limit: channel.user_limit.filter(|&l| l > 0),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Moves `user_id` into the existing `channel_id`, first letting them see it if hidden.
+pub(crate) async fn move_into(
+    ctx: &serenity::Context,
+    guild_id: serenity::GuildId,
+    user_id: serenity::UserId,
+    channel_id: serenity::ChannelId,
+    db: &DatabaseConnection,
+) -> Result<(), crate::Error> {
+    crate::privacy::show_if_hidden(&ctx.cache, &ctx.http, guild_id, channel_id, user_id, db)
+        .await?;
+    guild_id
+        .move_member(ctx, user_id, channel_id)
+        .await
+        .requires(&[Permissions::MOVE_MEMBERS])?;
+    Ok(())
+}
+
+/// Handles a member who hit `cap`: moves them into an existing channel from the trigger
+/// if the policy allows and one fits, otherwise explains and disconnects them.
+pub async fn turn_away(
+    ctx: &serenity::Context,
+    trigger: &db::entities::primary_channel::Model,
+    guild_id: serenity::GuildId,
+    user_id: serenity::UserId,
+    cap: Cap,
+    data: &Data,
+) -> Result<(), crate::Error> {
+    if Policy::load(trigger.guild_id, &data.db)
+        .await
+        .move_when_full
+    {
+        let candidates = candidates(ctx, trigger, guild_id, user_id, &data.db).await?;
+        if let Some(channel_id) = pick_existing(&candidates) {
+            move_into(ctx, guild_id, user_id, channel_id, &data.db).await?;
+            tracing::debug!("{user_id} hit {cap:?}; moved into existing channel {channel_id}");
+            return Ok(());
+        }
+    }
+
+    refuse(
+        ctx,
+        serenity::ChannelId::new(trigger.id as u64),
+        guild_id,
+        user_id,
+        &cap.explain(),
+    )
+    .await?;
+    tracing::debug!("{user_id} hit {cap:?}; no channel created");
+    Ok(())
+}
+
+/// Tells the member why they were turned away, by DM or in `fallback`'s text chat if
+/// their DMs are closed, and disconnects them from voice.
+pub(crate) async fn refuse(
+    ctx: &serenity::Context,
+    fallback: serenity::ChannelId,
+    guild_id: serenity::GuildId,
+    user_id: serenity::UserId,
+    notice: &str,
+) -> Result<(), crate::Error> {
+    let dm = match user_id.create_dm_channel(ctx).await {
+        Ok(dm) => dm.say(ctx, notice).await.map(|_| ()),
+        Err(e) => Err(e),
+    };
+    if dm.is_err() {
+        let _ = fallback.say(ctx, format!("<@{user_id}> {notice}")).await;
+    }
+    guild_id
+        .disconnect_member(ctx, user_id)
+        .await
+        .requires(&[Permissions::MOVE_MEMBERS])?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(max_per_guild: Option<i64>, max_per_user: Option<i64>) -> Policy {
+        Policy {
+            max_per_guild,
+            max_per_user,
+            move_when_full: true,
+        }
+    }
+
+    fn counts(guild: u64, trigger: u64, user: u64) -> Counts {
+        Counts {
+            guild,
+            trigger,
+            user,
+        }
+    }
+
+    fn candidate(
+        id: u64,
+        owned: bool,
+        open: bool,
+        members: usize,
+        limit: Option<u32>,
+    ) -> Candidate {
+        Candidate {
+            channel_id: serenity::ChannelId::new(id),
+            owned,
+            open,
+            barred: false,
+            members,
+            user_limit: limit,
+        }
+    }
+
+    #[test]
+    fn no_caps_never_block() {
+        assert_eq!(exceeded(counts(499, 99, 9), &Policy::default(), None), None);
+    }
+
+    #[test]
+    fn each_cap_applies_once_reached() {
+        let p = policy(Some(10), Some(2));
+        assert_eq!(exceeded(counts(9, 4, 1), &p, Some(5)), None);
+        assert_eq!(
+            exceeded(counts(10, 4, 1), &p, Some(5)),
+            Some(Cap::Guild(10))
+        );
+        assert_eq!(
+            exceeded(counts(9, 5, 1), &p, Some(5)),
+            Some(Cap::Trigger(5))
+        );
+        assert_eq!(exceeded(counts(9, 4, 2), &p, Some(5)), Some(Cap::User(2)));
+    }
+
+    #[test]
+    fn the_guild_cap_is_reported_before_narrower_ones() {
+        let p = policy(Some(10), Some(1));
+        assert_eq!(
+            exceeded(counts(10, 5, 1), &p, Some(5)),
+            Some(Cap::Guild(10))
+        );
+    }
+
+    #[test]
+    fn own_channel_is_preferred_even_when_private() {
+        let picked = pick_existing(&[
+            candidate(1, false, true, 3, None),
+            candidate(2, true, false, 0, None),
+        ]);
+        assert_eq!(picked, Some(serenity::ChannelId::new(2)));
+    }
+
+    #[test]
+    fn own_channel_is_skipped_when_full_or_barred() {
+        let full = candidate(1, true, false, 2, Some(2));
+        let barred = Candidate {
+            barred: true,
+            ..candidate(2, true, true, 0, None)
+        };
+        let picked = pick_existing(&[full, barred, candidate(3, false, true, 1, None)]);
+        assert_eq!(picked, Some(serenity::ChannelId::new(3)));
+    }
+
+    #[test]
+    fn picks_the_busiest_open_channel_with_room() {
+        let picked = pick_existing(&[
+            candidate(1, false, true, 2, None),
+            candidate(2, false, true, 4, Some(4)),
+            candidate(3, false, false, 5, None),
+            candidate(4, false, true, 3, Some(5)),
+        ]);
+        assert_eq!(picked, Some(serenity::ChannelId::new(4)));
+    }
+
+    #[test]
+    fn busiest_open_ignores_ownership() {
+        let picked = busiest_open(&[
+            candidate(1, true, false, 0, None),
+            candidate(2, false, true, 1, Some(2)),
+        ]);
+        assert_eq!(picked, Some(serenity::ChannelId::new(2)));
+    }
+
+    #[test]
+    fn busiest_open_skips_channels_that_bar_the_member() {
+        let banned_from = Candidate {
+            barred: true,
+            ..candidate(1, false, true, 3, None)
+        };
+        let picked = busiest_open(&[banned_from, candidate(2, false, true, 1, None)]);
+        assert_eq!(picked, Some(serenity::ChannelId::new(2)));
+    }
+
+    #[test]
+    fn nothing_fits() {
+        assert_eq!(
+            pick_existing(&[candidate(1, false, true, 2, Some(2))]),
+            None
+        );
+        assert_eq!(pick_existing(&[]), None);
+    }
+}
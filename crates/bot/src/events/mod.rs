@@ -4,9 +4,19 @@ use poise::serenity_prelude as serenity;
 
 use crate::{Data, Error};
 
+pub(crate) mod grace;
+pub(crate) mod idle;
+pub(crate) mod join_rate;
+pub(crate) mod join_request;
+pub(crate) mod limits;
+mod panel;
+mod presence;
+pub(crate) mod removal;
 mod voice_state;
 pub(crate) mod xp;
 
+pub(crate) use voice_state::recalculate_name;
+
 pub async fn handle(
     ctx: &serenity::Context,
     event: &serenity::FullEvent,
@@ -17,11 +27,45 @@ pub async fn handle(
         serenity::FullEvent::VoiceStateUpdate { old, new } => {
             voice_state::handle(ctx, old.clone(), new.clone(), data).await;
         }
-        // On reconnect (not a new guild join) clean up stale and empty temp channels.
-        // At this point the guild's voice_states reflect the current Discord state.
-        serenity::FullEvent::GuildCreate { guild, is_new } if *is_new != Some(true) => {
-            if let Err(e) = startup_cleanup(ctx, guild, data).await {
-                tracing::error!("Startup cleanup for guild {}: {e}", guild.id);
+        serenity::FullEvent::InteractionCreate {
+            interaction: serenity::Interaction::Component(component),
+        } => {
+            if let Err(e) = dispatch_component(ctx, component, data).await {
+                tracing::error!("component interaction {}: {e}", component.data.custom_id);
+            }
+        }
+        serenity::FullEvent::InteractionCreate {
+            interaction: serenity::Interaction::Modal(modal),
+        } => {
+            let result = match custom_id_prefix(&modal.data.custom_id) {
+                panel::PREFIX => panel::handle_modal(ctx, modal, data).await,
+                _ => Ok(()),
+            };
+            if let Err(e) = result {
+                tracing::error!("modal submit {}: {e}", modal.data.custom_id);
+            }
+        }
+        serenity::FullEvent::PresenceUpdate { new_data } => {
+            presence::handle(ctx, new_data, data).await;
+        }
+        serenity::FullEvent::GuildCreate { guild, is_new } => {
+            removal::on_guild_create(guild.id, &data.db).await;
+            // On reconnect (not a new guild join) clean up stale and empty temp channels.
+            // At this point the guild's voice_states reflect the current Discord state.
+            if *is_new != Some(true) {
+                if let Err(e) = startup_cleanup(ctx, guild, data).await {
+                    tracing::error!("Startup cleanup for guild {}: {e}", guild.id);
+                }
+            }
+        }
+        serenity::FullEvent::ChannelDelete { channel, .. } => {
+            if let Err(e) = removal::on_channel_delete(ctx, channel, data).await {
+                tracing::error!("Channel delete {}: {e}", channel.id);
+            }
+        }
+        serenity::FullEvent::GuildDelete { incomplete, .. } => {
+            if let Err(e) = removal::on_guild_delete(incomplete, data).await {
+                tracing::error!("Guild delete {}: {e}", incomplete.id);
             }
         }
         _ => {}
@@ -29,6 +73,29 @@ pub async fn handle(
     Ok(())
 }
 
+/// The part of a custom id before the first `:`, which names the feature that owns it.
+fn custom_id_prefix(custom_id: &str) -> &str {
+    custom_id.split(':').next().unwrap_or_default()
+}
+
+/// Routes a button or select-menu interaction to its feature by custom-id prefix.
+/// Components from before a restart arrive here too, so handlers must not rely on
+/// in-memory state.
+async fn dispatch_component(
+    ctx: &serenity::Context,
+    component: &serenity::ComponentInteraction,
+    data: &Data,
+) -> Result<(), Error> {
+    match custom_id_prefix(&component.data.custom_id) {
+        join_request::ALLOW_PREFIX | join_request::DENY_PREFIX => {
+            join_request::handle_component(ctx, component, data).await
+        }
+        panel::PREFIX => panel::handle_component(ctx, component, data).await,
+        // Paginators and other collector-driven components handle their own clicks.
+        _ => Ok(()),
+    }
+}
+
 /// Called once per guild on bot reconnect. Removes or deletes stale/empty temp channels,
 /// and awards XP for voice sessions that ended while the bot was offline.
 async fn startup_cleanup(
@@ -65,17 +132,48 @@ async fn startup_cleanup(
                     .values()
                     .any(|vs| vs.channel_id == Some(channel_id));
 
-                if !has_members {
+                if !has_members && channel.delete_at.is_some_and(|at| at > now) {
+                    // Emptied shortly before the restart; the grace sweeper deletes it
+                    // unless someone comes back first.
+                    tracing::debug!("Startup cleanup: {channel_id} is pending deletion; kept");
+                } else if !has_members {
                     crate::delete_join_channel_if_present(ctx, channel.join_channel_id).await;
                     let _ = channel_id.delete(ctx).await;
-                    db::repositories::temporary_channel::delete(channel.id, &data.db).await?;
-                    crate::metrics::temp_channel_deleted();
+                    // The ChannelDelete event may have dropped the row already.
+                    if db::repositories::temporary_channel::delete(channel.id, &data.db).await? {
+                        crate::metrics::temp_channel_deleted();
+                    }
                     removed += 1;
                     tracing::debug!(
                         "Startup cleanup: deleted empty temp channel {channel_id} in guild {guild_id}"
                     );
                 } else {
                     live_temp_ids.insert(channel.id as u64);
+                    if channel.delete_at.is_some() {
+                        // Someone rejoined while the bot was offline.
+                        db::repositories::temporary_channel::set_delete_at(
+                            channel.id, None, &data.db,
+                        )
+                        .await?;
+                    }
+                    // Overwrites may have been edited by hand while the bot was offline;
+                    // put @everyone back in line with the recorded lock and hidden state.
+                    let private = channel.join_channel_id.is_some();
+                    if private || channel.hidden {
+                        if let Err(e) = crate::privacy::sync_everyone(
+                            &ctx.http,
+                            guild_id,
+                            channel_id,
+                            private,
+                            channel.hidden,
+                        )
+                        .await
+                        {
+                            tracing::warn!(
+                                "Startup cleanup: failed to restore permissions on {channel_id}: {e}"
+                            );
+                        }
+                    }
                 }
             }
         }
@@ -89,63 +187,23 @@ async fn startup_cleanup(
 
     // Award XP for sessions that ended while the bot was offline, then discard them.
     // Sessions belonging to users still in a live temp channel are preserved.
-    let sessions = db::repositories::voice_session::list_by_guild(gid, &data.db).await?;
-
-    if !sessions.is_empty() {
-        // Users currently sitting in a temp channel — their session stays open.
-        let users_in_temp: HashSet<i64> = guild
-            .voice_states
-            .values()
-            .filter(|vs| {
-                vs.channel_id
-                    .map(|id| live_temp_ids.contains(&id.get()))
-                    .unwrap_or(false)
-            })
-            .map(|vs| vs.user_id.get() as i64)
-            .collect();
-
-        const MAX_DOWNTIME_XP: i64 = 4 * 3600; // 4 h cap
-        const MIN_SESSION_SECS: i64 = 60;
-
-        let mut recovered = 0u32;
-        for session in sessions {
-            if users_in_temp.contains(&session.user_id) {
-                continue;
-            }
-            // User left (or moved to a non-temp channel) while bot was offline.
-            let elapsed = (now - session.joined_at).clamp(0, MAX_DOWNTIME_XP);
-            if elapsed >= MIN_SESSION_SECS {
-                if let Err(e) = db::repositories::user_profile::add_xp(
-                    session.user_id,
-                    gid,
-                    elapsed,
-                    elapsed,
-                    &data.db,
-                )
-                .await
-                {
-                    tracing::warn!(
-                        "Startup cleanup: add_xp failed for user {}: {e}",
-                        session.user_id
-                    );
-                }
-            }
-            if let Err(e) =
-                db::repositories::voice_session::end(session.user_id, gid, &data.db).await
-            {
-                tracing::warn!(
-                    "Startup cleanup: end session failed for user {}: {e}",
-                    session.user_id
-                );
-            }
-            recovered += 1;
-        }
-
-        if recovered > 0 {
-            tracing::info!(
-                "Startup cleanup for guild {guild_id}: recovered XP for {recovered} offline session(s)"
-            );
-        }
+    let users_in_temp: HashSet<i64> = guild
+        .voice_states
+        .values()
+        .filter(|vs| {
+            vs.channel_id
+                .map(|id| live_temp_ids.contains(&id.get()))
+                .unwrap_or(false)
+        })
+        .map(|vs| vs.user_id.get() as i64)
+        .collect();
+    // `channels` was read before the loop above deleted rows, so history still gets
+    // the trigger and game of channels that are gone now.
+    let recovered = xp::recover_offline_sessions(gid, &channels, &users_in_temp, now, data).await?;
+    if recovered > 0 {
+        tracing::info!(
+            "Startup cleanup for guild {guild_id}: recovered XP for {recovered} offline session(s)"
+        );
     }
 
     Ok(())
@@ -0,0 +1,365 @@
+//! The control panel posted in each temp channel's text area.
+//!
+//! Every button, select menu and modal carries its action and channel in its custom id
+//! (`panel:<action>:<channel id>`), so the panel needs no in-memory state and keeps
+//! working after a restart. Only the channel's owner may use it.
+
+use poise::serenity_prelude::{self as serenity, Context, Permissions};
+
+use crate::{permissions::PermissionResultExt, Data};
+
+pub const PREFIX: &str = "panel";
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Action {
+    /// Button opening the rename modal; also the modal's own id.
+    Rename,
+    /// Button opening the user-limit modal; also the modal's own id.
+    Limit,
+    /// Button toggling between private and public.
+    Privacy,
+    /// Button toggling hidden mode.
+    Hide,
+    /// Button offering a member picker for the new owner.
+    Transfer,
+    /// The member picker itself.
+    TransferTo,
+}
+
+impl Action {
+    const ALL: [Action; 6] = [
+        Self::Rename,
+        Self::Limit,
+        Self::Privacy,
+        Self::Hide,
+        Self::Transfer,
+        Self::TransferTo,
+    ];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Rename => "rename",
+            Self::Limit => "limit",
+            Self::Privacy => "privacy",
+            Self::Hide => "hide",
+            Self::Transfer => "transfer",
+            Self::TransferTo => "transfer_to",
+        }
+    }
+}
+
+fn custom_id(action: Action, channel_id: serenity::ChannelId) -> String {
+    format!("{PREFIX}:{}:{channel_id}", action.as_str())
+}
+
+fn parse_custom_id(id: &str) -> Option<(Action, serenity::ChannelId)> {
+    let mut parts = id.split(':');
+    if parts.next()? != PREFIX {
+        return None;
+    }
+    let name = parts.next()?;
+    let action = Action::ALL.into_iter().find(|a| a.as_str() == name)?;
+    let channel_id = parts.next()?.parse().ok().filter(|&id| id != 0)?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((action, serenity::ChannelId::new(channel_id)))
+}
+
+/// Posts the panel in `channel_id`'s text area.
+pub async fn post(
+    http: &serenity::Http,
+    channel_id: serenity::ChannelId,
+) -> Result<(), crate::Error> {
+    let button = |action, label: &str, style| {
+        serenity::builder::CreateButton::new(custom_id(action, channel_id))
+            .label(label)
+            .style(style)
+    };
+    channel_id
+        .send_message(
+            http,
+            serenity::builder::CreateMessage::new()
+                .content("🎛️ Channel controls — only the channel's owner can use them.")
+                .components(vec![serenity::builder::CreateActionRow::Buttons(vec![
+                    button(Action::Rename, "Rename", serenity::ButtonStyle::Primary),
+                    button(Action::Limit, "Limit", serenity::ButtonStyle::Primary),
+                    button(
+                        Action::Privacy,
+                        "Private / Public",
+                        serenity::ButtonStyle::Secondary,
+                    ),
+                    button(
+                        Action::Hide,
+                        "Hide / Unhide",
+                        serenity::ButtonStyle::Secondary,
+                    ),
+                    button(Action::Transfer, "Transfer", serenity::ButtonStyle::Danger),
+                ])]),
+        )
+        .await
+        .requires(&[Permissions::SEND_MESSAGES])?;
+    Ok(())
+}
+
+fn ephemeral(content: impl Into<String>) -> serenity::builder::CreateInteractionResponse {
+    serenity::builder::CreateInteractionResponse::Message(
+        serenity::builder::CreateInteractionResponseMessage::new()
+            .content(content)
+            .ephemeral(true),
+    )
+}
+
+/// Checks that `channel_id` is still a temp channel owned by `user_id`. Returns the
+/// channel's row, or the reason to show the user.
+async fn require_owner(
+    channel_id: serenity::ChannelId,
+    user_id: serenity::UserId,
+    data: &Data,
+) -> Result<Result<db::entities::temporary_channel::Model, &'static str>, crate::Error> {
+    let Some(record) =
+        db::repositories::temporary_channel::find(channel_id.get() as i64, &data.db).await?
+    else {
+        return Ok(Err("This channel no longer exists."));
+    };
+    if record.owner_id != Some(user_id.get() as i64) {
+        return Ok(Err("Only the channel owner can use these controls."));
+    }
+    Ok(Ok(record))
+}
+
+/// Handles a panel button or select menu.
+pub async fn handle_component(
+    ctx: &Context,
+    interaction: &serenity::ComponentInteraction,
+    data: &Data,
+) -> Result<(), crate::Error> {
+    let Some((action, channel_id)) = parse_custom_id(&interaction.data.custom_id) else {
+        return Ok(());
+    };
+    let Some(guild_id) = interaction.guild_id else {
+        return Ok(());
+    };
+    let record = match require_owner(channel_id, interaction.user.id, data).await? {
+        Ok(record) => record,
+        Err(reason) => {
+            interaction.create_response(ctx, ephemeral(reason)).await?;
+            return Ok(());
+        }
+    };
+
+    let response = match action {
+        Action::Rename => serenity::builder::CreateInteractionResponse::Modal(
+            serenity::builder::CreateModal::new(custom_id(Action::Rename, channel_id), "Rename")
+                .components(vec![serenity::builder::CreateActionRow::InputText(
+                    serenity::builder::CreateInputText::new(
+                        serenity::InputTextStyle::Short,
+                        "New channel name",
+                        "name",
+                    )
+                    .max_length(100),
+                )]),
+        ),
+        Action::Limit => serenity::builder::CreateInteractionResponse::Modal(
+            serenity::builder::CreateModal::new(custom_id(Action::Limit, channel_id), "User limit")
+                .components(vec![serenity::builder::CreateActionRow::InputText(
+                    serenity::builder::CreateInputText::new(
+                        serenity::InputTextStyle::Short,
+                        "Maximum number of users (0 = unlimited)",
+                        "count",
+                    )
+                    .max_length(2),
+                )]),
+        ),
+        Action::Privacy | Action::Hide => {
+            let Some(_guard) =
+                crate::commands::voice::try_lock_channel(&data.channel_locks, channel_id)
+            else {
+                interaction.create_response(ctx, ephemeral(BUSY)).await?;
+                return Ok(());
+            };
+            // Locking creates a channel and hiding writes an overwrite per member, which
+            // can outlast Discord's 3-second deadline for the first response.
+            interaction.defer_ephemeral(ctx).await?;
+            let reply = match toggle(ctx, action, guild_id, channel_id, &record, data).await {
+                Ok(reply) => reply,
+                Err(e) => {
+                    let _ = interaction
+                        .edit_response(
+                            ctx,
+                            serenity::builder::EditInteractionResponse::new().content(
+                                "Couldn't update the channel — the bot may be missing \
+                                 permissions. Try `/private` or `/hide` for details.",
+                            ),
+                        )
+                        .await;
+                    return Err(e);
+                }
+            };
+            interaction
+                .edit_response(
+                    ctx,
+                    serenity::builder::EditInteractionResponse::new().content(reply),
+                )
+                .await?;
+            return Ok(());
+        }
+        Action::Transfer => serenity::builder::CreateInteractionResponse::Message(
+            serenity::builder::CreateInteractionResponseMessage::new()
+                .content("Who should own this channel?")
+                .components(vec![serenity::builder::CreateActionRow::SelectMenu(
+                    serenity::builder::CreateSelectMenu::new(
+                        custom_id(Action::TransferTo, channel_id),
+                        serenity::builder::CreateSelectMenuKind::User {
+                            default_users: None,
+                        },
+                    )
+                    .placeholder("Pick a member in the channel"),
+                )])
+                .ephemeral(true),
+        ),
+        Action::TransferTo => {
+            let serenity::ComponentInteractionDataKind::UserSelect { values } =
+                &interaction.data.kind
+            else {
+                return Ok(());
+            };
+            let Some(&new_owner) = values.first() else {
+                return Ok(());
+            };
+            ephemeral(
+                crate::commands::voice::transfer_channel(
+                    ctx,
+                    guild_id,
+                    channel_id,
+                    interaction.user.id,
+                    new_owner,
+                    data,
+                )
+                .await?,
+            )
+        }
+    };
+    interaction.create_response(ctx, response).await?;
+    Ok(())
+}
+
+const BUSY: &str = "This channel's privacy is already being updated — try again in a moment.";
+
+/// Flips the channel between private and public, or hidden and visible, returning the
+/// reply to show.
+async fn toggle(
+    ctx: &Context,
+    action: Action,
+    guild_id: serenity::GuildId,
+    channel_id: serenity::ChannelId,
+    record: &db::entities::temporary_channel::Model,
+    data: &Data,
+) -> Result<&'static str, crate::Error> {
+    Ok(match action {
+        Action::Privacy if record.join_channel_id.is_some() => {
+            crate::privacy::unlock(&ctx.http, guild_id, channel_id, &data.db).await?;
+            "Channel is now public."
+        }
+        Action::Privacy => {
+            let bot_id = ctx.cache.current_user().id;
+            crate::privacy::lock(&ctx.http, guild_id, channel_id, bot_id, &data.db).await?;
+            "Channel is now private. Others can request to join via the \"[join ↑]\" channel."
+        }
+        Action::Hide if record.hidden => {
+            crate::privacy::unhide(&ctx.http, guild_id, channel_id, &data.db).await?;
+            "Channel is visible again."
+        }
+        _ => {
+            crate::privacy::hide(&ctx.cache, &ctx.http, guild_id, channel_id, &data.db).await?;
+            "Channel is now hidden. Only current members and permitted users can see it."
+        }
+    })
+}
+
+/// Handles a submitted rename or user-limit modal.
+pub async fn handle_modal(
+    ctx: &Context,
+    interaction: &serenity::ModalInteraction,
+    data: &Data,
+) -> Result<(), crate::Error> {
+    let Some((action, channel_id)) = parse_custom_id(&interaction.data.custom_id) else {
+        return Ok(());
+    };
+    if let Err(reason) = require_owner(channel_id, interaction.user.id, data).await? {
+        interaction.create_response(ctx, ephemeral(reason)).await?;
+        return Ok(());
+    }
+    let value = interaction
+        .data
+        .components
+        .iter()
+        .flat_map(|row| &row.components)
+        .find_map(|component| match component {
+            serenity::ActionRowComponent::InputText(input) => input.value.clone(),
+            _ => None,
+        })
+        .unwrap_or_default();
+
+    let reply = match action {
+        Action::Rename => {
+            crate::commands::voice::rename_channel(ctx, channel_id, value.trim(), data).await?
+        }
+        Action::Limit => match parse_limit(&value) {
+            Some(count) => {
+                channel_id
+                    .edit(ctx, serenity::builder::EditChannel::new().user_limit(count))
+                    .await
+                    .requires(&[Permissions::MANAGE_CHANNELS])?;
+                if count == 0 {
+                    "User limit removed.".to_string()
+                } else {
+                    format!("User limit set to **{count}**.")
+                }
+            }
+            None => "Enter a number from 0 to 99.".to_string(),
+        },
+        _ => return Ok(()),
+    };
+    interaction.create_response(ctx, ephemeral(reply)).await?;
+    Ok(())
+}
+
+/// Parses the limit modal's input: 0 (unlimited) to 99.
+fn parse_limit(input: &str) -> Option<u32> {
+    input.trim().parse().ok().filter(|&n| n <= 99)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_ids_round_trip() {
+        let channel = serenity::ChannelId::new(123);
+        for action in Action::ALL {
+            assert_eq!(
+                parse_custom_id(&custom_id(action, channel)),
+                Some((action, channel))
+            );
+        }
+    }
+
+    #[test]
+    fn foreign_or_malformed_ids_are_rejected() {
+        assert_eq!(parse_custom_id("join_allow:1:2"), None);
+        assert_eq!(parse_custom_id("panel:explode:1"), None);
+        assert_eq!(parse_custom_id("panel:rename"), None);
+        assert_eq!(parse_custom_id("panel:rename:0"), None);
+        assert_eq!(parse_custom_id("panel:rename:1:2"), None);
+    }
+
+    #[test]
+    fn limit_input_accepts_zero_to_ninety_nine() {
+        assert_eq!(parse_limit(" 0 "), Some(0));
+        assert_eq!(parse_limit("99"), Some(99));
+        assert_eq!(parse_limit("100"), None);
+        assert_eq!(parse_limit("-1"), None);
+        assert_eq!(parse_limit("five"), None);
+    }
+}
@@ -0,0 +1,28 @@
+use poise::serenity_prelude::{self as serenity, Context};
+
+use crate::Data;
+
+/// Renames the member's temp channel when their activity changes, so a channel where
+/// everyone launched a new game doesn't wait for the next join or leave. Channels named
+/// by hand with `/rename` keep their name (see [`super::voice_state::recalculate_name`]).
+/// Also records status changes to and from Idle for [`super::idle`].
+pub async fn handle(ctx: &Context, presence: &serenity::Presence, data: &Data) {
+    let Some(guild_id) = presence.guild_id else {
+        return;
+    };
+    let user_id = presence.user.id;
+
+    // The cache already holds the new presence; find the member's voice channel in it.
+    let Some(channel_id) = ctx
+        .cache
+        .guild(guild_id)
+        .and_then(|g| g.voice_states.get(&user_id).and_then(|vs| vs.channel_id))
+    else {
+        return;
+    };
+
+    super::idle::sync(ctx, guild_id, user_id, data).await;
+    if let Err(e) = super::voice_state::recalculate_name(ctx, channel_id, guild_id, data).await {
+        tracing::error!("presence update handler: {e}");
+    }
+}
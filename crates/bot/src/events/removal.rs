@@ -0,0 +1,306 @@
+//! Keeping the db in sync when channels or whole guilds disappear from under the bot.
+//!
+//! A temp channel, trigger or join-request channel deleted by hand in Discord has its
+//! rows removed or cleared straight away, instead of lingering until the next reconnect
+//! or `rustvoice cleanup`. A trigger that still has members in its temp channels keeps
+//! its row until the last of them is deleted, since removing it would cascade to theirs.
+//!
+//! When the bot is removed from a guild, open sessions are closed and temp channel rows
+//! dropped, and the rest of the guild's data is kept for its retention period (set with
+//! `/config retention`) in case the bot is invited back. The same purge trims voice
+//! session history older than the guild's history period (set with `/config history`),
+//! per-day XP totals older than the longest `/ranking` window, and trigger bans that
+//! have run out.
+
+use std::time::Duration;
+
+use poise::serenity_prelude as serenity;
+
+use db::DatabaseConnection;
+
+use crate::Data;
+
+/// Retention for guilds that never ran `/config retention`.
+pub const DEFAULT_RETENTION_DAYS: i64 = 30;
+
+/// History period for guilds that never ran `/config history`.
+pub const DEFAULT_HISTORY_DAYS: i64 = 90;
+
+const DAY_SECS: i64 = 24 * 60 * 60;
+
+/// Per-day XP totals are only summed for `/ranking` windows of up to 30 days.
+const DAILY_STATS_DAYS: i64 = 31;
+
+/// How often the purge looks for guilds whose retention period ran out.
+const PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// The guild's retention period in days, falling back to the default on a missing
+/// setting or db error. 0 deletes the guild's data as soon as the bot is removed.
+pub async fn retention_days(guild_id: i64, db: &DatabaseConnection) -> i64 {
+    match db::repositories::guild::retention_days(guild_id, db).await {
+        Ok(days) => days.unwrap_or(DEFAULT_RETENTION_DAYS),
+        Err(e) => {
+            tracing::warn!("Failed to load data retention for guild {guild_id}: {e}");
+            DEFAULT_RETENTION_DAYS
+        }
+    }
+}
+
+/// Days of voice session history the guild keeps, falling back to the default on a
+/// missing setting or db error.
+pub async fn history_days(guild_id: i64, db: &DatabaseConnection) -> i64 {
+    match db::repositories::guild::history_days(guild_id, db).await {
+        Ok(days) => days.unwrap_or(DEFAULT_HISTORY_DAYS),
+        Err(e) => {
+            tracing::warn!("Failed to load history period for guild {guild_id}: {e}");
+            DEFAULT_HISTORY_DAYS
+        }
+    }
+}
+
+/// Pure check: whether data kept since `removed_at` for `days` is due for deletion.
+fn expired(removed_at: i64, days: i64, now: i64) -> bool {
+    now >= removed_at + days.max(0) * DAY_SECS
+}
+
+/// Deletes the trigger's row once the trigger is gone from Discord and its last temp
+/// channel has been deleted. Does nothing while the guild isn't cached.
+pub(crate) async fn reap_trigger_if_deleted(
+    cache: &serenity::Cache,
+    guild_id: serenity::GuildId,
+    trigger_id: i64,
+    db: &DatabaseConnection,
+) -> Result<(), crate::Error> {
+    let trigger_channel = serenity::ChannelId::new(trigger_id as u64);
+    let Some(deleted) = cache
+        .guild(guild_id)
+        .map(|g| !g.channels.contains_key(&trigger_channel))
+    else {
+        return Ok(());
+    };
+    if deleted
+        && db::repositories::temporary_channel::count_by_primary_channel(trigger_id, db).await? == 0
+    {
+        db::repositories::primary_channel::delete(trigger_id, db).await?;
+        tracing::debug!("Removed deleted trigger {trigger_channel}");
+    }
+    Ok(())
+}
+
+pub async fn on_channel_delete(
+    ctx: &serenity::Context,
+    channel: &serenity::GuildChannel,
+    data: &Data,
+) -> Result<(), crate::Error> {
+    let id = channel.id.get() as i64;
+
+    if let Some(record) = db::repositories::temporary_channel::find(id, &data.db).await? {
+        // Discord disconnects the members too, but their voice state updates may arrive
+        // after the row is gone; close their sessions from the cache now.
+        let members: Vec<i64> = ctx
+            .cache
+            .guild(channel.guild_id)
+            .map(|g| {
+                g.voice_states
+                    .values()
+                    .filter(|vs| vs.channel_id == Some(channel.id))
+                    .map(|vs| vs.user_id.get() as i64)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let now = crate::time::now_unix();
+        for uid in members {
+            super::xp::close_session(uid, record.guild_id, now, data).await;
+        }
+        crate::delete_join_channel_if_present(ctx, record.join_channel_id).await;
+        // The bot's own deletions land here too, after or before it drops the row.
+        if db::repositories::temporary_channel::delete(id, &data.db).await? {
+            crate::metrics::temp_channel_deleted();
+            tracing::debug!("Temp channel {} was deleted in Discord", channel.id);
+        }
+        data.renames.forget(channel.id);
+        reap_trigger_if_deleted(
+            &ctx.cache,
+            channel.guild_id,
+            record.primary_channel_id,
+            &data.db,
+        )
+        .await?;
+        return Ok(());
+    }
+
+    if let Some(record) =
+        db::repositories::temporary_channel::find_by_join_channel(id, &data.db).await?
+    {
+        // The temp channel stays private; `/private` again recreates the join channel.
+        db::repositories::temporary_channel::set_join_channel(record.id, None, &data.db).await?;
+        tracing::debug!("Join channel {} of {} was deleted", channel.id, record.id);
+        return Ok(());
+    }
+
+    if db::repositories::primary_channel::exists(id, &data.db).await? {
+        on_trigger_delete(ctx, channel, data).await?;
+    }
+    Ok(())
+}
+
+/// Deletes the trigger's empty temp channels and, if none are in use, its row. Channels
+/// with members are left alone; the trigger is reaped after the last one is deleted.
+async fn on_trigger_delete(
+    ctx: &serenity::Context,
+    channel: &serenity::GuildChannel,
+    data: &Data,
+) -> Result<(), crate::Error> {
+    let id = channel.id.get() as i64;
+    let records =
+        db::repositories::temporary_channel::list_by_primary_channel(id, &data.db).await?;
+    let occupied: Vec<bool> = ctx
+        .cache
+        .guild(channel.guild_id)
+        .map(|g| {
+            records
+                .iter()
+                .map(|r| {
+                    let channel_id = serenity::ChannelId::new(r.id as u64);
+                    g.voice_states
+                        .values()
+                        .any(|vs| vs.channel_id == Some(channel_id))
+                })
+                .collect()
+        })
+        .unwrap_or_else(|| vec![true; records.len()]);
+
+    for (record, occupied) in records.iter().zip(occupied) {
+        if occupied {
+            continue;
+        }
+        if let Err(e) =
+            super::grace::delete(&ctx.cache, &ctx.http, record, &data.db, &data.renames).await
+        {
+            tracing::warn!(
+                "Failed to delete temp channel {} of deleted trigger: {e}",
+                record.id
+            );
+        }
+    }
+    // Covers a trigger that had no temp channels; otherwise the last delete above did it.
+    reap_trigger_if_deleted(&ctx.cache, channel.guild_id, id, &data.db).await?;
+    tracing::debug!("Trigger {} was deleted in Discord", channel.id);
+    Ok(())
+}
+
+/// Handles the bot leaving a guild. Outages also raise GuildDelete, with `unavailable`
+/// set, and are ignored.
+pub async fn on_guild_delete(
+    incomplete: &serenity::UnavailableGuild,
+    data: &Data,
+) -> Result<(), crate::Error> {
+    if incomplete.unavailable {
+        return Ok(());
+    }
+    let guild_id = incomplete.id;
+    let gid = guild_id.get() as i64;
+    let now = crate::time::now_unix();
+
+    for session in db::repositories::voice_session::list_by_guild(gid, &data.db).await? {
+        super::xp::close_session(session.user_id, gid, now, data).await;
+    }
+    // The channels can no longer be managed; forget them whatever happens to the rest.
+    for record in db::repositories::temporary_channel::list_by_guild(gid, &data.db).await? {
+        if db::repositories::temporary_channel::delete(record.id, &data.db).await? {
+            crate::metrics::temp_channel_deleted();
+        }
+        data.renames
+            .forget(serenity::ChannelId::new(record.id as u64));
+    }
+
+    if retention_days(gid, &data.db).await == 0 {
+        db::repositories::guild::delete(gid, &data.db).await?;
+        tracing::info!("Removed from guild {guild_id}; deleted its data");
+    } else {
+        db::repositories::guild::set_removed_at(gid, Some(now), &data.db).await?;
+        tracing::info!("Removed from guild {guild_id}; keeping its data for now");
+    }
+    Ok(())
+}
+
+/// Clears the removal mark of a guild the bot is in, e.g. after being invited back.
+pub async fn on_guild_create(guild_id: serenity::GuildId, db: &DatabaseConnection) {
+    if let Err(e) = db::repositories::guild::set_removed_at(guild_id.get() as i64, None, db).await {
+        tracing::warn!("Failed to clear removal of guild {guild_id}: {e}");
+    }
+}
+
+/// Marks guilds missing from the Ready payload as removed, for a bot kicked while it
+/// was offline. Ready lists every guild the bot is in, unavailable ones included.
+pub async fn mark_missing(
+    present: &[serenity::UnavailableGuild],
+    db: &DatabaseConnection,
+) -> Result<(), crate::Error> {
+    let now = crate::time::now_unix();
+    for gid in db::repositories::guild::list_present_ids(db).await? {
+        if present.iter().all(|g| g.id.get() as i64 != gid) {
+            db::repositories::guild::set_removed_at(gid, Some(now), db).await?;
+            tracing::info!("Guild {gid} was left while offline; keeping its data for now");
+        }
+    }
+    Ok(())
+}
+
+/// Periodically deletes the data of guilds whose retention period ran out, voice
+/// history past each guild's history period, per-day totals no ranking uses, and
+/// expired trigger bans.
+pub fn spawn_purge(db: DatabaseConnection) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PURGE_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            if let Err(e) = purge(&db).await {
+                tracing::warn!("Retention purge failed: {e}");
+            }
+        }
+    });
+}
+
+async fn purge(db: &DatabaseConnection) -> Result<(), crate::Error> {
+    let now = crate::time::now_unix();
+    for guild in db::repositories::guild::list_removed(db).await? {
+        let Some(removed_at) = guild.removed_at else {
+            continue;
+        };
+        let days = guild.retention_days.unwrap_or(DEFAULT_RETENTION_DAYS);
+        if expired(removed_at, days, now) {
+            db::repositories::guild::delete(guild.id, db).await?;
+            tracing::info!("Deleted data of guild {} after {days} day(s)", guild.id);
+        }
+    }
+    for gid in db::repositories::guild::list_present_ids(db).await? {
+        let cutoff = now - history_days(gid, db).await * DAY_SECS;
+        let deleted =
+            db::repositories::voice_session_history::delete_ended_before(gid, cutoff, db).await?;
+        if deleted > 0 {
+            tracing::debug!("Deleted {deleted} old voice session(s) in guild {gid}");
+        }
+    }
+    let today = db::repositories::user_daily_stat::day_of(now);
+    db::repositories::user_daily_stat::delete_before(today - DAILY_STATS_DAYS, db).await?;
+    db::repositories::trigger_ban::delete_expired(now, db).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn data_is_kept_for_the_retention_period() {
+        assert!(!expired(1_000, 30, 1_000 + 30 * DAY_SECS - 1));
+        assert!(expired(1_000, 30, 1_000 + 30 * DAY_SECS));
+    }
+
+    #[test]
+    fn zero_days_expires_immediately() {
+        assert!(expired(1_000, 0, 1_000));
+    }
+}
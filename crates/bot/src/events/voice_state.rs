@@ -1,7 +1,6 @@
-use std::time::Duration;
+use std::collections::HashMap;
 
 use poise::serenity_prelude::{self as serenity, ChannelType, Context, Permissions};
-use serenity::futures::StreamExt as _;
 
 use crate::{permissions::PermissionResultExt, Data};
 
@@ -19,11 +18,19 @@ pub async fn handle(
     let old_channel = old.as_ref().and_then(|o| o.channel_id);
     super::xp::handle_voice_transition(new.user_id, old_channel, new.channel_id, guild_id, data)
         .await;
+    if new.channel_id.is_some() {
+        // Catches self-deafen toggles as well as joining already deafened.
+        super::idle::sync(ctx, guild_id, new.user_id, data).await;
+    }
 
     // User left a channel
     if let Some(ref old_state) = old {
         if let Some(left_channel) = old_state.channel_id {
-            if let Err(e) = on_leave(ctx, left_channel, guild_id, data).await {
+            // Moved elsewhere: a `/vdeafen` from the channel they left ends now.
+            if new.deaf && new.channel_id.is_some_and(|c| c != left_channel) {
+                lift_channel_deafen(ctx, guild_id, new.user_id, data).await;
+            }
+            if let Err(e) = on_leave(ctx, left_channel, guild_id, new.user_id, data).await {
                 tracing::error!("voice leave handler: {e}");
             }
         }
@@ -34,12 +41,58 @@ pub async fn handle(
         if old.as_ref().and_then(|o| o.channel_id) == Some(joined_channel) {
             return; // same channel, no change
         }
+        // Reconnecting after leaving voice altogether while `/vdeafen`ed.
+        if new.deaf && old_channel.is_none() {
+            lift_channel_deafen(ctx, guild_id, new.user_id, data).await;
+        }
         if let Err(e) = on_join(ctx, joined_channel, guild_id, &new.user_id, data).await {
             tracing::error!("voice join handler: {e}");
         }
     }
 }
 
+/// Undeafens someone deafened with `/vdeafen` now that they've left that channel. Done
+/// as they leave when they move to another channel; Discord refuses to edit members who
+/// aren't connected, so someone who left voice altogether is undeafened on reconnecting.
+async fn lift_channel_deafen(
+    ctx: &Context,
+    guild_id: serenity::GuildId,
+    user_id: serenity::UserId,
+    data: &Data,
+) {
+    let result = async {
+        if db::repositories::voice_deafen::find(
+            guild_id.get() as i64,
+            user_id.get() as i64,
+            &data.db,
+        )
+        .await?
+        .is_none()
+        {
+            return Ok(());
+        }
+        guild_id
+            .edit_member(
+                ctx,
+                user_id,
+                serenity::builder::EditMember::new().deafen(false),
+            )
+            .await
+            .requires(&[Permissions::DEAFEN_MEMBERS])?;
+        db::repositories::voice_deafen::delete(
+            guild_id.get() as i64,
+            user_id.get() as i64,
+            &data.db,
+        )
+        .await?;
+        Ok::<_, crate::Error>(())
+    }
+    .await;
+    if let Err(e) = result {
+        tracing::warn!("Failed to lift channel deafen for {user_id}: {e}");
+    }
+}
+
 async fn on_join(
     ctx: &Context,
     channel_id: serenity::ChannelId,
@@ -52,38 +105,102 @@ async fn on_join(
         db::repositories::temporary_channel::find_by_join_channel(channel_id.get() as i64, &data.db)
             .await?
     {
-        handle_join_request(ctx, channel_id, temp_record, *user_id, guild_id).await?;
+        super::join_request::open(ctx, channel_id, temp_record, *user_id, guild_id, data).await?;
         return Ok(());
     }
 
-    let is_primary =
-        db::repositories::primary_channel::exists(channel_id.get() as i64, &data.db).await?;
-    if !is_primary {
-        // Not a trigger channel; check if we should rename an existing temp channel
+    let Some(trigger) =
+        db::repositories::primary_channel::find(channel_id.get() as i64, &data.db).await?
+    else {
+        // Not a trigger channel; an existing temp channel may be waiting to be deleted
+        // or need a rename.
+        super::grace::cancel(channel_id, &data.db).await;
         recalculate_name(ctx, channel_id, guild_id, data).await?;
         return Ok(());
+    };
+
+    // Fill-first triggers only create a channel once every existing one is full.
+    if trigger.fill_existing {
+        let candidates =
+            super::limits::candidates(ctx, &trigger, guild_id, *user_id, &data.db).await?;
+        if let Some(target) = super::limits::busiest_open(&candidates) {
+            super::limits::move_into(ctx, guild_id, *user_id, target, &data.db).await?;
+            tracing::debug!("Filled {user_id} into existing channel {target}");
+            return Ok(());
+        }
+    }
+    // Caps first: a capped member is moved or refused without creating anything, so
+    // it mustn't cost them a token.
+    if let Some(cap) = super::limits::check(&trigger, *user_id, &data.db).await? {
+        return super::limits::turn_away(ctx, &trigger, guild_id, *user_id, cap, data).await;
+    }
+    if !super::join_rate::admit(ctx, &trigger, guild_id, *user_id, data).await? {
+        return Ok(());
     }
 
-    // Create a new temporary voice channel in the same category
-    let parent_id = ctx
-        .http
-        .get_channel(channel_id)
-        .await
-        .ok()
-        .and_then(|c| c.guild())
-        .and_then(|gc| gc.parent_id);
+    // Create a new temporary voice channel in the trigger's configured category, or
+    // alongside the trigger if none is set.
+    let parent_id = match trigger.category_id {
+        Some(category_id) => Some(serenity::ChannelId::new(category_id as u64)),
+        None => ctx
+            .http
+            .get_channel(channel_id)
+            .await
+            .ok()
+            .and_then(|c| c.guild())
+            .and_then(|gc| gc.parent_id),
+    };
 
     let template = crate::activity::resolve_template(
         guild_id.get() as i64,
+        Some(trigger.id),
         &data.db,
         &data.default_channel_name_template,
     )
     .await;
-    let initial_name = crate::activity::render_channel_name(&template, "General");
-    let mut create = serenity::builder::CreateChannel::new(initial_name).kind(ChannelType::Voice);
+    let ordinal = db::repositories::temporary_channel::next_ordinal(trigger.id, &data.db).await?;
+    let owner_name = ctx
+        .cache
+        .guild(guild_id)
+        .and_then(|g| g.members.get(user_id).map(|m| m.display_name().to_owned()));
+    let default_name = crate::activity::render_channel_name(
+        &template,
+        &crate::template::Vars {
+            owner: owner_name.as_deref(),
+            count: 1,
+            ordinal,
+            ..Default::default()
+        },
+    );
+    let preset = db::repositories::channel_preset::get(
+        user_id.get() as i64,
+        guild_id.get() as i64,
+        &data.db,
+    )
+    .await?;
+    let max_bitrate = ctx
+        .cache
+        .guild(guild_id)
+        .map(|g| crate::presets::max_bitrate(g.premium_tier))
+        .unwrap_or(crate::presets::max_bitrate(serenity::PremiumTier::Tier0));
+    let settings = crate::presets::resolve(&trigger, preset.as_ref(), default_name, max_bitrate);
+
+    let mut create = serenity::builder::CreateChannel::new(settings.name).kind(ChannelType::Voice);
     if let Some(parent) = parent_id {
         create = create.category(parent);
     }
+    if let Some(limit) = settings.user_limit {
+        create = create.user_limit(limit);
+    }
+    if let Some(bitrate) = settings.bitrate {
+        create = create.bitrate(bitrate);
+    }
+    if let Some(region) = settings.rtc_region {
+        create = create.rtc_region(region);
+    }
+    if let Some(mode) = settings.video_quality {
+        create = create.video_quality_mode(mode);
+    }
     let temp_channel = guild_id
         .create_channel(ctx, create)
         .await
@@ -93,10 +210,20 @@ async fn on_join(
         temp_channel.id.get() as i64,
         guild_id.get() as i64,
         channel_id.get() as i64,
+        user_id.get() as i64,
+        ordinal,
         &data.db,
     )
     .await?;
     crate::metrics::temp_channel_created();
+    if preset.as_ref().is_some_and(|p| p.name.is_some()) {
+        db::repositories::temporary_channel::set_name_locked(
+            temp_channel.id.get() as i64,
+            true,
+            &data.db,
+        )
+        .await?;
+    }
 
     // Move the user to the new channel
     guild_id
@@ -104,6 +231,28 @@ async fn on_join(
         .await
         .requires(&[Permissions::MOVE_MEMBERS])?;
 
+    // Locked only after the move so the creator isn't subject to their own CONNECT deny.
+    if settings.private {
+        let bot_id = ctx.cache.current_user().id;
+        if let Err(e) =
+            crate::privacy::lock(&ctx.http, guild_id, temp_channel.id, bot_id, &data.db).await
+        {
+            tracing::warn!("Preset: failed to make {} private: {e}", temp_channel.id);
+        }
+    }
+    if let Err(e) = super::panel::post(&ctx.http, temp_channel.id).await {
+        tracing::warn!("Failed to post control panel in {}: {e}", temp_channel.id);
+    }
+    let rules =
+        db::repositories::access_rule::list(user_id.get() as i64, guild_id.get() as i64, &data.db)
+            .await?;
+    if let Err(e) = crate::privacy::apply_access_rules(&ctx.http, temp_channel.id, &rules).await {
+        tracing::warn!(
+            "Failed to apply permit/reject lists to {}: {e}",
+            temp_channel.id
+        );
+    }
+
     tracing::debug!(
         "Created temp channel {} for user {}",
         temp_channel.id,
@@ -112,169 +261,11 @@ async fn on_join(
     Ok(())
 }
 
-async fn handle_join_request(
-    ctx: &Context,
-    join_channel_id: serenity::ChannelId,
-    temp_record: db::entities::temporary_channel::Model,
-    requester_id: serenity::UserId,
-    guild_id: serenity::GuildId,
-) -> Result<(), crate::Error> {
-    let private_channel_id = serenity::ChannelId::new(temp_record.id as u64);
-
-    let allow_id = format!("join_allow:{}:{}", join_channel_id, requester_id);
-    let deny_id = format!("join_deny:{}:{}", join_channel_id, requester_id);
-
-    let msg = private_channel_id
-        .send_message(
-            ctx,
-            serenity::builder::CreateMessage::new()
-                .content(format!("<@{}> wants to join. Allow or deny?", requester_id))
-                .components(vec![serenity::builder::CreateActionRow::Buttons(vec![
-                    serenity::builder::CreateButton::new(&allow_id)
-                        .label("Allow")
-                        .style(serenity::ButtonStyle::Success),
-                    serenity::builder::CreateButton::new(&deny_id)
-                        .label("Deny")
-                        .style(serenity::ButtonStyle::Danger),
-                ])]),
-        )
-        .await
-        .requires(&[Permissions::SEND_MESSAGES])?;
-
-    // Spawn a task to collect the response without blocking the event handler.
-    let ctx = ctx.clone();
-    tokio::spawn(async move {
-        await_join_response(
-            ctx,
-            msg,
-            private_channel_id,
-            join_channel_id,
-            requester_id,
-            guild_id,
-        )
-        .await;
-    });
-
-    Ok(())
-}
-
-async fn await_join_response(
-    ctx: Context,
-    mut msg: serenity::Message,
-    private_channel_id: serenity::ChannelId,
-    join_channel_id: serenity::ChannelId,
-    requester_id: serenity::UserId,
-    guild_id: serenity::GuildId,
-) {
-    let allow_id = format!("join_allow:{}:{}", join_channel_id, requester_id);
-    let deny_id = format!("join_deny:{}:{}", join_channel_id, requester_id);
-    let msg_id = msg.id;
-    let mut stream = serenity::collector::ComponentInteractionCollector::new(&ctx)
-        .filter(move |i| i.message.id == msg_id)
-        .timeout(Duration::from_secs(120))
-        .stream();
-
-    loop {
-        match stream.next().await {
-            None => {
-                // Timeout — edit message to reflect expiry.
-                let _ = msg
-                    .edit(
-                        &ctx,
-                        serenity::builder::EditMessage::new()
-                            .content(format!(
-                                "~~<@{}> wants to join.~~ Request expired.",
-                                requester_id
-                            ))
-                            .components(vec![]),
-                    )
-                    .await;
-                break;
-            }
-            Some(interaction) => {
-                // Only members currently inside the private channel may respond.
-                let in_channel = ctx
-                    .cache
-                    .guild(guild_id)
-                    .map(|g| {
-                        g.voice_states
-                            .get(&interaction.user.id)
-                            .and_then(|vs| vs.channel_id)
-                            == Some(private_channel_id)
-                    })
-                    .unwrap_or(false);
-
-                if !in_channel {
-                    let _ = interaction
-                        .create_response(
-                            &ctx,
-                            serenity::builder::CreateInteractionResponse::Message(
-                                serenity::builder::CreateInteractionResponseMessage::new()
-                                    .content("You must be inside the private channel to respond.")
-                                    .ephemeral(true),
-                            ),
-                        )
-                        .await;
-                    continue;
-                }
-
-                if interaction.data.custom_id == allow_id {
-                    let _ = guild_id
-                        .move_member(&ctx, requester_id, private_channel_id)
-                        .await;
-                    let _ = interaction
-                        .create_response(
-                            &ctx,
-                            serenity::builder::CreateInteractionResponse::UpdateMessage(
-                                serenity::builder::CreateInteractionResponseMessage::new()
-                                    .content(format!(
-                                        "✅ <@{}> was allowed in by <@{}>.",
-                                        requester_id, interaction.user.id
-                                    ))
-                                    .components(vec![]),
-                            ),
-                        )
-                        .await;
-                } else if interaction.data.custom_id == deny_id {
-                    let _ = guild_id
-                        .edit_member(
-                            &ctx,
-                            requester_id,
-                            serenity::builder::EditMember::new().disconnect_member(),
-                        )
-                        .await;
-                    let _ = interaction
-                        .create_response(
-                            &ctx,
-                            serenity::builder::CreateInteractionResponse::UpdateMessage(
-                                serenity::builder::CreateInteractionResponseMessage::new()
-                                    .content(format!(
-                                        "❌ <@{}> was denied by <@{}>.",
-                                        requester_id, interaction.user.id
-                                    ))
-                                    .components(vec![]),
-                            ),
-                        )
-                        .await;
-                } else {
-                    continue;
-                }
-
-                tracing::debug!(
-                    "Join request via {} resolved for user {}",
-                    join_channel_id,
-                    requester_id
-                );
-                break;
-            }
-        }
-    }
-}
-
 async fn on_leave(
     ctx: &Context,
     channel_id: serenity::ChannelId,
     guild_id: serenity::GuildId,
+    user_id: serenity::UserId,
     data: &Data,
 ) -> Result<(), crate::Error> {
     let is_temp =
@@ -293,39 +284,115 @@ async fn on_leave(
     };
 
     if member_count == 0 {
-        // Also delete the join channel if one was created.
-        if let Some(record) =
+        let grace = super::grace::grace_secs(guild_id.get() as i64, &data.db).await;
+        if grace > 0 {
+            db::repositories::temporary_channel::set_delete_at(
+                channel_id.get() as i64,
+                Some(crate::time::now_unix() + grace),
+                &data.db,
+            )
+            .await?;
+            tracing::debug!("Temp channel {channel_id} is empty; deleting in {grace}s");
+        } else if let Some(record) =
             db::repositories::temporary_channel::find(channel_id.get() as i64, &data.db).await?
         {
-            crate::delete_join_channel_if_present(ctx, record.join_channel_id).await;
+            super::grace::delete(&ctx.cache, &ctx.http, &record, &data.db, &data.renames).await?;
         }
-        channel_id
-            .delete(ctx)
-            .await
-            .requires(&[Permissions::MANAGE_CHANNELS])?;
-        db::repositories::temporary_channel::delete(channel_id.get() as i64, &data.db).await?;
-        crate::metrics::temp_channel_deleted();
-        tracing::debug!("Deleted empty temp channel {}", channel_id);
     } else {
+        hand_off_ownership(ctx, channel_id, guild_id, user_id, data).await?;
         recalculate_name(ctx, channel_id, guild_id, data).await?;
     }
 
     Ok(())
 }
 
-async fn recalculate_name(
+/// If `leaver` owned `channel_id`, passes ownership to the remaining member who has
+/// been in the channel longest and announces it in the channel's text area.
+async fn hand_off_ownership(
     ctx: &Context,
     channel_id: serenity::ChannelId,
     guild_id: serenity::GuildId,
+    leaver: serenity::UserId,
     data: &Data,
 ) -> Result<(), crate::Error> {
-    let is_temp =
-        db::repositories::temporary_channel::exists(channel_id.get() as i64, &data.db).await?;
-    if !is_temp {
+    let Some(record) =
+        db::repositories::temporary_channel::find(channel_id.get() as i64, &data.db).await?
+    else {
+        return Ok(());
+    };
+    if record.owner_id != Some(leaver.get() as i64) {
+        return Ok(());
+    }
+
+    let members: Vec<i64> = match ctx.cache.guild(guild_id) {
+        Some(g) => g
+            .voice_states
+            .values()
+            .filter(|vs| vs.channel_id == Some(channel_id))
+            .filter(|vs| !g.members.get(&vs.user_id).is_some_and(|m| m.user.bot))
+            .map(|vs| vs.user_id.get() as i64)
+            .collect(),
+        None => return Ok(()),
+    };
+
+    let successor = successor(guild_id.get() as i64, &members, &data.db).await?;
+    db::repositories::temporary_channel::set_owner(channel_id.get() as i64, successor, &data.db)
+        .await?;
+
+    if let Some(new_owner) = successor {
+        let _ = channel_id
+            .say(ctx, format!("👑 <@{new_owner}> is now the channel owner."))
+            .await;
+        tracing::debug!("Ownership of {channel_id} handed from {leaver} to {new_owner}");
+    }
+    Ok(())
+}
+
+/// The member of `members` who has been in the channel longest.
+async fn successor(
+    guild_id: i64,
+    members: &[i64],
+    db: &db::DatabaseConnection,
+) -> Result<Option<i64>, crate::Error> {
+    // Each move into a temp channel opens a fresh session, so `started_at` is when the
+    // member entered this particular channel. `joined_at` won't do: checkpoints move it.
+    let started_at: HashMap<i64, i64> =
+        db::repositories::voice_session::list_by_guild(guild_id, db)
+            .await?
+            .into_iter()
+            .map(|s| (s.user_id, s.started_at))
+            .collect();
+    Ok(longest_present(members, &started_at))
+}
+
+/// Pure decision: the member with the earliest session start. Members without a
+/// recorded session rank last; ties fall back to the lowest user ID so the choice is
+/// deterministic.
+fn longest_present(members: &[i64], started_at: &HashMap<i64, i64>) -> Option<i64> {
+    members
+        .iter()
+        .copied()
+        .min_by_key(|id| (started_at.get(id).copied().unwrap_or(i64::MAX), *id))
+}
+
+/// Requests the automatic name for a temp channel from its members' activities, unless
+/// the channel's name is locked by `/rename` or a preset.
+pub(crate) async fn recalculate_name(
+    ctx: &Context,
+    channel_id: serenity::ChannelId,
+    guild_id: serenity::GuildId,
+    data: &Data,
+) -> Result<(), crate::Error> {
+    let Some(record) =
+        db::repositories::temporary_channel::find(channel_id.get() as i64, &data.db).await?
+    else {
+        return Ok(());
+    };
+    if record.name_locked {
         return Ok(());
     }
 
-    let (members, current_name) = match ctx.cache.guild(guild_id) {
+    let (members, current_name, owner_name) = match ctx.cache.guild(guild_id) {
         Some(g) => {
             let members: Vec<_> = g
                 .voice_states
@@ -338,25 +405,97 @@ async fn recalculate_name(
                 .get(&channel_id)
                 .map(|c| c.name.clone())
                 .unwrap_or_default();
-            (members, name)
+            let owner_name = record.owner_id.and_then(|id| {
+                g.members
+                    .get(&serenity::UserId::new(id as u64))
+                    .map(|m| m.display_name().to_owned())
+            });
+            (members, name, owner_name)
         }
         None => return Ok(()),
     };
 
     let template = crate::activity::resolve_template(
         guild_id.get() as i64,
+        Some(record.primary_channel_id),
         &data.db,
         &data.default_channel_name_template,
     )
     .await;
-    let new_name = crate::activity::suggested_name(&members, ctx, &template).await;
+    let facts = crate::activity::ChannelFacts {
+        owner: owner_name,
+        ordinal: record.ordinal,
+    };
+    let policy = crate::activity::NamingPolicy::load(guild_id.get() as i64, &data.db).await;
+    let suggestion =
+        crate::activity::suggested_name(&members, ctx, &template, &facts, &policy).await;
+    // Kept for the channel's voice session history.
+    if suggestion.game != record.game {
+        db::repositories::temporary_channel::set_game(record.id, suggestion.game, &data.db).await?;
+    }
 
-    if current_name != new_name {
-        channel_id
-            .edit(ctx, serenity::builder::EditChannel::new().name(&new_name))
-            .await
-            .requires(&[Permissions::MANAGE_CHANNELS])?;
+    if data
+        .renames
+        .request(channel_id, &current_name, suggestion.name)
+    {
+        crate::renamer::spawn_flusher(
+            ctx.http.clone(),
+            data.renames.clone(),
+            data.db.clone(),
+            channel_id,
+        );
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn longest_present_picks_the_earliest_joiner() {
+        let started_at = HashMap::from([(1, 300), (2, 100), (3, 200)]);
+        assert_eq!(longest_present(&[1, 2, 3], &started_at), Some(2));
+    }
+
+    #[test]
+    fn longest_present_ranks_members_without_a_session_last() {
+        let started_at = HashMap::from([(2, 500)]);
+        assert_eq!(longest_present(&[1, 2], &started_at), Some(2));
+    }
+
+    #[test]
+    fn longest_present_breaks_ties_by_lowest_user_id() {
+        let started_at = HashMap::from([(5, 100), (3, 100)]);
+        assert_eq!(longest_present(&[5, 3], &started_at), Some(3));
+    }
+
+    #[test]
+    fn longest_present_of_nobody_is_none() {
+        assert_eq!(longest_present(&[], &HashMap::new()), None);
+    }
+
+    #[tokio::test]
+    async fn successor_survives_a_checkpoint_between_joins() {
+        let db = db::connection::connect_in_memory_for_tests().await.unwrap();
+        db::repositories::guild::upsert(1, &db).await.unwrap();
+        db::repositories::voice_session::start(2, 1, 100, 1_000, &db)
+            .await
+            .unwrap();
+        db::repositories::voice_session::start(1, 1, 100, 1_100, &db)
+            .await
+            .unwrap();
+        // A checkpoint round gives both sessions the same `joined_at`.
+        for session in db::repositories::voice_session::list_all(&db)
+            .await
+            .unwrap()
+        {
+            db::repositories::voice_session::checkpoint(&session, 60, 1_400, &db)
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(successor(1, &[1, 2], &db).await.unwrap(), Some(2));
+    }
+}
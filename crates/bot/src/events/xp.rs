@@ -1,12 +1,92 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
 use poise::serenity_prelude as serenity;
 
-use crate::Data;
+use db::DatabaseConnection;
+
+use crate::{leveling, Data};
 
-const MIN_SESSION_SECS: i64 = 60;
-const DAILY_BONUS_XP: i64 = 3600;
+/// How often open sessions are credited for the time so far (see [`spawn_checkpoints`]).
+/// This is also the most XP a crash can lose, so it caps what startup cleanup credits
+/// for a session whose member left while the bot was down.
+pub(crate) const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+pub const DEFAULT_XP_PER_SECOND: f64 = 1.0;
+pub const DEFAULT_MIN_SESSION_SECS: i64 = 60;
+pub const DEFAULT_DAILY_BONUS_XP: i64 = 3600;
 // Daily window: eligible from 22 h after last award; in-window up to 26 h.
-const DAILY_EARLY_SECS: i64 = 22 * 3600;
-pub(crate) const DAILY_LATE_SECS: i64 = 26 * 3600;
+pub const DEFAULT_DAILY_EARLY_SECS: i64 = 22 * 3600;
+pub const DEFAULT_DAILY_LATE_SECS: i64 = 26 * 3600;
+
+/// A guild's XP rules, set with `/config xp`; guilds that never ran it use
+/// [`Policy::default`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Policy {
+    pub xp_per_second: f64,
+    /// Sessions shorter than this earn nothing.
+    pub min_session_secs: i64,
+    pub daily_bonus_xp: i64,
+    /// The next daily bonus can be earned this long after the last one...
+    pub daily_early_secs: i64,
+    /// ...and keeps the streak going until this long after it.
+    pub daily_late_secs: i64,
+    pub curve: leveling::Curve,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self {
+            xp_per_second: DEFAULT_XP_PER_SECOND,
+            min_session_secs: DEFAULT_MIN_SESSION_SECS,
+            daily_bonus_xp: DEFAULT_DAILY_BONUS_XP,
+            daily_early_secs: DEFAULT_DAILY_EARLY_SECS,
+            daily_late_secs: DEFAULT_DAILY_LATE_SECS,
+            curve: leveling::Curve::default(),
+        }
+    }
+}
+
+impl From<db::entities::xp_policy::Model> for Policy {
+    fn from(p: db::entities::xp_policy::Model) -> Self {
+        Self {
+            xp_per_second: p.xp_per_second,
+            min_session_secs: p.min_session_secs,
+            daily_bonus_xp: p.daily_bonus_xp,
+            daily_early_secs: p.daily_early_secs,
+            daily_late_secs: p.daily_late_secs,
+            curve: leveling::Curve {
+                base: p.level_base as f64,
+                growth: p.level_growth,
+            },
+        }
+    }
+}
+
+impl Policy {
+    pub async fn load(guild_id: i64, db: &DatabaseConnection) -> Self {
+        let get = db::repositories::xp_policy::get(guild_id, db);
+        crate::policy::load_or_default("XP policy", guild_id, get).await
+    }
+
+    /// XP earned for `secs` of active voice time.
+    pub fn xp_for(&self, secs: i64) -> i64 {
+        (secs as f64 * self.xp_per_second).round() as i64
+    }
+
+    /// Pure check of the rules the `/config xp` options can't enforce one by one:
+    /// the daily window has to contain the 24 h a kept streak moves forward by.
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.daily_early_secs > 86_400 || self.daily_late_secs < 86_400 {
+            return Err("The daily window has to include the 24th hour after the last bonus.");
+        }
+        if self.daily_early_secs == self.daily_late_secs {
+            return Err("The daily window can't open and close at the same time.");
+        }
+        Ok(())
+    }
+}
 
 pub async fn handle_voice_transition(
     user_id: serenity::UserId,
@@ -27,34 +107,7 @@ pub async fn handle_voice_transition(
     // Close session if leaving a bot-managed temp channel.
     if let Some(old_id) = old_channel_id {
         match db::repositories::temporary_channel::exists(old_id.get() as i64, &data.db).await {
-            Ok(true) => match db::repositories::voice_session::end(uid, gid, &data.db).await {
-                Ok(Some(joined_at)) => {
-                    let duration = (now - joined_at).max(0);
-                    if duration >= MIN_SESSION_SECS {
-                        if let Err(e) = db::repositories::user_profile::add_xp(
-                            uid, gid, duration, duration, &data.db,
-                        )
-                        .await
-                        {
-                            tracing::warn!("XP: add_xp failed for user {uid} in guild {gid}: {e}");
-                        } else {
-                            crate::metrics::xp_awarded(duration);
-                            if let Err(e) = db::repositories::user_profile::update_longest_session(
-                                uid, gid, duration, &data.db,
-                            )
-                            .await
-                            {
-                                tracing::warn!(
-                                    "XP: update_longest_session failed for user {uid} in guild {gid}: {e}"
-                                );
-                            }
-                            check_achievements(uid, gid, now, data).await;
-                        }
-                    }
-                }
-                Ok(None) => {}
-                Err(e) => tracing::warn!("XP: voice_session::end failed: {e}"),
-            },
+            Ok(true) => close_session(uid, gid, now, data).await,
             Ok(false) => {}
             Err(e) => tracing::warn!("XP: temp channel existence check failed: {e}"),
         }
@@ -66,8 +119,14 @@ pub async fn handle_voice_transition(
             Ok(true) => {
                 award_daily_bonus_if_eligible(uid, gid, now, data).await;
 
-                if let Err(e) =
-                    db::repositories::voice_session::start(uid, gid, now, &data.db).await
+                if let Err(e) = db::repositories::voice_session::start(
+                    uid,
+                    gid,
+                    new_id.get() as i64,
+                    now,
+                    &data.db,
+                )
+                .await
                 {
                     tracing::warn!("XP: voice_session::start failed: {e}");
                 }
@@ -78,6 +137,198 @@ pub async fn handle_voice_transition(
     }
 }
 
+/// Ends the member's open session, if any, and credits its active time as XP.
+pub(crate) async fn close_session(uid: i64, gid: i64, now: i64, data: &Data) {
+    match db::repositories::voice_session::end(uid, gid, &data.db).await {
+        Ok(Some(session)) => {
+            // The session ends before its channel's row is deleted, so the row is there.
+            let channel = match session.channel_id {
+                Some(id) => db::repositories::temporary_channel::find(id, &data.db)
+                    .await
+                    .ok()
+                    .flatten(),
+                None => None,
+            };
+            let active = active_secs(&session, now);
+            credit_ended(&session, channel.as_ref(), active, now, &data.db).await;
+        }
+        Ok(None) => {}
+        Err(e) => tracing::warn!("XP: voice_session::end failed: {e}"),
+    }
+}
+
+/// Ends the guild's sessions left open by members who left voice while the bot was
+/// offline, i.e. everyone not in `users_in_temp`, crediting what the last checkpoint
+/// missed. `channels` are the guild's temp channel rows as they were before startup
+/// cleanup deleted any. Returns how many sessions were settled.
+pub(crate) async fn recover_offline_sessions(
+    gid: i64,
+    channels: &[db::entities::temporary_channel::Model],
+    users_in_temp: &HashSet<i64>,
+    now: i64,
+    data: &Data,
+) -> Result<u32, crate::Error> {
+    // Checkpoints credited the session up to the last one before the crash; the
+    // member left some time after that, so credit at most one more interval.
+    let max_downtime_xp = CHECKPOINT_INTERVAL.as_secs() as i64;
+
+    let mut recovered = 0u32;
+    for session in db::repositories::voice_session::list_by_guild(gid, &data.db).await? {
+        if users_in_temp.contains(&session.user_id) {
+            continue;
+        }
+        // User left (or moved to a non-temp channel) while bot was offline.
+        match db::repositories::voice_session::end(session.user_id, gid, &data.db).await {
+            Ok(Some(session)) => {
+                let channel = channels.iter().find(|c| Some(c.id) == session.channel_id);
+                let elapsed = active_secs(&session, now).min(max_downtime_xp);
+                let ended_at = now.min(session.joined_at + max_downtime_xp);
+                credit_ended(&session, channel, elapsed, ended_at, &data.db).await;
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!(
+                "Startup cleanup: end session failed for user {}: {e}",
+                session.user_id
+            ),
+        }
+        recovered += 1;
+    }
+    Ok(recovered)
+}
+
+/// Credits the last `active` seconds of a `session` that ended at `ended_at` on top of
+/// what checkpoints already credited, and records the whole session with the trigger
+/// and game of its `channel`.
+pub(crate) async fn credit_ended(
+    session: &db::entities::voice_session::Model,
+    channel: Option<&db::entities::temporary_channel::Model>,
+    active: i64,
+    ended_at: i64,
+    db: &DatabaseConnection,
+) {
+    let (uid, gid) = (session.user_id, session.guild_id);
+    let policy = Policy::load(gid, db).await;
+    let duration = session.credited_secs + active;
+    if duration < policy.min_session_secs {
+        return;
+    }
+    let xp = policy.xp_for(active);
+    if let Err(e) = db::repositories::user_profile::add_xp(uid, gid, xp, active, ended_at, db).await
+    {
+        tracing::warn!("XP: add_xp failed for user {uid} in guild {gid}: {e}");
+        return;
+    }
+    crate::metrics::xp_awarded(xp);
+    if let Err(e) =
+        db::repositories::user_profile::update_longest_session(uid, gid, duration, db).await
+    {
+        tracing::warn!("XP: update_longest_session failed for user {uid} in guild {gid}: {e}");
+    }
+    record_history(session, channel, ended_at, duration, db).await;
+    check_achievements(uid, gid, &policy.curve, ended_at, db).await;
+}
+
+/// Keeps the finished session for `/history`, with the trigger and game of its channel.
+async fn record_history(
+    session: &db::entities::voice_session::Model,
+    channel: Option<&db::entities::temporary_channel::Model>,
+    ended_at: i64,
+    duration: i64,
+    db: &DatabaseConnection,
+) {
+    let (trigger, game) = channel.map_or((None, None), |c| {
+        (Some(c.primary_channel_id), c.game.clone())
+    });
+    if let Err(e) = db::repositories::voice_session_history::record(
+        session, trigger, game, ended_at, duration, db,
+    )
+    .await
+    {
+        tracing::warn!(
+            "XP: recording history failed for user {} in guild {}: {e}",
+            session.user_id,
+            session.guild_id
+        );
+    }
+}
+
+/// Credits the active time of a still-open session so far and restarts it from `now`.
+/// Returns the seconds credited.
+async fn checkpoint(
+    session: &db::entities::voice_session::Model,
+    now: i64,
+    db: &DatabaseConnection,
+) -> Result<i64, crate::Error> {
+    let (uid, gid) = (session.user_id, session.guild_id);
+    let active = active_secs(session, now);
+    let policy = Policy::load(gid, db).await;
+    // Sessions too short to earn anything yet are left for a later round.
+    if active == 0 || session.credited_secs + active < policy.min_session_secs {
+        return Ok(0);
+    }
+    // Advance first: if the member left in the meantime, the leave credits the time.
+    if !db::repositories::voice_session::checkpoint(session, active, now, db).await? {
+        return Ok(0);
+    }
+    let xp = policy.xp_for(active);
+    db::repositories::user_profile::add_xp(uid, gid, xp, active, now, db).await?;
+    crate::metrics::xp_awarded(xp);
+    check_achievements(uid, gid, &policy.curve, now, db).await;
+    Ok(active)
+}
+
+/// Periodically credits open sessions, so long sessions show up in `/profile` as they
+/// go and a crash loses at most [`CHECKPOINT_INTERVAL`] of anyone's time.
+pub fn spawn_checkpoints(cache: Arc<serenity::Cache>, db: DatabaseConnection) {
+    tokio::spawn(async move {
+        let start = tokio::time::Instant::now() + CHECKPOINT_INTERVAL;
+        let mut interval = tokio::time::interval_at(start, CHECKPOINT_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            if let Err(e) = checkpoint_all(&cache, &db).await {
+                tracing::warn!("XP checkpoint failed: {e}");
+            }
+        }
+    });
+}
+
+async fn checkpoint_all(
+    cache: &serenity::Cache,
+    db: &DatabaseConnection,
+) -> Result<(), crate::Error> {
+    let now = crate::time::now_unix();
+    let mut credited = 0u32;
+    for session in db::repositories::voice_session::list_all(db).await? {
+        let guild_id = serenity::GuildId::new(session.guild_id as u64);
+        let user_id = serenity::UserId::new(session.user_id as u64);
+        // Only members the cache shows in voice: sessions left over from before a
+        // restart are settled by startup cleanup once their guild has loaded.
+        let in_voice = cache.guild(guild_id).is_some_and(|g| {
+            g.voice_states
+                .get(&user_id)
+                .is_some_and(|vs| vs.channel_id.is_some())
+        });
+        if in_voice && checkpoint(&session, now, db).await? > 0 {
+            credited += 1;
+        }
+    }
+    if credited > 0 {
+        tracing::debug!("XP checkpoint: credited {credited} open session(s)");
+    }
+    Ok(())
+}
+
+/// Seconds of `session` that earn XP: time since joining, minus time spent idle
+/// (self-deafened or away; see [`super::idle`]), including a stretch still running.
+/// A stretch that began before the last checkpoint only counts from `joined_at`.
+pub(crate) fn active_secs(session: &db::entities::voice_session::Model, now: i64) -> i64 {
+    let idle_now = session
+        .idle_since
+        .map_or(0, |since| (now - since.max(session.joined_at)).max(0));
+    (now - session.joined_at - session.idle_secs - idle_now).max(0)
+}
+
 async fn award_daily_bonus_if_eligible(uid: i64, gid: i64, now: i64, data: &Data) {
     let profile = match db::repositories::user_profile::get(uid, gid, &data.db).await {
         Err(e) => {
@@ -86,6 +337,7 @@ async fn award_daily_bonus_if_eligible(uid: i64, gid: i64, now: i64, data: &Data
         }
         Ok(p) => p,
     };
+    let policy = Policy::load(gid, &data.db).await;
 
     // Determine new anchor timestamp and streak based on the ±2 h grace window.
     let (new_last_daily, new_streak) = match profile {
@@ -94,9 +346,9 @@ async fn award_daily_bonus_if_eligible(uid: i64, gid: i64, now: i64, data: &Data
             None => (now, 1),
             Some(last_daily) => {
                 let elapsed = now - last_daily;
-                if elapsed < DAILY_EARLY_SECS {
+                if elapsed < policy.daily_early_secs {
                     return; // too early
-                } else if elapsed <= DAILY_LATE_SECS {
+                } else if elapsed <= policy.daily_late_secs {
                     // In-window: anchor to original cadence, preserve streak.
                     (last_daily + 86_400, p.streak + 1)
                 } else {
@@ -107,13 +359,13 @@ async fn award_daily_bonus_if_eligible(uid: i64, gid: i64, now: i64, data: &Data
         },
     };
 
-    if let Err(e) =
-        db::repositories::user_profile::add_xp(uid, gid, DAILY_BONUS_XP, 0, &data.db).await
+    let bonus = policy.daily_bonus_xp;
+    if let Err(e) = db::repositories::user_profile::add_xp(uid, gid, bonus, 0, now, &data.db).await
     {
         tracing::warn!("XP: daily bonus add_xp failed for user {uid}: {e}");
         return;
     }
-    crate::metrics::xp_awarded(DAILY_BONUS_XP);
+    crate::metrics::xp_awarded(bonus);
     crate::metrics::daily_bonus_awarded();
 
     if let Err(e) = db::repositories::user_profile::set_daily_state(
@@ -129,17 +381,23 @@ async fn award_daily_bonus_if_eligible(uid: i64, gid: i64, now: i64, data: &Data
         return;
     }
 
-    check_achievements(uid, gid, now, data).await;
+    check_achievements(uid, gid, &policy.curve, now, &data.db).await;
 
     tracing::debug!("XP: daily bonus awarded to user {uid} in guild {gid} (streak {new_streak})");
 }
 
 /// Re-fetches the profile and checks it against every achievement threshold. Called
 /// after any event that can move `xp`, `total_voice_seconds`, or `streak`.
-async fn check_achievements(uid: i64, gid: i64, now: i64, data: &Data) {
-    match db::repositories::user_profile::get(uid, gid, &data.db).await {
+async fn check_achievements(
+    uid: i64,
+    gid: i64,
+    curve: &leveling::Curve,
+    now: i64,
+    db: &DatabaseConnection,
+) {
+    match db::repositories::user_profile::get(uid, gid, db).await {
         Ok(Some(profile)) => {
-            crate::achievements::check_and_unlock(uid, gid, &profile, now, &data.db).await;
+            crate::achievements::check_and_unlock(uid, gid, &profile, curve, now, db).await;
         }
         Ok(None) => {}
         Err(e) => tracing::warn!("XP: achievement check failed for user {uid} in guild {gid}: {e}"),
@@ -160,6 +418,8 @@ mod tests {
             channel_locks: Default::default(),
             default_channel_name_template: crate::activity::DEFAULT_CHANNEL_NAME_TEMPLATE
                 .to_owned(),
+            renames: Default::default(),
+            creations: Default::default(),
         }
     }
 
@@ -171,11 +431,75 @@ mod tests {
         db::repositories::primary_channel::insert(9_000, guild_id, &data.db)
             .await
             .unwrap();
-        db::repositories::temporary_channel::insert(channel_id, guild_id, 9_000, &data.db)
+        db::repositories::temporary_channel::insert(channel_id, guild_id, 9_000, 1, 1, &data.db)
             .await
             .unwrap();
     }
 
+    #[test]
+    fn idle_time_does_not_count_as_active() {
+        let session = db::entities::voice_session::Model {
+            user_id: 1,
+            guild_id: 1,
+            joined_at: 1_000,
+            idle_since: None,
+            idle_secs: 0,
+            credited_secs: 0,
+            channel_id: None,
+            started_at: 1_000,
+        };
+        assert_eq!(active_secs(&session, 2_000), 1_000);
+
+        let finished_idle = db::entities::voice_session::Model {
+            idle_secs: 300,
+            ..session.clone()
+        };
+        assert_eq!(active_secs(&finished_idle, 2_000), 700);
+
+        let still_idle = db::entities::voice_session::Model {
+            idle_since: Some(1_800),
+            ..finished_idle
+        };
+        assert_eq!(active_secs(&still_idle, 2_000), 500);
+
+        let idle_across_a_checkpoint = db::entities::voice_session::Model {
+            idle_since: Some(500),
+            ..still_idle
+        };
+        assert_eq!(active_secs(&idle_across_a_checkpoint, 2_000), 0);
+    }
+
+    #[test]
+    fn default_policy_is_valid() {
+        assert_eq!(Policy::default().validate(), Ok(()));
+        assert_eq!(Policy::default().xp_for(90), 90);
+    }
+
+    #[test]
+    fn daily_window_must_contain_the_24th_hour() {
+        let shut = Policy {
+            daily_early_secs: 86_400,
+            daily_late_secs: 86_400,
+            ..Policy::default()
+        };
+        assert!(shut.validate().is_err());
+        let late = Policy {
+            daily_early_secs: 25 * 3600,
+            daily_late_secs: 30 * 3600,
+            ..Policy::default()
+        };
+        assert!(late.validate().is_err());
+    }
+
+    #[test]
+    fn fractional_rates_round_to_whole_xp() {
+        let half = Policy {
+            xp_per_second: 0.5,
+            ..Policy::default()
+        };
+        assert_eq!(half.xp_for(301), 151);
+    }
+
     mod daily_bonus {
         use super::*;
 
@@ -189,7 +513,7 @@ mod tests {
                 .await
                 .unwrap()
                 .unwrap();
-            assert_eq!(profile.xp, DAILY_BONUS_XP);
+            assert_eq!(profile.xp, DEFAULT_DAILY_BONUS_XP);
             assert_eq!(profile.streak, 1);
             assert_eq!(profile.last_daily_at, Some(1_000_000));
         }
@@ -203,7 +527,7 @@ mod tests {
                 .await
                 .unwrap();
 
-            let too_early = last_daily + DAILY_EARLY_SECS - 1;
+            let too_early = last_daily + DEFAULT_DAILY_EARLY_SECS - 1;
             award_daily_bonus_if_eligible(1, 1, too_early, &data).await;
 
             let profile = db::repositories::user_profile::get(1, 1, &data.db)
@@ -225,14 +549,14 @@ mod tests {
                 .unwrap();
 
             // Well within [22h, 26h] of the old anchor, but not equal to "now".
-            let now = last_daily + DAILY_EARLY_SECS + 3_600;
+            let now = last_daily + DEFAULT_DAILY_EARLY_SECS + 3_600;
             award_daily_bonus_if_eligible(1, 1, now, &data).await;
 
             let profile = db::repositories::user_profile::get(1, 1, &data.db)
                 .await
                 .unwrap()
                 .unwrap();
-            assert_eq!(profile.xp, DAILY_BONUS_XP);
+            assert_eq!(profile.xp, DEFAULT_DAILY_BONUS_XP);
             assert_eq!(profile.streak, 6);
             // Anchored to the *old* timestamp + 24h, not to `now`.
             assert_eq!(profile.last_daily_at, Some(last_daily + 86_400));
@@ -247,14 +571,14 @@ mod tests {
                 .await
                 .unwrap();
 
-            let now = last_daily + DAILY_LATE_SECS + 1;
+            let now = last_daily + DEFAULT_DAILY_LATE_SECS + 1;
             award_daily_bonus_if_eligible(1, 1, now, &data).await;
 
             let profile = db::repositories::user_profile::get(1, 1, &data.db)
                 .await
                 .unwrap()
                 .unwrap();
-            assert_eq!(profile.xp, DAILY_BONUS_XP);
+            assert_eq!(profile.xp, DEFAULT_DAILY_BONUS_XP);
             assert_eq!(profile.streak, 1);
             assert_eq!(profile.last_daily_at, Some(now));
         }
@@ -318,7 +642,7 @@ mod tests {
                 &data,
             )
             .await;
-            // A test executes far faster than MIN_SESSION_SECS, so this leave is always
+            // A test executes far faster than DEFAULT_MIN_SESSION_SECS, so this leave is always
             // "too short" and should award no XP.
             handle_voice_transition(
                 UserId::new(42),
@@ -352,7 +676,7 @@ mod tests {
             // Seed a session that started well over 2h ago so the leave transition
             // below computes a duration long enough to cross the session-2h achievement.
             let joined_at = crate::time::now_unix() - 2 * 3600 - 60;
-            db::repositories::voice_session::start(42, 1, joined_at, &data.db)
+            db::repositories::voice_session::start(42, 1, 100, joined_at, &data.db)
                 .await
                 .unwrap();
 
@@ -372,6 +696,82 @@ mod tests {
             assert!(profile.longest_session_seconds >= 2 * 3600);
         }
 
+        #[tokio::test]
+        async fn leaving_after_a_checkpoint_credits_only_the_rest() {
+            let data = test_data().await;
+            seed_temp_channel(&data, 100, 1).await;
+            let now = crate::time::now_unix();
+            db::repositories::voice_session::start(42, 1, 100, now - 2 * 3600, &data.db)
+                .await
+                .unwrap();
+            let session = db::repositories::voice_session::list_by_guild(1, &data.db)
+                .await
+                .unwrap()
+                .remove(0);
+            let credited = checkpoint(&session, now - 600, &data.db).await.unwrap();
+            assert_eq!(credited, 2 * 3600 - 600);
+
+            handle_voice_transition(
+                UserId::new(42),
+                Some(ChannelId::new(100)),
+                None,
+                GuildId::new(1),
+                &data,
+            )
+            .await;
+
+            let profile = db::repositories::user_profile::get(42, 1, &data.db)
+                .await
+                .unwrap()
+                .unwrap();
+            // Each stretch is credited once, and the record covers the whole session.
+            assert!((2 * 3600..2 * 3600 + 5).contains(&profile.total_voice_seconds));
+            assert!(profile.longest_session_seconds >= 2 * 3600);
+
+            let history =
+                db::repositories::voice_session_history::list_by_member(1, 42, 0, 10, &data.db)
+                    .await
+                    .unwrap();
+            assert_eq!(history.len(), 1);
+            assert_eq!(history[0].started_at, now - 2 * 3600);
+            assert_eq!(history[0].channel_id, Some(100));
+            assert_eq!(history[0].primary_channel_id, Some(9_000));
+            assert_eq!(history[0].duration_secs, profile.longest_session_seconds);
+        }
+
+        #[tokio::test]
+        async fn sessions_recovered_after_a_restart_keep_their_channel_in_history() {
+            let data = test_data().await;
+            seed_temp_channel(&data, 100, 1).await;
+            let now = crate::time::now_unix();
+            db::repositories::voice_session::start(42, 1, 100, now - 600, &data.db)
+                .await
+                .unwrap();
+            db::repositories::temporary_channel::set_game(100, Some("Chess".into()), &data.db)
+                .await
+                .unwrap();
+
+            // Startup cleanup reads the rows, then deletes the channel the member left.
+            let channels = db::repositories::temporary_channel::list_by_guild(1, &data.db)
+                .await
+                .unwrap();
+            db::repositories::temporary_channel::delete(100, &data.db)
+                .await
+                .unwrap();
+            let recovered = recover_offline_sessions(1, &channels, &HashSet::new(), now, &data)
+                .await
+                .unwrap();
+            assert_eq!(recovered, 1);
+
+            let history =
+                db::repositories::voice_session_history::list_by_member(1, 42, 0, 10, &data.db)
+                    .await
+                    .unwrap();
+            assert_eq!(history.len(), 1);
+            assert_eq!(history[0].primary_channel_id, Some(9_000));
+            assert_eq!(history[0].game.as_deref(), Some("Chess"));
+        }
+
         #[tokio::test]
         async fn same_channel_transition_is_a_no_op() {
             let data = test_data().await;
@@ -110,8 +110,10 @@ async fn cleanup(
                 if is_empty {
                     crate::delete_join_channel_if_present(&ctx.http, channel.join_channel_id).await;
                     let _ = ctx.http.delete_channel(channel_id, None).await;
-                    db::repositories::temporary_channel::delete(channel.id, db).await?;
-                    crate::metrics::temp_channel_deleted();
+                    // The ChannelDelete event may have dropped the row already.
+                    if db::repositories::temporary_channel::delete(channel.id, db).await? {
+                        crate::metrics::temp_channel_deleted();
+                    }
                     removed += 1;
                     tracing::debug!(
                         "Cleanup: deleted empty temp channel {channel_id} in guild {guild_id}"
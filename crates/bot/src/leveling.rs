@@ -1,34 +1,54 @@
-const BASE: f64 = 3600.0;
-const GROWTH: f64 = 1.047;
+pub const DEFAULT_BASE: f64 = 3600.0;
+pub const DEFAULT_GROWTH: f64 = 1.047;
+
+/// Past level 100, each level costs this much more than the one before.
+const POST_100_STEP: i64 = 86400;
+
+/// A guild's level curve, set with `/config xp curve`: level 1→2 costs `base` XP and
+/// each level up to 100 costs `growth` times the one before.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Curve {
+    pub base: f64,
+    pub growth: f64,
+}
 
-fn cost_99_100() -> i64 {
-    (BASE * GROWTH.powf(98.0)) as i64
+impl Default for Curve {
+    fn default() -> Self {
+        Self {
+            base: DEFAULT_BASE,
+            growth: DEFAULT_GROWTH,
+        }
+    }
+}
+
+fn cost_99_100(curve: &Curve) -> i64 {
+    (curve.base * curve.growth.powf(98.0)) as i64
 }
 
 /// Total XP required to reach level `n` from level 1.
-pub fn xp_for_level(n: u32) -> i64 {
+pub fn xp_for_level(n: u32, curve: &Curve) -> i64 {
     if n <= 1 {
         return 0;
     }
     if n <= 100 {
-        (BASE * (GROWTH.powf((n - 1) as f64) - 1.0) / (GROWTH - 1.0)) as i64
+        (curve.base * (curve.growth.powf((n - 1) as f64) - 1.0) / (curve.growth - 1.0)) as i64
     } else {
-        let base = xp_for_level(100);
-        let c99 = cost_99_100();
+        let base = xp_for_level(100, curve);
+        let c99 = cost_99_100(curve);
         let m = (n - 100) as i64;
         // Arithmetic: each post-100 level costs c99 + k*86400 more than the previous.
         // Sum_{k=1}^{m} (c99 + k*86400) = m*c99 + 86400 * m*(m+1)/2
-        base + m * c99 + 86400 * m * (m + 1) / 2
+        base + m * c99 + POST_100_STEP * m * (m + 1) / 2
     }
 }
 
 /// Level corresponding to the given total XP (binary search).
-pub fn level_from_xp(xp: i64) -> u32 {
+pub fn level_from_xp(xp: i64, curve: &Curve) -> u32 {
     let mut lo = 1u32;
     let mut hi = 300u32;
     while lo < hi {
         let mid = lo + (hi - lo).div_ceil(2);
-        if xp_for_level(mid) <= xp {
+        if xp_for_level(mid, curve) <= xp {
             lo = mid;
         } else {
             hi = mid - 1;
@@ -38,14 +58,14 @@ pub fn level_from_xp(xp: i64) -> u32 {
 }
 
 /// XP earned within the current level (above that level's threshold).
-pub fn xp_in_level(xp: i64) -> i64 {
-    xp - xp_for_level(level_from_xp(xp))
+pub fn xp_in_level(xp: i64, curve: &Curve) -> i64 {
+    xp - xp_for_level(level_from_xp(xp, curve), curve)
 }
 
 /// XP needed to advance from the current level to the next.
-pub fn xp_to_next_level(xp: i64) -> i64 {
-    let level = level_from_xp(xp);
-    xp_for_level(level + 1) - xp_for_level(level)
+pub fn xp_to_next_level(xp: i64, curve: &Curve) -> i64 {
+    let level = level_from_xp(xp, curve);
+    xp_for_level(level + 1, curve) - xp_for_level(level, curve)
 }
 
 pub fn format_duration(seconds: i64) -> String {
@@ -84,19 +104,24 @@ pub fn progress_bar(current: i64, total: i64, width: usize) -> String {
 mod tests {
     use super::*;
 
+    const CURVE: Curve = Curve {
+        base: DEFAULT_BASE,
+        growth: DEFAULT_GROWTH,
+    };
+
     #[test]
     fn level_1_is_zero_xp() {
-        assert_eq!(xp_for_level(1), 0);
+        assert_eq!(xp_for_level(1, &CURVE), 0);
     }
 
     #[test]
     fn level_2_costs_one_hour() {
-        assert_eq!(xp_for_level(2), 3600);
+        assert_eq!(xp_for_level(2, &CURVE), 3600);
     }
 
     #[test]
     fn level_100_near_two_thousand_hours() {
-        let xp = xp_for_level(100);
+        let xp = xp_for_level(100, &CURVE);
         let hours = xp / 3600;
         assert!(
             hours > 1800 && hours < 2200,
@@ -106,9 +131,9 @@ mod tests {
 
     #[test]
     fn post_100_grows_slower() {
-        let cost_99_100 = xp_for_level(100) - xp_for_level(99);
-        let cost_100_101 = xp_for_level(101) - xp_for_level(100);
-        let cost_101_102 = xp_for_level(102) - xp_for_level(101);
+        let cost_99_100 = xp_for_level(100, &CURVE) - xp_for_level(99, &CURVE);
+        let cost_100_101 = xp_for_level(101, &CURVE) - xp_for_level(100, &CURVE);
+        let cost_101_102 = xp_for_level(102, &CURVE) - xp_for_level(101, &CURVE);
         assert!(
             cost_100_101 > cost_99_100,
             "post-100 should cost more than 99→100"
@@ -123,15 +148,28 @@ mod tests {
     #[test]
     fn level_from_xp_roundtrip() {
         for n in [1, 5, 10, 50, 99, 100, 101, 150] {
-            let xp = xp_for_level(n);
+            let xp = xp_for_level(n, &CURVE);
             assert_eq!(
-                level_from_xp(xp),
+                level_from_xp(xp, &CURVE),
                 n,
                 "level_from_xp(xp_for_level({n})) should be {n}"
             );
         }
     }
 
+    #[test]
+    fn a_steeper_curve_costs_more_per_level() {
+        let steep = Curve {
+            base: 1800.0,
+            growth: 1.1,
+        };
+        assert_eq!(xp_for_level(2, &steep), 1800);
+        assert!(xp_for_level(50, &steep) > xp_for_level(50, &CURVE));
+        for n in [1, 10, 100, 120] {
+            assert_eq!(level_from_xp(xp_for_level(n, &steep), &steep), n);
+        }
+    }
+
     #[test]
     fn format_duration_examples() {
         assert_eq!(format_duration(30), "<1m");
@@ -158,19 +196,22 @@ mod tests {
 
     #[test]
     fn xp_in_level_is_zero_at_a_level_boundary() {
-        let xp = xp_for_level(10);
-        assert_eq!(xp_in_level(xp), 0);
+        let xp = xp_for_level(10, &CURVE);
+        assert_eq!(xp_in_level(xp, &CURVE), 0);
     }
 
     #[test]
     fn xp_in_level_reflects_progress_past_the_boundary() {
-        let xp = xp_for_level(10) + 100;
-        assert_eq!(xp_in_level(xp), 100);
+        let xp = xp_for_level(10, &CURVE) + 100;
+        assert_eq!(xp_in_level(xp, &CURVE), 100);
     }
 
     #[test]
     fn xp_to_next_level_matches_the_next_boundarys_cost() {
-        let xp = xp_for_level(10);
-        assert_eq!(xp_to_next_level(xp), xp_for_level(11) - xp_for_level(10));
+        let xp = xp_for_level(10, &CURVE);
+        assert_eq!(
+            xp_to_next_level(xp, &CURVE),
+            xp_for_level(11, &CURVE) - xp_for_level(10, &CURVE)
+        );
     }
 }
@@ -16,6 +16,12 @@ pub mod ipc_server;
 pub mod leveling;
 pub mod metrics;
 pub mod permissions;
+pub mod policy;
+pub mod presets;
+pub mod privacy;
+pub mod renamer;
+pub mod template;
+pub mod throttle;
 pub mod time;
 
 /// HTTP + cache handles shared between the IPC server and event handlers.
@@ -65,6 +71,10 @@ pub struct Data {
     /// App-level fallback channel-name template, from `DEFAULT_CHANNEL_NAME_TEMPLATE`
     /// or [`activity::DEFAULT_CHANNEL_NAME_TEMPLATE`]. Guilds may override via `/config`.
     pub default_channel_name_template: String,
+    /// Coalesces automatic channel renames to fit Discord's per-channel rename limit.
+    pub renames: Arc<renamer::RenameScheduler>,
+    /// Per-member token buckets on temp-channel creation.
+    pub creations: throttle::CreationLimiter,
 }
 
 pub use error::BotError;
@@ -139,6 +149,8 @@ pub async fn run(token: String, db: DatabaseConnection, socket_path: String) ->
             owner_id,
             channel_locks: ChannelLocks::default(),
             default_channel_name_template,
+            renames: Default::default(),
+            creations: Default::default(),
         },
         bot_ctx,
     )
@@ -46,6 +46,13 @@ pub fn temp_channel_deleted() {
     metrics::gauge!("rustvoice_temp_channels_active").decrement(1.0);
 }
 
+/// A trigger join that created no channel because the member was rate-limited
+/// (`reason = "rate_limited"`) or serving a trigger ban (`reason = "banned"`).
+pub fn trigger_join_rejected(_reason: &'static str) {
+    #[cfg(feature = "metrics")]
+    metrics::counter!("rustvoice_trigger_joins_rejected_total", "reason" => _reason).increment(1);
+}
+
 pub fn xp_awarded(_amount: i64) {
     #[cfg(feature = "metrics")]
     metrics::counter!("rustvoice_xp_awarded_total").increment(_amount.max(0) as u64);
@@ -4,6 +4,7 @@ use poise::serenity_prelude::{self as serenity, Permissions};
 pub enum Category {
     Core,
     Privacy,
+    Moderation,
 }
 
 #[derive(Copy, Clone)]
@@ -47,13 +48,21 @@ const MANAGE_ROLES_ENTRY: PermissionEntry = PermissionEntry {
     name: "Manage Roles",
     // Discord requires this bit to edit channel permission overwrites (PUT /channels/{id}/permissions/{id}).
     // The bot does not create or modify server roles — this permission is used solely for
-    // voice channel overwrite management (/private, /public).
+    // voice channel overwrite management (/private, /public, /hide, /unhide, /permit, /reject,
+    // /vban, /vmute).
     // As an alternative to granting this server-wide, admins can grant "Manage Permissions"
     // (same bit) as a channel-level overwrite on the voice category.
-    description: "Edit channel permission overwrites (/private, /public)",
+    description: "Edit channel permission overwrites (/private, /hide, /permit, …)",
     category: Category::Privacy,
 };
 
+const DEAFEN_MEMBERS_ENTRY: PermissionEntry = PermissionEntry {
+    permission: Permissions::DEAFEN_MEMBERS,
+    name: "Deafen Members",
+    description: "Deafen members inside a temp channel with /vdeafen",
+    category: Category::Moderation,
+};
+
 /// All tracked permissions with metadata, in display order.
 pub const ENTRIES: &[PermissionEntry] = &[
     VIEW_CHANNEL_ENTRY,
@@ -61,6 +70,7 @@ pub const ENTRIES: &[PermissionEntry] = &[
     MOVE_MEMBERS_ENTRY,
     SEND_MESSAGES_ENTRY,
     MANAGE_ROLES_ENTRY,
+    DEAFEN_MEMBERS_ENTRY,
 ];
 
 /// Permissions required for the bot's core operation.
@@ -73,8 +83,11 @@ pub const CORE: Permissions = Permissions::VIEW_CHANNEL
 /// Can be granted server-wide or at the voice category level (same permission bit).
 pub const PRIVACY: Permissions = Permissions::MANAGE_ROLES;
 
+/// Permissions required for channel-owner moderation beyond overwrites (/vdeafen).
+pub const MODERATION: Permissions = Permissions::DEAFEN_MEMBERS;
+
 /// Union of all permissions the bot uses; drives the invite URL.
-pub const ALL: Permissions = CORE.union(PRIVACY);
+pub const ALL: Permissions = CORE.union(PRIVACY).union(MODERATION);
 
 fn format_names(required: &[Permissions]) -> String {
     required
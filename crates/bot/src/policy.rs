@@ -0,0 +1,23 @@
+use std::future::Future;
+
+use db::DbError;
+
+/// Loads a guild's settings row via `get` and converts it into its policy. A guild that
+/// never configured the setting gets the default policy; so does a db error, after a
+/// warning naming `what`, since a broken settings row shouldn't stop the feature.
+pub async fn load_or_default<M, P>(
+    what: &str,
+    guild_id: i64,
+    get: impl Future<Output = Result<Option<M>, DbError>>,
+) -> P
+where
+    P: From<M> + Default,
+{
+    match get.await {
+        Ok(row) => row.map(P::from).unwrap_or_default(),
+        Err(e) => {
+            tracing::warn!("Failed to load {what} for guild {guild_id}: {e}");
+            P::default()
+        }
+    }
+}
@@ -0,0 +1,306 @@
+//! Settings a new temp channel starts with: the trigger's defaults (`/trigger edit`)
+//! overlaid by the creator's own preset (`/preset save`), applied by
+//! `voice_state::on_join`.
+
+use poise::serenity_prelude::{PremiumTier, VideoQualityMode};
+
+/// Highest voice bitrate (bps) Discord allows at each server boost tier.
+pub fn max_bitrate(tier: PremiumTier) -> u32 {
+    match tier {
+        PremiumTier::Tier1 => 128_000,
+        PremiumTier::Tier2 => 256_000,
+        PremiumTier::Tier3 => 384_000,
+        _ => 96_000,
+    }
+}
+
+/// Voice regions offered by `/region` and `/trigger edit`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum Region {
+    #[name = "Automatic"]
+    Automatic,
+    Brazil,
+    #[name = "Hong Kong"]
+    HongKong,
+    India,
+    Japan,
+    Rotterdam,
+    Russia,
+    Singapore,
+    #[name = "South Africa"]
+    SouthAfrica,
+    Sydney,
+    #[name = "US Central"]
+    UsCentral,
+    #[name = "US East"]
+    UsEast,
+    #[name = "US South"]
+    UsSouth,
+    #[name = "US West"]
+    UsWest,
+}
+
+impl Region {
+    /// Discord's region id, or `None` for automatic selection.
+    pub fn id(self) -> Option<&'static str> {
+        Some(match self {
+            Self::Automatic => return None,
+            Self::Brazil => "brazil",
+            Self::HongKong => "hongkong",
+            Self::India => "india",
+            Self::Japan => "japan",
+            Self::Rotterdam => "rotterdam",
+            Self::Russia => "russia",
+            Self::Singapore => "singapore",
+            Self::SouthAfrica => "southafrica",
+            Self::Sydney => "sydney",
+            Self::UsCentral => "us-central",
+            Self::UsEast => "us-east",
+            Self::UsSouth => "us-south",
+            Self::UsWest => "us-west",
+        })
+    }
+}
+
+/// Video quality choices offered by `/video-quality` and `/trigger edit`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum VideoQuality {
+    #[name = "Auto"]
+    Auto,
+    #[name = "Full (720p)"]
+    Full,
+}
+
+impl VideoQuality {
+    pub fn mode(self) -> VideoQualityMode {
+        match self {
+            Self::Auto => VideoQualityMode::Auto,
+            Self::Full => VideoQualityMode::Full,
+        }
+    }
+}
+
+/// Settings a new temp channel is created with.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ChannelSettings {
+    pub name: String,
+    pub user_limit: Option<u32>,
+    pub bitrate: Option<u32>,
+    pub private: bool,
+    /// `None` lets Discord pick the region.
+    pub rtc_region: Option<String>,
+    pub video_quality: Option<VideoQualityMode>,
+}
+
+/// Pure decision: overlays the creator's saved preset, if any, on `trigger`'s defaults.
+/// `default_name` is the name rendered from the trigger's (or guild's) template. The
+/// bitrate is clamped to `max_bitrate` so a value saved in a boosted server keeps
+/// working after the boost lapses; a limit of 0 means "no limit".
+pub fn resolve(
+    trigger: &db::entities::primary_channel::Model,
+    preset: Option<&db::entities::channel_preset::Model>,
+    default_name: String,
+    max_bitrate: u32,
+) -> ChannelSettings {
+    ChannelSettings {
+        name: preset.and_then(|p| p.name.clone()).unwrap_or(default_name),
+        user_limit: preset
+            .and_then(|p| p.user_limit)
+            .or(trigger.user_limit)
+            .filter(|&n| n > 0)
+            .map(|n| n.min(99) as u32),
+        bitrate: preset
+            .and_then(|p| p.bitrate)
+            .or(trigger.bitrate)
+            .map(|b| b.clamp(8_000, max_bitrate as i64) as u32),
+        private: preset.map_or(trigger.private, |p| p.private),
+        rtc_region: preset
+            .and_then(|p| p.rtc_region.clone())
+            .or_else(|| trigger.rtc_region.clone()),
+        video_quality: preset
+            .and_then(|p| p.video_quality_mode)
+            .or(trigger.video_quality_mode)
+            .map(|mode| VideoQualityMode::from(mode as u8)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trigger() -> db::entities::primary_channel::Model {
+        db::entities::primary_channel::Model {
+            id: 10,
+            guild_id: 1,
+            name_template: None,
+            user_limit: None,
+            bitrate: None,
+            category_id: None,
+            private: false,
+            rtc_region: None,
+            video_quality_mode: None,
+            max_channels: None,
+            fill_existing: false,
+        }
+    }
+
+    fn preset() -> db::entities::channel_preset::Model {
+        db::entities::channel_preset::Model {
+            user_id: 42,
+            guild_id: 1,
+            name: Some("Squad".to_owned()),
+            user_limit: Some(5),
+            private: true,
+            bitrate: Some(64_000),
+            rtc_region: None,
+            video_quality_mode: None,
+        }
+    }
+
+    #[test]
+    fn no_preset_uses_the_default_name_only() {
+        assert_eq!(
+            resolve(&trigger(), None, "[General]".to_owned(), 96_000),
+            ChannelSettings {
+                name: "[General]".to_owned(),
+                user_limit: None,
+                bitrate: None,
+                private: false,
+                rtc_region: None,
+                video_quality: None,
+            }
+        );
+    }
+
+    #[test]
+    fn preset_values_replace_the_defaults() {
+        assert_eq!(
+            resolve(&trigger(), Some(&preset()), "[General]".to_owned(), 96_000),
+            ChannelSettings {
+                name: "Squad".to_owned(),
+                user_limit: Some(5),
+                bitrate: Some(64_000),
+                private: true,
+                rtc_region: None,
+                video_quality: None,
+            }
+        );
+    }
+
+    #[test]
+    fn preset_without_a_name_keeps_the_default_name() {
+        let preset = db::entities::channel_preset::Model {
+            name: None,
+            ..preset()
+        };
+        assert_eq!(
+            resolve(&trigger(), Some(&preset), "[General]".to_owned(), 96_000).name,
+            "[General]"
+        );
+    }
+
+    #[test]
+    fn bitrate_is_clamped_to_the_guild_tier() {
+        let preset = db::entities::channel_preset::Model {
+            bitrate: Some(384_000),
+            ..preset()
+        };
+        assert_eq!(
+            resolve(&trigger(), Some(&preset), "[General]".to_owned(), 96_000).bitrate,
+            Some(96_000)
+        );
+    }
+
+    #[test]
+    fn zero_user_limit_means_unlimited() {
+        let preset = db::entities::channel_preset::Model {
+            user_limit: Some(0),
+            ..preset()
+        };
+        assert_eq!(
+            resolve(&trigger(), Some(&preset), "[General]".to_owned(), 96_000).user_limit,
+            None
+        );
+    }
+
+    #[test]
+    fn trigger_defaults_apply_without_a_preset() {
+        let trigger = db::entities::primary_channel::Model {
+            user_limit: Some(4),
+            bitrate: Some(64_000),
+            private: true,
+            ..trigger()
+        };
+        assert_eq!(
+            resolve(&trigger, None, "Squad".to_owned(), 96_000),
+            ChannelSettings {
+                name: "Squad".to_owned(),
+                user_limit: Some(4),
+                bitrate: Some(64_000),
+                private: true,
+                rtc_region: None,
+                video_quality: None,
+            }
+        );
+    }
+
+    #[test]
+    fn preset_overrides_trigger_defaults() {
+        let trigger = db::entities::primary_channel::Model {
+            user_limit: Some(4),
+            private: true,
+            ..trigger()
+        };
+        let preset = db::entities::channel_preset::Model {
+            private: false,
+            ..preset()
+        };
+        let settings = resolve(&trigger, Some(&preset), "Squad".to_owned(), 96_000);
+        assert_eq!(settings.user_limit, Some(5));
+        assert!(!settings.private);
+    }
+
+    #[test]
+    fn region_and_video_quality_fall_back_to_the_trigger() {
+        let trigger = db::entities::primary_channel::Model {
+            rtc_region: Some("rotterdam".to_owned()),
+            video_quality_mode: Some(2),
+            ..trigger()
+        };
+        let settings = resolve(&trigger, Some(&preset()), "Squad".to_owned(), 96_000);
+        assert_eq!(settings.rtc_region.as_deref(), Some("rotterdam"));
+        assert_eq!(settings.video_quality, Some(VideoQualityMode::Full));
+
+        let preset = db::entities::channel_preset::Model {
+            rtc_region: Some("japan".to_owned()),
+            video_quality_mode: Some(1),
+            ..preset()
+        };
+        let settings = resolve(&trigger, Some(&preset), "Squad".to_owned(), 96_000);
+        assert_eq!(settings.rtc_region.as_deref(), Some("japan"));
+        assert_eq!(settings.video_quality, Some(VideoQualityMode::Auto));
+    }
+
+    #[test]
+    fn region_ids_match_discord() {
+        assert_eq!(Region::Automatic.id(), None);
+        assert_eq!(Region::HongKong.id(), Some("hongkong"));
+        assert_eq!(Region::UsCentral.id(), Some("us-central"));
+    }
+
+    #[test]
+    fn preset_without_a_limit_falls_back_to_the_trigger_limit() {
+        let trigger = db::entities::primary_channel::Model {
+            user_limit: Some(4),
+            ..trigger()
+        };
+        let preset = db::entities::channel_preset::Model {
+            user_limit: None,
+            ..preset()
+        };
+        assert_eq!(
+            resolve(&trigger, Some(&preset), "Squad".to_owned(), 96_000).user_limit,
+            Some(4)
+        );
+    }
+}
@@ -0,0 +1,606 @@
+//! Permission-overwrite changes behind `/private`, `/public`, `/hide`, `/unhide`,
+//! `/permit`, `/reject`, `/vban` and `/vmute`, shared with every other path that needs them (e.g.
+//! restoring a preset).
+//!
+//! A channel's single @everyone overwrite encodes both locking and hiding, so it is
+//! always derived from the `temporary_channels` row via [`everyone_overwrite`] rather
+//! than edited piecemeal.
+
+use poise::serenity_prelude::{
+    self as serenity, ChannelType, PermissionOverwrite, PermissionOverwriteType, Permissions,
+};
+
+use db::DatabaseConnection;
+
+use crate::{permissions::PermissionResultExt, Error};
+
+/// Locks `channel_id`: denies @everyone CONNECT and creates the `[join ↑]` companion
+/// channel, recording it on the channel's `temporary_channels` row.
+pub async fn lock(
+    http: &serenity::Http,
+    guild_id: serenity::GuildId,
+    channel_id: serenity::ChannelId,
+    bot_id: serenity::UserId,
+    db: &DatabaseConnection,
+) -> Result<serenity::ChannelId, Error> {
+    let everyone_id = guild_id.everyone_role();
+    let hidden = db::repositories::temporary_channel::find(channel_id.get() as i64, db)
+        .await?
+        .is_some_and(|record| record.hidden);
+
+    grant_bot(http, channel_id, bot_id).await?;
+    sync_everyone(http, guild_id, channel_id, true, hidden).await?;
+
+    // Create the companion join-request channel in the same category.
+    let parent_id = http
+        .get_channel(channel_id)
+        .await
+        .ok()
+        .and_then(|c| c.guild())
+        .and_then(|gc| gc.parent_id);
+
+    let mut builder = serenity::builder::CreateChannel::new("[join ↑]").kind(ChannelType::Voice);
+    if let Some(parent) = parent_id {
+        builder = builder.category(parent);
+    }
+    // Explicitly allow @everyone to CONNECT so the join channel is reachable even if the
+    // parent category has CONNECT denied.
+    builder = builder.permissions(vec![PermissionOverwrite {
+        allow: Permissions::CONNECT,
+        deny: Permissions::empty(),
+        kind: PermissionOverwriteType::Role(everyone_id),
+    }]);
+
+    let join_ch = guild_id
+        .create_channel(http, builder)
+        .await
+        .requires(&[Permissions::MANAGE_CHANNELS])?;
+
+    db::repositories::temporary_channel::set_join_channel(
+        channel_id.get() as i64,
+        Some(join_ch.id.get() as i64),
+        db,
+    )
+    .await?;
+
+    Ok(join_ch.id)
+}
+
+/// Makes `channel_id` fully public: deletes its `[join ↑]` channel, if any, leaves
+/// hidden mode, and removes the @everyone overwrite.
+pub async fn unlock(
+    http: &serenity::Http,
+    guild_id: serenity::GuildId,
+    channel_id: serenity::ChannelId,
+    db: &DatabaseConnection,
+) -> Result<(), Error> {
+    if let Some(record) =
+        db::repositories::temporary_channel::find(channel_id.get() as i64, db).await?
+    {
+        if record.join_channel_id.is_some() {
+            crate::delete_join_channel_if_present(http, record.join_channel_id).await;
+            db::repositories::temporary_channel::set_join_channel(
+                channel_id.get() as i64,
+                None,
+                db,
+            )
+            .await?;
+        }
+        if record.hidden {
+            db::repositories::temporary_channel::set_hidden(channel_id.get() as i64, false, db)
+                .await?;
+        }
+    }
+
+    // The bot's member overwrite is intentionally left in place so it retains
+    // MANAGE_CHANNELS and MANAGE_ROLES on the channel even if those were only granted at
+    // the category level.
+    sync_everyone(http, guild_id, channel_id, false, false).await
+}
+
+/// Hides `channel_id` from @everyone. The people currently inside, per the cache, get
+/// VIEW_CHANNEL added to their overwrite so they keep seeing it, without losing a
+/// `/permit` or `/vmute` already on it; permitted users already have it. Members the
+/// bot moves in later get it from [`show_if_hidden`].
+pub async fn hide(
+    cache: &serenity::Cache,
+    http: &serenity::Http,
+    guild_id: serenity::GuildId,
+    channel_id: serenity::ChannelId,
+    db: &DatabaseConnection,
+) -> Result<(), Error> {
+    let private = db::repositories::temporary_channel::find(channel_id.get() as i64, db)
+        .await?
+        .is_some_and(|record| record.join_channel_id.is_some());
+    let bot_id = cache.current_user().id;
+    let members: Vec<(serenity::UserId, Option<PermissionOverwrite>)> = cache
+        .guild(guild_id)
+        .map(|g| {
+            let overwrites = g
+                .channels
+                .get(&channel_id)
+                .map(|c| c.permission_overwrites.as_slice())
+                .unwrap_or_default();
+            g.voice_states
+                .values()
+                .filter(|vs| vs.channel_id == Some(channel_id) && vs.user_id != bot_id)
+                .map(|vs| {
+                    let existing = overwrites
+                        .iter()
+                        .find(|o| o.kind == PermissionOverwriteType::Member(vs.user_id))
+                        .cloned();
+                    (vs.user_id, existing)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    grant_bot(http, channel_id, bot_id).await?;
+    for (member, existing) in &members {
+        set_member_allow(
+            http,
+            channel_id,
+            existing.as_ref(),
+            *member,
+            Permissions::VIEW_CHANNEL,
+            true,
+        )
+        .await?;
+    }
+    sync_everyone(http, guild_id, channel_id, private, true).await?;
+    db::repositories::temporary_channel::set_hidden(channel_id.get() as i64, true, db).await?;
+    Ok(())
+}
+
+/// Adds VIEW_CHANNEL to `user_id`'s overwrite if `channel_id` is hidden. Called before
+/// the bot moves someone in: the move skips permission checks, and without this they'd
+/// end up in a channel they can't see.
+pub async fn show_if_hidden(
+    cache: &serenity::Cache,
+    http: &serenity::Http,
+    guild_id: serenity::GuildId,
+    channel_id: serenity::ChannelId,
+    user_id: serenity::UserId,
+    db: &DatabaseConnection,
+) -> Result<(), Error> {
+    let hidden = db::repositories::temporary_channel::find(channel_id.get() as i64, db)
+        .await?
+        .is_some_and(|record| record.hidden);
+    if !hidden {
+        return Ok(());
+    }
+    let existing = cache.guild(guild_id).and_then(|g| {
+        g.channels.get(&channel_id).and_then(|c| {
+            c.permission_overwrites
+                .iter()
+                .find(|o| o.kind == PermissionOverwriteType::Member(user_id))
+                .cloned()
+        })
+    });
+    set_member_allow(
+        http,
+        channel_id,
+        existing.as_ref(),
+        user_id,
+        Permissions::VIEW_CHANNEL,
+        true,
+    )
+    .await
+}
+
+/// Makes a hidden `channel_id` visible again, keeping it locked if it was.
+pub async fn unhide(
+    http: &serenity::Http,
+    guild_id: serenity::GuildId,
+    channel_id: serenity::ChannelId,
+    db: &DatabaseConnection,
+) -> Result<(), Error> {
+    let private = db::repositories::temporary_channel::find(channel_id.get() as i64, db)
+        .await?
+        .is_some_and(|record| record.join_channel_id.is_some());
+
+    sync_everyone(http, guild_id, channel_id, private, false).await?;
+    db::repositories::temporary_channel::set_hidden(channel_id.get() as i64, false, db).await?;
+    Ok(())
+}
+
+/// Grants the bot explicit access so an @everyone deny cannot lock it out.
+/// MANAGE_CHANNELS and MANAGE_ROLES are included so that category-level grants are preserved
+/// on this channel after the overwrite is applied (member overwrites replace inherited ones).
+async fn grant_bot(
+    http: &serenity::Http,
+    channel_id: serenity::ChannelId,
+    bot_id: serenity::UserId,
+) -> Result<(), Error> {
+    channel_id
+        .create_permission(
+            http,
+            PermissionOverwrite {
+                allow: Permissions::VIEW_CHANNEL
+                    | Permissions::CONNECT
+                    | Permissions::MANAGE_CHANNELS
+                    | Permissions::MANAGE_ROLES,
+                deny: Permissions::empty(),
+                kind: PermissionOverwriteType::Member(bot_id),
+            },
+        )
+        .await
+        .requires(&[Permissions::MANAGE_ROLES])?;
+    Ok(())
+}
+
+/// The @everyone overwrite for a channel that is `private` (locked) and/or `hidden`, or
+/// `None` for a public, visible channel.
+pub fn everyone_overwrite(
+    everyone_id: serenity::RoleId,
+    private: bool,
+    hidden: bool,
+) -> Option<PermissionOverwrite> {
+    let mut deny = Permissions::empty();
+    if private {
+        deny |= Permissions::CONNECT;
+    }
+    if hidden {
+        deny |= Permissions::VIEW_CHANNEL;
+    }
+    (!deny.is_empty()).then_some(PermissionOverwrite {
+        allow: Permissions::empty(),
+        deny,
+        kind: PermissionOverwriteType::Role(everyone_id),
+    })
+}
+
+/// Writes (or removes) the @everyone overwrite to match `private` and `hidden`.
+pub async fn sync_everyone(
+    http: &serenity::Http,
+    guild_id: serenity::GuildId,
+    channel_id: serenity::ChannelId,
+    private: bool,
+    hidden: bool,
+) -> Result<(), Error> {
+    let everyone_id = guild_id.everyone_role();
+    match everyone_overwrite(everyone_id, private, hidden) {
+        Some(overwrite) => channel_id.create_permission(http, overwrite).await,
+        None => {
+            channel_id
+                .delete_permission(http, PermissionOverwriteType::Role(everyone_id))
+                .await
+        }
+    }
+    .requires(&[Permissions::MANAGE_ROLES])?;
+    Ok(())
+}
+
+/// Who a `/permit` or `/reject` entry applies to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AccessTarget {
+    User(serenity::UserId),
+    Role(serenity::RoleId),
+}
+
+impl AccessTarget {
+    pub fn from_rule(rule: &db::entities::access_rule::Model) -> Self {
+        if rule.is_role {
+            Self::Role(serenity::RoleId::new(rule.target_id as u64))
+        } else {
+            Self::User(serenity::UserId::new(rule.target_id as u64))
+        }
+    }
+
+    /// Discord mention for replies.
+    pub fn mention(self) -> String {
+        match self {
+            Self::User(id) => format!("<@{id}>"),
+            Self::Role(id) => format!("<@&{id}>"),
+        }
+    }
+}
+
+/// The overwrite for a permit (`allowed`) or reject entry. Permitted targets may also
+/// see the channel so they can still find it if it's ever hidden.
+pub fn access_overwrite(target: AccessTarget, allowed: bool) -> PermissionOverwrite {
+    let kind = match target {
+        AccessTarget::User(id) => PermissionOverwriteType::Member(id),
+        AccessTarget::Role(id) => PermissionOverwriteType::Role(id),
+    };
+    if allowed {
+        PermissionOverwrite {
+            allow: Permissions::VIEW_CHANNEL | Permissions::CONNECT,
+            deny: Permissions::empty(),
+            kind,
+        }
+    } else {
+        PermissionOverwrite {
+            allow: Permissions::empty(),
+            deny: Permissions::CONNECT,
+            kind,
+        }
+    }
+}
+
+/// Whether `overwrites` keep `user_id`, who has `roles`, from connecting. Follows
+/// Discord's order: a role allow beats a role deny, and the member's own overwrite
+/// beats both. @everyone isn't considered; it's what locking a channel sets.
+pub fn connect_denied(
+    overwrites: &[PermissionOverwrite],
+    user_id: serenity::UserId,
+    roles: &[serenity::RoleId],
+) -> bool {
+    let mut role_deny = false;
+    let mut role_allow = false;
+    for overwrite in overwrites {
+        match overwrite.kind {
+            PermissionOverwriteType::Member(id) if id == user_id => {
+                if overwrite.allow.contains(Permissions::CONNECT) {
+                    return false;
+                }
+                if overwrite.deny.contains(Permissions::CONNECT) {
+                    return true;
+                }
+            }
+            PermissionOverwriteType::Role(id) if roles.contains(&id) => {
+                role_deny |= overwrite.deny.contains(Permissions::CONNECT);
+                role_allow |= overwrite.allow.contains(Permissions::CONNECT);
+            }
+            _ => {}
+        }
+    }
+    role_deny && !role_allow
+}
+
+/// Writes an owner's permit and reject entries onto `channel_id`.
+pub async fn apply_access_rules(
+    http: &serenity::Http,
+    channel_id: serenity::ChannelId,
+    rules: &[db::entities::access_rule::Model],
+) -> Result<(), Error> {
+    for rule in rules {
+        channel_id
+            .create_permission(
+                http,
+                access_overwrite(AccessTarget::from_rule(rule), rule.allowed),
+            )
+            .await
+            .requires(&[Permissions::MANAGE_ROLES])?;
+    }
+    Ok(())
+}
+
+/// `existing` (a member's current overwrite on a channel) with `bits` added to its deny
+/// set, or removed from it when `deny` is false, leaving its other bits alone. `None`
+/// means nothing is left and the overwrite should be deleted.
+pub fn with_member_deny(
+    existing: Option<&PermissionOverwrite>,
+    user_id: serenity::UserId,
+    bits: Permissions,
+    deny: bool,
+) -> Option<PermissionOverwrite> {
+    let (mut allow, mut denied) = existing
+        .map_or((Permissions::empty(), Permissions::empty()), |o| {
+            (o.allow, o.deny)
+        });
+    if deny {
+        allow.remove(bits);
+        denied.insert(bits);
+    } else {
+        denied.remove(bits);
+    }
+    (!allow.is_empty() || !denied.is_empty()).then_some(PermissionOverwrite {
+        allow,
+        deny: denied,
+        kind: PermissionOverwriteType::Member(user_id),
+    })
+}
+
+/// `existing` with `bits` added to its allow set, or removed from it when `allow` is
+/// false, leaving its other bits alone. The allow-side twin of [`with_member_deny`].
+pub fn with_member_allow(
+    existing: Option<&PermissionOverwrite>,
+    user_id: serenity::UserId,
+    bits: Permissions,
+    allow: bool,
+) -> Option<PermissionOverwrite> {
+    let (mut allowed, mut deny) = existing
+        .map_or((Permissions::empty(), Permissions::empty()), |o| {
+            (o.allow, o.deny)
+        });
+    if allow {
+        deny.remove(bits);
+        allowed.insert(bits);
+    } else {
+        allowed.remove(bits);
+    }
+    (!allowed.is_empty() || !deny.is_empty()).then_some(PermissionOverwrite {
+        allow: allowed,
+        deny,
+        kind: PermissionOverwriteType::Member(user_id),
+    })
+}
+
+/// Denies (or stops denying) `bits` to `user_id` on `channel_id` without disturbing the
+/// rest of their overwrite. `existing` is their current overwrite, read from the cache.
+pub async fn set_member_deny(
+    http: &serenity::Http,
+    channel_id: serenity::ChannelId,
+    existing: Option<&PermissionOverwrite>,
+    user_id: serenity::UserId,
+    bits: Permissions,
+    deny: bool,
+) -> Result<(), Error> {
+    let overwrite = with_member_deny(existing, user_id, bits, deny);
+    write_member_overwrite(http, channel_id, user_id, overwrite).await
+}
+
+/// Allows (or stops allowing) `bits` to `user_id` on `channel_id`; see [`set_member_deny`].
+pub async fn set_member_allow(
+    http: &serenity::Http,
+    channel_id: serenity::ChannelId,
+    existing: Option<&PermissionOverwrite>,
+    user_id: serenity::UserId,
+    bits: Permissions,
+    allow: bool,
+) -> Result<(), Error> {
+    let overwrite = with_member_allow(existing, user_id, bits, allow);
+    write_member_overwrite(http, channel_id, user_id, overwrite).await
+}
+
+/// Writes a member's overwrite, or deletes it when nothing is left.
+async fn write_member_overwrite(
+    http: &serenity::Http,
+    channel_id: serenity::ChannelId,
+    user_id: serenity::UserId,
+    overwrite: Option<PermissionOverwrite>,
+) -> Result<(), Error> {
+    match overwrite {
+        Some(overwrite) => channel_id.create_permission(http, overwrite).await,
+        None => {
+            channel_id
+                .delete_permission(http, PermissionOverwriteType::Member(user_id))
+                .await
+        }
+    }
+    .requires(&[Permissions::MANAGE_ROLES])?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn everyone_overwrite_combines_lock_and_hide() {
+        let everyone = serenity::RoleId::new(1);
+        assert!(everyone_overwrite(everyone, false, false).is_none());
+        assert_eq!(
+            everyone_overwrite(everyone, true, false).unwrap().deny,
+            Permissions::CONNECT
+        );
+        assert_eq!(
+            everyone_overwrite(everyone, false, true).unwrap().deny,
+            Permissions::VIEW_CHANNEL
+        );
+        let both = everyone_overwrite(everyone, true, true).unwrap();
+        assert_eq!(both.deny, Permissions::CONNECT | Permissions::VIEW_CHANNEL);
+        assert!(both.allow.is_empty());
+        assert_eq!(both.kind, PermissionOverwriteType::Role(everyone));
+    }
+
+    #[test]
+    fn permit_allows_viewing_and_connecting() {
+        let overwrite = access_overwrite(AccessTarget::User(serenity::UserId::new(7)), true);
+        assert_eq!(
+            overwrite.allow,
+            Permissions::VIEW_CHANNEL | Permissions::CONNECT
+        );
+        assert!(overwrite.deny.is_empty());
+        assert_eq!(
+            overwrite.kind,
+            PermissionOverwriteType::Member(serenity::UserId::new(7))
+        );
+    }
+
+    #[test]
+    fn reject_denies_connecting_only() {
+        let overwrite = access_overwrite(AccessTarget::Role(serenity::RoleId::new(9)), false);
+        assert!(overwrite.allow.is_empty());
+        assert_eq!(overwrite.deny, Permissions::CONNECT);
+        assert_eq!(
+            overwrite.kind,
+            PermissionOverwriteType::Role(serenity::RoleId::new(9))
+        );
+    }
+
+    #[test]
+    fn member_deny_keeps_the_other_bits() {
+        let user = serenity::UserId::new(7);
+        let permitted = access_overwrite(AccessTarget::User(user), true);
+
+        let muted = with_member_deny(Some(&permitted), user, Permissions::SPEAK, true).unwrap();
+        assert_eq!(
+            muted.allow,
+            Permissions::VIEW_CHANNEL | Permissions::CONNECT
+        );
+        assert_eq!(muted.deny, Permissions::SPEAK);
+
+        // Banning a permitted member takes CONNECT out of their allow set.
+        let banned = with_member_deny(Some(&muted), user, Permissions::CONNECT, true).unwrap();
+        assert_eq!(banned.allow, Permissions::VIEW_CHANNEL);
+        assert_eq!(banned.deny, Permissions::SPEAK | Permissions::CONNECT);
+
+        let unmuted = with_member_deny(Some(&banned), user, Permissions::SPEAK, false).unwrap();
+        assert_eq!(unmuted.deny, Permissions::CONNECT);
+    }
+
+    #[test]
+    fn member_deny_drops_an_overwrite_left_empty() {
+        let user = serenity::UserId::new(7);
+        let muted = with_member_deny(None, user, Permissions::SPEAK, true).unwrap();
+        assert_eq!(muted.kind, PermissionOverwriteType::Member(user));
+        assert!(with_member_deny(Some(&muted), user, Permissions::SPEAK, false).is_none());
+        assert!(with_member_deny(None, user, Permissions::SPEAK, false).is_none());
+    }
+
+    #[test]
+    fn member_allow_keeps_the_other_bits() {
+        let user = serenity::UserId::new(7);
+        let muted = with_member_deny(None, user, Permissions::SPEAK, true).unwrap();
+
+        let seen = with_member_allow(Some(&muted), user, Permissions::VIEW_CHANNEL, true).unwrap();
+        assert_eq!(seen.allow, Permissions::VIEW_CHANNEL);
+        assert_eq!(seen.deny, Permissions::SPEAK);
+
+        let unseen =
+            with_member_allow(Some(&seen), user, Permissions::VIEW_CHANNEL, false).unwrap();
+        assert_eq!(unseen, muted);
+        assert!(with_member_allow(None, user, Permissions::CONNECT, false).is_none());
+    }
+
+    #[test]
+    fn connect_denied_follows_discords_overwrite_order() {
+        let user = serenity::UserId::new(7);
+        let (rejected, permitted) = (serenity::RoleId::new(8), serenity::RoleId::new(9));
+        let banned = with_member_deny(None, user, Permissions::CONNECT, true).unwrap();
+        let role_reject = access_overwrite(AccessTarget::Role(rejected), false);
+        let role_permit = access_overwrite(AccessTarget::Role(permitted), true);
+
+        assert!(connect_denied(std::slice::from_ref(&banned), user, &[]));
+        assert!(!connect_denied(&[banned], serenity::UserId::new(1), &[]));
+        assert!(connect_denied(
+            std::slice::from_ref(&role_reject),
+            user,
+            &[rejected]
+        ));
+        assert!(!connect_denied(
+            std::slice::from_ref(&role_reject),
+            user,
+            &[]
+        ));
+        // Another of the member's roles is permitted, or the member is.
+        assert!(!connect_denied(
+            &[role_reject.clone(), role_permit],
+            user,
+            &[rejected, permitted]
+        ));
+        let member_permit = access_overwrite(AccessTarget::User(user), true);
+        assert!(!connect_denied(
+            &[role_reject, member_permit],
+            user,
+            &[rejected]
+        ));
+    }
+
+    #[test]
+    fn from_rule_reads_the_target_kind() {
+        let rule = db::entities::access_rule::Model {
+            user_id: 1,
+            guild_id: 2,
+            target_id: 3,
+            is_role: true,
+            allowed: true,
+        };
+        assert_eq!(
+            AccessTarget::from_rule(&rule),
+            AccessTarget::Role(serenity::RoleId::new(3))
+        );
+    }
+}
@@ -0,0 +1,408 @@
+//! Per-channel rename scheduling.
+//!
+//! Discord allows only [`RENAMES_PER_WINDOW`] renames of a channel per [`RENAME_WINDOW`];
+//! further edits sit in serenity's rate limiter and land long after the name they carry
+//! has gone stale. Instead of editing on every voice or presence change, callers record
+//! the name they want with [`RenameScheduler::request`]. One flusher task per channel
+//! then applies the latest requested name as soon as the channel has budget left, so
+//! any number of requests in between collapse into a single edit.
+//!
+//! Manual renames go through [`RenameScheduler::try_rename_now`] and lock the channel's
+//! name before editing. Both kinds of edit hold the channel's edit lock, and the flusher
+//! re-checks the name lock under it, so an automatic name can't land after a manual one.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use poise::serenity_prelude::{self as serenity, ChannelId, Permissions};
+
+use db::DatabaseConnection;
+
+use crate::permissions::PermissionResultExt;
+use crate::time::{Clock, SystemClock};
+
+/// Renames Discord allows per channel within [`RENAME_WINDOW`].
+pub const RENAMES_PER_WINDOW: usize = 2;
+/// Sliding window over which [`RENAMES_PER_WINDOW`] applies.
+pub const RENAME_WINDOW: Duration = Duration::from_secs(10 * 60);
+
+/// Failed automatic renames in a row after which the flusher drops the name.
+const MAX_ATTEMPTS: u32 = 3;
+/// Wait after a failed automatic rename, multiplied by the failures so far.
+const RETRY_DELAY: Duration = Duration::from_secs(30);
+
+#[derive(Default)]
+struct ChannelState {
+    /// When this channel's recent renames happened, oldest first, within the window.
+    recent: VecDeque<Instant>,
+    /// Latest requested name not yet applied.
+    pending: Option<String>,
+    /// Whether a flusher task currently owns applying `pending`.
+    flushing: bool,
+    /// When the rename [`RenameScheduler::poll`] last handed out was charged.
+    charged: Option<Instant>,
+    /// Held while an edit of the channel's name is in flight.
+    edit: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl ChannelState {
+    fn prune(&mut self, now: Instant) {
+        while self
+            .recent
+            .front()
+            .is_some_and(|&at| now.duration_since(at) >= RENAME_WINDOW)
+        {
+            self.recent.pop_front();
+        }
+    }
+
+    /// When the next rename fits in the budget: `now` if it already does.
+    fn next_slot(&self, now: Instant) -> Instant {
+        if self.recent.len() < RENAMES_PER_WINDOW {
+            now
+        } else {
+            self.recent[self.recent.len() - RENAMES_PER_WINDOW] + RENAME_WINDOW
+        }
+    }
+}
+
+/// What a flusher should do next for its channel.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Poll {
+    /// Apply this name now; the rename has been charged to the budget.
+    Rename(String),
+    /// Budget is spent; poll again at this instant.
+    Wait(Instant),
+    /// Nothing left to apply; the flusher should exit.
+    Idle,
+}
+
+pub struct RenameScheduler<C: Clock = SystemClock> {
+    clock: C,
+    channels: Mutex<HashMap<ChannelId, ChannelState>>,
+}
+
+impl Default for RenameScheduler {
+    fn default() -> Self {
+        Self::new(SystemClock)
+    }
+}
+
+impl<C: Clock> RenameScheduler<C> {
+    pub fn new(clock: C) -> Self {
+        Self {
+            clock,
+            channels: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn channels(&self) -> std::sync::MutexGuard<'_, HashMap<ChannelId, ChannelState>> {
+        self.channels
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Records `desired` as the name `channel_id` should end up with. A request matching
+    /// `current` drops any pending rename instead. Returns `true` if the caller must
+    /// start a flusher (see [`spawn_flusher`]) because none is running for the channel.
+    pub fn request(&self, channel_id: ChannelId, current: &str, desired: String) -> bool {
+        let mut channels = self.channels();
+        let state = channels.entry(channel_id).or_default();
+        if desired == current {
+            state.pending = None;
+            return false;
+        }
+        state.pending = Some(desired);
+        !std::mem::replace(&mut state.flushing, true)
+    }
+
+    /// Called by the flusher: hands out the pending name if the budget allows, charging
+    /// it, or says how long to wait. Returning [`Poll::Idle`] releases the flusher role.
+    pub fn poll(&self, channel_id: ChannelId) -> Poll {
+        let now = self.clock.now();
+        let mut channels = self.channels();
+        let Some(state) = channels.get_mut(&channel_id) else {
+            return Poll::Idle;
+        };
+        if state.pending.is_none() {
+            state.flushing = false;
+            return Poll::Idle;
+        }
+        state.prune(now);
+        let slot = state.next_slot(now);
+        if slot > now {
+            return Poll::Wait(slot);
+        }
+        state.recent.push_back(now);
+        state.charged = Some(now);
+        Poll::Rename(state.pending.take().expect("checked above"))
+    }
+
+    /// Gives back the budget charged for the rename [`Self::poll`] last handed out, whose
+    /// edit didn't happen. `requeue` is the name to try again, unless a newer one was
+    /// requested in the meantime.
+    pub fn undo(&self, channel_id: ChannelId, requeue: Option<String>) {
+        let mut channels = self.channels();
+        let Some(state) = channels.get_mut(&channel_id) else {
+            return;
+        };
+        if let Some(charged) = state.charged.take() {
+            if let Some(i) = state.recent.iter().position(|&at| at == charged) {
+                state.recent.remove(i);
+            }
+        }
+        if state.pending.is_none() {
+            state.pending = requeue;
+        }
+    }
+
+    /// Charges an immediate rename (e.g. `/rename`) to the channel's budget and drops any
+    /// pending automatic name, which would otherwise overwrite it. Returns when the
+    /// rename was charged, for [`Self::refund`], or how long to wait if the budget is spent.
+    pub fn try_rename_now(&self, channel_id: ChannelId) -> Result<Instant, Duration> {
+        let now = self.clock.now();
+        let mut channels = self.channels();
+        let state = channels.entry(channel_id).or_default();
+        state.prune(now);
+        let slot = state.next_slot(now);
+        if slot > now {
+            return Err(slot - now);
+        }
+        state.recent.push_back(now);
+        state.pending = None;
+        Ok(now)
+    }
+
+    /// Gives back a rename charged by [`Self::try_rename_now`] at `charged` whose edit
+    /// then failed, so it doesn't use up the channel's budget.
+    pub fn refund(&self, channel_id: ChannelId, charged: Instant) {
+        let mut channels = self.channels();
+        let Some(state) = channels.get_mut(&channel_id) else {
+            return;
+        };
+        if let Some(i) = state.recent.iter().position(|&at| at == charged) {
+            state.recent.remove(i);
+        }
+    }
+
+    /// The lock to hold while editing `channel_id`'s name, so edits land in order.
+    pub fn edit_lock(&self, channel_id: ChannelId) -> Arc<tokio::sync::Mutex<()>> {
+        self.channels().entry(channel_id).or_default().edit.clone()
+    }
+
+    /// Drops all state for a deleted channel. A running flusher sees [`Poll::Idle`].
+    pub fn forget(&self, channel_id: ChannelId) {
+        self.channels().remove(&channel_id);
+    }
+}
+
+/// Applies `channel_id`'s pending names as its budget allows, until none is left.
+pub fn spawn_flusher(
+    http: Arc<serenity::Http>,
+    scheduler: Arc<RenameScheduler>,
+    db: DatabaseConnection,
+    channel_id: ChannelId,
+) {
+    tokio::spawn(async move {
+        let mut failures = 0;
+        loop {
+            match scheduler.poll(channel_id) {
+                Poll::Rename(name) => {
+                    let edit = scheduler.edit_lock(channel_id);
+                    let guard = edit.lock().await;
+                    // A manual rename may have locked the name since this one was
+                    // requested; it locks before taking the edit lock, so this sees it.
+                    if !auto_named(channel_id, &db).await {
+                        scheduler.undo(channel_id, None);
+                        continue;
+                    }
+                    let result = channel_id
+                        .edit(&http, serenity::builder::EditChannel::new().name(&name))
+                        .await
+                        .requires(&[Permissions::MANAGE_CHANNELS]);
+                    drop(guard);
+                    match result {
+                        Ok(_) => failures = 0,
+                        Err(e) => {
+                            tracing::warn!("Failed to rename channel {channel_id}: {e}");
+                            // The name never landed, so it mustn't cost budget; try it
+                            // again after a pause unless it keeps failing.
+                            failures += 1;
+                            let retry = failures < MAX_ATTEMPTS;
+                            scheduler.undo(channel_id, retry.then_some(name));
+                            if retry {
+                                tokio::time::sleep(RETRY_DELAY * failures).await;
+                            }
+                        }
+                    }
+                }
+                Poll::Wait(at) => {
+                    tokio::time::sleep_until(tokio::time::Instant::from_std(at)).await
+                }
+                Poll::Idle => break,
+            }
+        }
+    });
+}
+
+/// Whether `channel_id` is still a temp channel with automatic naming on.
+async fn auto_named(channel_id: ChannelId, db: &DatabaseConnection) -> bool {
+    matches!(
+        db::repositories::temporary_channel::find(channel_id.get() as i64, db).await,
+        Ok(Some(record)) if !record.name_locked
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::MockClock;
+
+    fn scheduler() -> RenameScheduler<MockClock> {
+        RenameScheduler::new(MockClock::new())
+    }
+
+    const CHANNEL: ChannelId = ChannelId::new(1);
+
+    #[test]
+    fn first_request_starts_a_flusher_and_renames_at_once() {
+        let s = scheduler();
+        assert!(s.request(CHANNEL, "[General]", "[Chess]".into()));
+        assert_eq!(s.poll(CHANNEL), Poll::Rename("[Chess]".into()));
+        assert_eq!(s.poll(CHANNEL), Poll::Idle);
+    }
+
+    #[test]
+    fn requests_while_a_flusher_runs_do_not_start_another() {
+        let s = scheduler();
+        assert!(s.request(CHANNEL, "[General]", "[Chess]".into()));
+        assert!(!s.request(CHANNEL, "[General]", "[Go]".into()));
+        assert_eq!(s.poll(CHANNEL), Poll::Rename("[Go]".into()));
+        assert_eq!(s.poll(CHANNEL), Poll::Idle);
+        // The flusher has exited, so the next request needs a new one.
+        assert!(s.request(CHANNEL, "[Go]", "[Chess]".into()));
+    }
+
+    #[test]
+    fn third_rename_waits_for_the_window_and_applies_the_latest_name() {
+        let s = scheduler();
+        let start = s.clock.now();
+        s.request(CHANNEL, "a", "b".into());
+        assert_eq!(s.poll(CHANNEL), Poll::Rename("b".into()));
+        s.clock.advance(Duration::from_secs(60));
+        s.request(CHANNEL, "b", "c".into());
+        assert_eq!(s.poll(CHANNEL), Poll::Rename("c".into()));
+
+        s.request(CHANNEL, "c", "d".into());
+        s.request(CHANNEL, "c", "e".into());
+        assert_eq!(s.poll(CHANNEL), Poll::Wait(start + RENAME_WINDOW));
+
+        s.clock.advance(RENAME_WINDOW - Duration::from_secs(60));
+        assert_eq!(s.poll(CHANNEL), Poll::Rename("e".into()));
+        // The second rename is still inside the window, so a fourth must wait for it.
+        s.request(CHANNEL, "e", "f".into());
+        assert_eq!(
+            s.poll(CHANNEL),
+            Poll::Wait(start + Duration::from_secs(60) + RENAME_WINDOW)
+        );
+    }
+
+    #[test]
+    fn requesting_the_current_name_cancels_the_pending_rename() {
+        let s = scheduler();
+        s.request(CHANNEL, "a", "b".into());
+        s.poll(CHANNEL);
+        s.request(CHANNEL, "b", "c".into());
+        s.poll(CHANNEL);
+        s.request(CHANNEL, "c", "d".into());
+        assert!(matches!(s.poll(CHANNEL), Poll::Wait(_)));
+
+        assert!(!s.request(CHANNEL, "c", "c".into()));
+        assert_eq!(s.poll(CHANNEL), Poll::Idle);
+    }
+
+    #[test]
+    fn channels_have_independent_budgets() {
+        let s = scheduler();
+        let other = ChannelId::new(2);
+        for name in ["b", "c"] {
+            s.request(CHANNEL, "a", name.into());
+            s.poll(CHANNEL);
+        }
+        s.request(CHANNEL, "c", "d".into());
+        assert!(matches!(s.poll(CHANNEL), Poll::Wait(_)));
+
+        assert!(s.request(other, "a", "b".into()));
+        assert_eq!(s.poll(other), Poll::Rename("b".into()));
+    }
+
+    #[test]
+    fn manual_renames_share_the_budget_and_drop_pending_names() {
+        let s = scheduler();
+        s.request(CHANNEL, "a", "b".into());
+        s.poll(CHANNEL);
+        s.request(CHANNEL, "b", "c".into());
+        assert_eq!(s.try_rename_now(CHANNEL), Ok(s.clock.now()));
+        assert_eq!(s.poll(CHANNEL), Poll::Idle);
+
+        assert_eq!(s.try_rename_now(CHANNEL), Err(RENAME_WINDOW));
+        s.clock.advance(Duration::from_secs(90));
+        assert_eq!(
+            s.try_rename_now(CHANNEL),
+            Err(RENAME_WINDOW - Duration::from_secs(90))
+        );
+    }
+
+    #[test]
+    fn a_refunded_rename_frees_its_slot() {
+        let s = scheduler();
+        s.request(CHANNEL, "a", "b".into());
+        s.poll(CHANNEL);
+        s.clock.advance(Duration::from_secs(30));
+        let charged = s.try_rename_now(CHANNEL).unwrap();
+        assert!(s.try_rename_now(CHANNEL).is_err());
+
+        s.refund(CHANNEL, charged);
+        assert_eq!(s.try_rename_now(CHANNEL), Ok(charged));
+    }
+
+    #[test]
+    fn a_failed_rename_is_refunded_and_requeued() {
+        let s = scheduler();
+        s.request(CHANNEL, "a", "b".into());
+        assert_eq!(s.poll(CHANNEL), Poll::Rename("b".into()));
+        s.clock.advance(Duration::from_secs(60));
+        s.request(CHANNEL, "a", "c".into());
+        assert_eq!(s.poll(CHANNEL), Poll::Rename("c".into()));
+
+        // The edit to "c" failed: it's handed out again at once, on the same budget.
+        s.undo(CHANNEL, Some("c".into()));
+        assert_eq!(s.poll(CHANNEL), Poll::Rename("c".into()));
+        assert!(s.try_rename_now(CHANNEL).is_err());
+    }
+
+    #[test]
+    fn undo_keeps_a_newer_request() {
+        let s = scheduler();
+        s.request(CHANNEL, "a", "b".into());
+        assert_eq!(s.poll(CHANNEL), Poll::Rename("b".into()));
+        s.request(CHANNEL, "a", "c".into());
+
+        s.undo(CHANNEL, Some("b".into()));
+        assert_eq!(s.poll(CHANNEL), Poll::Rename("c".into()));
+        // Giving up on a name still returns its slot.
+        s.undo(CHANNEL, None);
+        assert_eq!(s.poll(CHANNEL), Poll::Idle);
+        assert!(s.try_rename_now(CHANNEL).is_ok());
+        assert!(s.try_rename_now(CHANNEL).is_ok());
+    }
+
+    #[test]
+    fn forget_stops_the_flusher() {
+        let s = scheduler();
+        s.request(CHANNEL, "a", "b".into());
+        s.forget(CHANNEL);
+        assert_eq!(s.poll(CHANNEL), Poll::Idle);
+    }
+}
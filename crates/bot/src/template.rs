@@ -0,0 +1,313 @@
+//! Channel-name template language.
+//!
+//! A template is literal text with `{placeholder}` substitutions, each optionally
+//! followed by a fallback used when the value is unknown: `{game|Hangout}`. Literal
+//! braces are written `{{` and `}}`.
+//!
+//! | Placeholder  | Value                                             | Default     |
+//! |--------------|---------------------------------------------------|-------------|
+//! | `{game}`     | Activity most members share                       | `General`   |
+//! | `{activity}` | What that activity is: Playing, Streaming, …      | `Chatting`  |
+//! | `{emoji}`    | Emoji for that activity                           | `💬`        |
+//! | `{owner}`    | Channel owner's display name                      | `Someone`   |
+//! | `{count}`    | Members currently in the channel                  | —           |
+//! | `{n}`        | Ordinal among channels from the same trigger      | —           |
+
+/// The kind of Discord activity a channel is named after.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ActivityKind {
+    Playing,
+    Streaming,
+    Listening,
+    Competing,
+}
+
+impl ActivityKind {
+    fn verb(self) -> &'static str {
+        match self {
+            Self::Playing => "Playing",
+            Self::Streaming => "Streaming",
+            Self::Listening => "Listening",
+            Self::Competing => "Competing",
+        }
+    }
+
+    fn emoji(self) -> &'static str {
+        match self {
+            Self::Playing => "🎮",
+            Self::Streaming => "📺",
+            Self::Listening => "🎧",
+            Self::Competing => "🏆",
+        }
+    }
+}
+
+/// Values available to a template when it is rendered. `None` means unknown, which
+/// renders the placeholder's fallback or built-in default.
+#[derive(Clone, Debug, Default)]
+pub struct Vars<'a> {
+    pub game: Option<&'a str>,
+    pub activity: Option<ActivityKind>,
+    pub owner: Option<&'a str>,
+    pub count: usize,
+    pub ordinal: i64,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Placeholder {
+    Game,
+    Activity,
+    Emoji,
+    Owner,
+    Count,
+    Ordinal,
+}
+
+impl Placeholder {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "game" => Self::Game,
+            "activity" => Self::Activity,
+            "emoji" => Self::Emoji,
+            "owner" => Self::Owner,
+            "count" => Self::Count,
+            "n" => Self::Ordinal,
+            _ => return None,
+        })
+    }
+
+    /// Rendered when the value is unknown and the template gives no fallback.
+    fn default_value(self) -> &'static str {
+        match self {
+            Self::Game => "General",
+            Self::Activity => "Chatting",
+            Self::Emoji => "💬",
+            Self::Owner => "Someone",
+            Self::Count | Self::Ordinal => "",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Placeholder {
+        placeholder: Placeholder,
+        fallback: Option<String>,
+    },
+}
+
+/// Why a template failed to parse. Positions are 1-based character offsets.
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum TemplateError {
+    #[error("`{{` at position {0} is never closed")]
+    Unclosed(usize),
+    #[error("unexpected `}}` at position {0} (write `}}}}` for a literal brace)")]
+    UnexpectedClose(usize),
+    #[error("empty placeholder at position {0}")]
+    Empty(usize),
+    #[error(
+        "unknown placeholder `{{{0}}}` — use {{game}}, {{activity}}, {{emoji}}, {{owner}}, \
+         {{count}} or {{n}}"
+    )]
+    Unknown(String),
+}
+
+/// A parsed channel-name template.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+impl Template {
+    pub fn parse(source: &str) -> Result<Self, TemplateError> {
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut chars = source.chars().enumerate().peekable();
+
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '{' if chars.peek().map(|&(_, c)| c) == Some('{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek().map(|&(_, c)| c) == Some('}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '}' => return Err(TemplateError::UnexpectedClose(i + 1)),
+                '{' => {
+                    let mut body = String::new();
+                    loop {
+                        match chars.next() {
+                            Some((_, '}')) => break,
+                            Some((_, '{')) | None => return Err(TemplateError::Unclosed(i + 1)),
+                            Some((_, c)) => body.push(c),
+                        }
+                    }
+                    let (name, fallback) = match body.split_once('|') {
+                        Some((name, fallback)) => (name, Some(fallback.to_owned())),
+                        None => (body.as_str(), None),
+                    };
+                    if name.is_empty() {
+                        return Err(TemplateError::Empty(i + 1));
+                    }
+                    let placeholder = Placeholder::from_name(name)
+                        .ok_or_else(|| TemplateError::Unknown(name.to_owned()))?;
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(Segment::Placeholder {
+                        placeholder,
+                        fallback,
+                    });
+                }
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+
+        Ok(Self { segments })
+    }
+
+    pub fn render(&self, vars: &Vars) -> String {
+        let mut out = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(text) => out.push_str(text),
+                Segment::Placeholder {
+                    placeholder,
+                    fallback,
+                } => {
+                    let value = match placeholder {
+                        Placeholder::Game => vars.game.map(str::to_owned),
+                        Placeholder::Activity => vars.activity.map(|a| a.verb().to_owned()),
+                        Placeholder::Emoji => vars.activity.map(|a| a.emoji().to_owned()),
+                        Placeholder::Owner => vars.owner.map(str::to_owned),
+                        Placeholder::Count => Some(vars.count.to_string()),
+                        Placeholder::Ordinal => Some(vars.ordinal.to_string()),
+                    };
+                    let text = value
+                        .or_else(|| fallback.clone())
+                        .unwrap_or_else(|| placeholder.default_value().to_owned());
+                    out.push_str(&text);
+                }
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(source: &str, vars: &Vars) -> String {
+        Template::parse(source).unwrap().render(vars)
+    }
+
+    #[test]
+    fn literal_text_renders_unchanged() {
+        assert_eq!(render("Voice Chat", &Vars::default()), "Voice Chat");
+    }
+
+    #[test]
+    fn game_placeholder_defaults_to_general() {
+        assert_eq!(render("[{game}]", &Vars::default()), "[General]");
+    }
+
+    #[test]
+    fn fallback_replaces_the_default() {
+        assert_eq!(render("{game|Hangout}", &Vars::default()), "Hangout");
+        let vars = Vars {
+            game: Some("Chess"),
+            ..Default::default()
+        };
+        assert_eq!(render("{game|Hangout}", &vars), "Chess");
+    }
+
+    #[test]
+    fn empty_fallback_is_allowed() {
+        assert_eq!(render("Room{game|}", &Vars::default()), "Room");
+    }
+
+    #[test]
+    fn every_placeholder_renders_its_value() {
+        let vars = Vars {
+            game: Some("Chess"),
+            activity: Some(ActivityKind::Streaming),
+            owner: Some("Alice"),
+            count: 4,
+            ordinal: 3,
+        };
+        assert_eq!(
+            render(
+                "{emoji} {activity} {game} · {owner} · {count} · Squad {n}",
+                &vars
+            ),
+            "📺 Streaming Chess · Alice · 4 · Squad 3"
+        );
+    }
+
+    #[test]
+    fn unknown_activity_uses_defaults() {
+        assert_eq!(
+            render("{emoji} {activity}", &Vars::default()),
+            "💬 Chatting"
+        );
+    }
+
+    #[test]
+    fn doubled_braces_are_literal() {
+        assert_eq!(render("{{{game}}}", &Vars::default()), "{General}");
+    }
+
+    #[test]
+    fn unclosed_placeholder_is_rejected() {
+        assert_eq!(Template::parse("[{game"), Err(TemplateError::Unclosed(2)));
+    }
+
+    #[test]
+    fn nested_brace_is_rejected_as_unclosed() {
+        assert_eq!(
+            Template::parse("{game{owner}}"),
+            Err(TemplateError::Unclosed(1))
+        );
+    }
+
+    #[test]
+    fn stray_closing_brace_is_rejected() {
+        assert_eq!(
+            Template::parse("game}"),
+            Err(TemplateError::UnexpectedClose(5))
+        );
+    }
+
+    #[test]
+    fn empty_placeholder_is_rejected() {
+        assert_eq!(Template::parse("a {}"), Err(TemplateError::Empty(3)));
+        assert_eq!(Template::parse("{|x}"), Err(TemplateError::Empty(1)));
+    }
+
+    #[test]
+    fn unknown_placeholder_is_rejected() {
+        assert_eq!(
+            Template::parse("{gmae}"),
+            Err(TemplateError::Unknown("gmae".to_owned()))
+        );
+    }
+
+    #[test]
+    fn error_messages_name_the_problem() {
+        assert_eq!(
+            TemplateError::Unknown("x".to_owned()).to_string(),
+            "unknown placeholder `{x}` — use {game}, {activity}, {emoji}, {owner}, {count} or {n}"
+        );
+        assert_eq!(
+            TemplateError::UnexpectedClose(3).to_string(),
+            "unexpected `}` at position 3 (write `}}` for a literal brace)"
+        );
+    }
+}
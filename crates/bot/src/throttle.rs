@@ -0,0 +1,168 @@
+//! Per-member token buckets on temp-channel creation.
+//!
+//! Every entry into a trigger that would create a channel takes a token from the
+//! member's bucket for that guild. A bucket holds up to `burst` tokens and regains one
+//! every `refill` seconds, so a member can create a few channels in a row but not keep
+//! hopping in and out of a trigger, which would burn the guild's channel-create rate
+//! limit for everyone. Buckets live in memory only; a restart hands everyone a full one.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use poise::serenity_prelude::{GuildId, UserId};
+
+use crate::time::{Clock, SystemClock};
+
+#[derive(Clone, Copy, Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    /// Adds the tokens earned since the last update, up to `burst`.
+    fn refill(&mut self, burst: f64, refill: Duration, now: Instant) {
+        let earned = now.duration_since(self.updated).as_secs_f64() / refill.as_secs_f64();
+        self.tokens = (self.tokens + earned).min(burst);
+        self.updated = now;
+    }
+
+    /// When the bucket is back to `burst` tokens and no longer worth keeping.
+    fn full_at(&self, burst: f64, refill: Duration) -> Instant {
+        self.updated + refill.mul_f64((burst - self.tokens).max(0.0))
+    }
+}
+
+pub struct CreationLimiter<C: Clock = SystemClock> {
+    clock: C,
+    buckets: Mutex<HashMap<(GuildId, UserId), (Bucket, Instant)>>,
+}
+
+impl Default for CreationLimiter {
+    fn default() -> Self {
+        Self::new(SystemClock)
+    }
+}
+
+impl<C: Clock> CreationLimiter<C> {
+    pub fn new(clock: C) -> Self {
+        Self {
+            clock,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Takes a token from the member's bucket, returning `false` if it's empty. `burst`
+    /// of 0 disables the limit.
+    pub fn try_acquire(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        burst: u32,
+        refill: Duration,
+    ) -> bool {
+        if burst == 0 || refill.is_zero() {
+            return true;
+        }
+        let now = self.clock.now();
+        let burst = f64::from(burst);
+        let mut buckets = self
+            .buckets
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        // Full buckets behave exactly like missing ones; drop them so the map stays small.
+        buckets.retain(|_, (_, full_at)| *full_at > now);
+
+        let (bucket, full_at) = buckets.entry((guild_id, user_id)).or_insert((
+            Bucket {
+                tokens: burst,
+                updated: now,
+            },
+            now,
+        ));
+        bucket.refill(burst, refill, now);
+        let admitted = bucket.tokens >= 1.0;
+        if admitted {
+            bucket.tokens -= 1.0;
+        }
+        *full_at = bucket.full_at(burst, refill);
+        admitted
+    }
+
+    /// Refills the member's bucket, e.g. after an admin lifts their ban.
+    pub fn reset(&self, guild_id: GuildId, user_id: UserId) {
+        self.buckets
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .remove(&(guild_id, user_id));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::MockClock;
+
+    fn limiter() -> CreationLimiter<MockClock> {
+        CreationLimiter::new(MockClock::new())
+    }
+
+    const GUILD: GuildId = GuildId::new(1);
+    const USER: UserId = UserId::new(2);
+    const REFILL: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn allows_a_burst_then_refuses() {
+        let l = limiter();
+        assert!(l.try_acquire(GUILD, USER, 3, REFILL));
+        assert!(l.try_acquire(GUILD, USER, 3, REFILL));
+        assert!(l.try_acquire(GUILD, USER, 3, REFILL));
+        assert!(!l.try_acquire(GUILD, USER, 3, REFILL));
+    }
+
+    #[test]
+    fn regains_one_token_per_refill_period() {
+        let l = limiter();
+        assert!(l.try_acquire(GUILD, USER, 1, REFILL));
+        l.clock.advance(REFILL / 2);
+        assert!(!l.try_acquire(GUILD, USER, 1, REFILL));
+        l.clock.advance(REFILL / 2);
+        assert!(l.try_acquire(GUILD, USER, 1, REFILL));
+    }
+
+    #[test]
+    fn buckets_are_per_member_and_per_guild() {
+        let l = limiter();
+        assert!(l.try_acquire(GUILD, USER, 1, REFILL));
+        assert!(l.try_acquire(GUILD, UserId::new(3), 1, REFILL));
+        assert!(l.try_acquire(GuildId::new(9), USER, 1, REFILL));
+        assert!(!l.try_acquire(GUILD, USER, 1, REFILL));
+    }
+
+    #[test]
+    fn reset_refills_the_bucket() {
+        let l = limiter();
+        assert!(l.try_acquire(GUILD, USER, 1, REFILL));
+        assert!(!l.try_acquire(GUILD, USER, 1, REFILL));
+        l.reset(GUILD, USER);
+        assert!(l.try_acquire(GUILD, USER, 1, REFILL));
+    }
+
+    #[test]
+    fn zero_burst_disables_the_limit() {
+        let l = limiter();
+        for _ in 0..10 {
+            assert!(l.try_acquire(GUILD, USER, 0, REFILL));
+        }
+    }
+
+    #[test]
+    fn full_buckets_are_dropped() {
+        let l = limiter();
+        assert!(l.try_acquire(GUILD, USER, 2, REFILL));
+        l.clock.advance(REFILL);
+        assert!(l.try_acquire(GUILD, UserId::new(3), 2, REFILL));
+        assert_eq!(l.buckets.lock().unwrap().len(), 1);
+    }
+}
@@ -1,3 +1,5 @@
+use std::time::Instant;
+
 /// Current Unix timestamp in seconds.
 pub fn now_unix() -> i64 {
     std::time::SystemTime::now()
@@ -5,3 +7,38 @@ pub fn now_unix() -> i64 {
         .unwrap_or_default()
         .as_secs() as i64
 }
+
+/// Source of the current time, swappable so tests can drive time-based state by hand.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// A clock that only moves when told to.
+#[cfg(test)]
+pub(crate) struct MockClock(std::sync::Mutex<Instant>);
+
+#[cfg(test)]
+impl MockClock {
+    pub(crate) fn new() -> Self {
+        Self(std::sync::Mutex::new(Instant::now()))
+    }
+
+    pub(crate) fn advance(&self, by: std::time::Duration) {
+        *self.0.lock().unwrap() += by;
+    }
+}
+
+#[cfg(test)]
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        *self.0.lock().unwrap()
+    }
+}
@@ -0,0 +1,38 @@
+use sea_orm::entity::prelude::*;
+
+/// One entry on a channel owner's permit (whitelist) or reject (blacklist) list,
+/// applied to every temp channel they own in the guild.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "access_rules")]
+pub struct Model {
+    /// The owner whose list this entry belongs to.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub user_id: i64,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub guild_id: i64,
+    /// User or role id, per `is_role`.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub target_id: i64,
+    pub is_role: bool,
+    /// `true` for `/permit`, `false` for `/reject`.
+    pub allowed: bool,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::guild::Entity",
+        from = "Column::GuildId",
+        to = "super::guild::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Guild,
+}
+
+impl Related<super::guild::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Guild.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
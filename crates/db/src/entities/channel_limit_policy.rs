@@ -0,0 +1,36 @@
+use sea_orm::entity::prelude::*;
+
+/// Per-guild caps on temp channels, set with `/config channel-limits`. Per-trigger caps
+/// live on `primary_channels.max_channels`.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "channel_limit_policies")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub guild_id: i64,
+    /// Most temp channels the guild may have at once; `None` is unlimited.
+    pub max_per_guild: Option<i64>,
+    /// Most live temp channels one member may have created; `None` is unlimited.
+    pub max_per_user: Option<i64>,
+    /// At a cap, move the member into an existing channel from the same trigger when
+    /// one has room, instead of only turning them away.
+    pub move_when_full: bool,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::guild::Entity",
+        from = "Column::GuildId",
+        to = "super::guild::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Guild,
+}
+
+impl Related<super::guild::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Guild.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
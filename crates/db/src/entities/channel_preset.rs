@@ -0,0 +1,38 @@
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "channel_presets")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub user_id: i64,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub guild_id: i64,
+    pub name: Option<String>,
+    pub user_limit: Option<i64>,
+    pub private: bool,
+    /// Bits per second, as Discord reports it.
+    pub bitrate: Option<i64>,
+    /// Voice region id (e.g. `rotterdam`); `None` lets Discord pick automatically.
+    pub rtc_region: Option<String>,
+    /// Discord's video quality mode: 1 = auto, 2 = full (720p).
+    pub video_quality_mode: Option<i64>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::guild::Entity",
+        from = "Column::GuildId",
+        to = "super::guild::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Guild,
+}
+
+impl Related<super::guild::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Guild.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
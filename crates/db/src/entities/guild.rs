@@ -6,6 +6,16 @@ pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub id: i64,
     pub channel_name_template: Option<String>,
+    /// Seconds an empty temp channel is kept before deletion; `None` uses the bot's default.
+    pub empty_grace_secs: Option<i64>,
+    /// When the bot was removed from the guild; cleared if it's added back.
+    pub removed_at: Option<i64>,
+    /// Days the guild's data is kept after the bot is removed; `None` uses the bot's
+    /// default.
+    pub retention_days: Option<i64>,
+    /// Days finished voice sessions are kept in `voice_session_history`; `None` uses the
+    /// bot's default.
+    pub history_days: Option<i64>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -0,0 +1,35 @@
+use sea_orm::entity::prelude::*;
+
+/// Per-guild settings for the idle-channel sweeper, set with `/config idle`.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "idle_policies")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub guild_id: i64,
+    /// How long every member of a channel must be idle before they're warned; 0 turns
+    /// the sweeper off.
+    pub threshold_secs: i64,
+    /// How long after the warning the members are moved out.
+    pub warning_secs: i64,
+    /// Disconnect idle members instead of moving them to the guild's AFK channel.
+    pub disconnect: bool,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::guild::Entity",
+        from = "Column::GuildId",
+        to = "super::guild::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Guild,
+}
+
+impl Related<super::guild::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Guild.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
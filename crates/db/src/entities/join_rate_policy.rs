@@ -0,0 +1,36 @@
+use sea_orm::entity::prelude::*;
+
+/// Per-guild limits on how fast one member can create temp channels, set with
+/// `/config join-rate`.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "join_rate_policies")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub guild_id: i64,
+    /// Channels a member can create back to back; 0 turns the limit off.
+    pub burst: i64,
+    /// Seconds for the member to earn back one more channel.
+    pub refill_secs: i64,
+    /// How long a member who runs out is kept from creating channels; 0 only refuses
+    /// the attempt.
+    pub ban_secs: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::guild::Entity",
+        from = "Column::GuildId",
+        to = "super::guild::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Guild,
+}
+
+impl Related<super::guild::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Guild.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
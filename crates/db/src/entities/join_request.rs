@@ -0,0 +1,34 @@
+use sea_orm::entity::prelude::*;
+
+/// A pending Allow/Deny prompt posted in a private channel's text area.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "join_requests")]
+pub struct Model {
+    /// The private temp channel being asked to join; the prompt is posted in its chat.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub channel_id: i64,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub requester_id: i64,
+    pub message_id: i64,
+    /// Unix seconds after which the prompt is expired by the sweep.
+    pub expires_at: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::temporary_channel::Entity",
+        from = "Column::ChannelId",
+        to = "super::temporary_channel::Column::Id",
+        on_delete = "Cascade"
+    )]
+    TemporaryChannel,
+}
+
+impl Related<super::temporary_channel::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TemporaryChannel.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,36 @@
+use sea_orm::entity::prelude::*;
+
+/// Per-(channel, requester) counters behind join-request cooldowns and auto-denial.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "join_request_stats")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub channel_id: i64,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub requester_id: i64,
+    /// Unix seconds when the requester's last prompt was posted.
+    pub last_requested_at: i64,
+    /// Denials since `first_denied_at`.
+    pub denials: i64,
+    /// Unix seconds when the current denial window started.
+    pub first_denied_at: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::temporary_channel::Entity",
+        from = "Column::ChannelId",
+        to = "super::temporary_channel::Column::Id",
+        on_delete = "Cascade"
+    )]
+    TemporaryChannel,
+}
+
+impl Related<super::temporary_channel::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TemporaryChannel.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
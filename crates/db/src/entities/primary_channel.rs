@@ -6,6 +6,23 @@ pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub id: i64,
     pub guild_id: i64,
+    /// Overrides `guilds.channel_name_template` for channels spawned from this trigger.
+    pub name_template: Option<String>,
+    pub user_limit: Option<i64>,
+    /// Bits per second, as Discord reports it.
+    pub bitrate: Option<i64>,
+    /// Category new channels are created in, instead of the trigger's own parent.
+    pub category_id: Option<i64>,
+    pub private: bool,
+    /// Voice region id (e.g. `rotterdam`); `None` lets Discord pick automatically.
+    pub rtc_region: Option<String>,
+    /// Discord's video quality mode: 1 = auto, 2 = full (720p).
+    pub video_quality_mode: Option<i64>,
+    /// Most temp channels this trigger may have at once; `None` is unlimited.
+    pub max_channels: Option<i64>,
+    /// Move joiners into an existing public channel from this trigger with a free slot,
+    /// creating a new one only when all are full.
+    pub fill_existing: bool,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -8,6 +8,20 @@ pub struct Model {
     pub guild_id: i64,
     pub primary_channel_id: i64,
     pub join_channel_id: Option<i64>,
+    pub owner_id: Option<i64>,
+    /// 1-based position among channels from the same trigger, for the `{n}` placeholder.
+    pub ordinal: i64,
+    /// Set when the channel was named by hand, so automatic naming leaves it alone.
+    pub name_locked: bool,
+    /// Set by `/hide`: @everyone is denied VIEW_CHANNEL.
+    pub hidden: bool,
+    /// When the now-empty channel is due for deletion; cleared if someone rejoins.
+    pub delete_at: Option<i64>,
+    /// The activity the channel was last automatically named after, if any.
+    pub game: Option<String>,
+    /// Who created the channel, for the per-member cap. Unlike `owner_id`, `/transfer`
+    /// and `/claim` don't change it.
+    pub creator_id: Option<i64>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
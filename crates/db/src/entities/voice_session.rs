@@ -8,6 +8,18 @@ pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub guild_id: i64,
     pub joined_at: i64,
+    /// When the member went idle (self-deafened or away), if they still are. Checkpoints
+    /// leave it alone; only the part after `joined_at` is still to be accounted for.
+    pub idle_since: Option<i64>,
+    /// Idle time already finished within this session.
+    pub idle_secs: i64,
+    /// Active time already credited as XP by checkpoints, so the session's full length
+    /// is `credited_secs` plus whatever has accrued since `joined_at`.
+    pub credited_secs: i64,
+    /// The temp channel the session is in; `None` for sessions from before it was kept.
+    pub channel_id: Option<i64>,
+    /// When the member joined the channel. Unlike `joined_at`, checkpoints don't move it.
+    pub started_at: i64,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -0,0 +1,39 @@
+use sea_orm::entity::prelude::*;
+
+/// A finished voice session, kept for `/history` until the guild's history period ends.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "voice_session_history")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub user_id: i64,
+    pub guild_id: i64,
+    pub channel_id: Option<i64>,
+    /// The trigger the channel was created from.
+    pub primary_channel_id: Option<i64>,
+    pub started_at: i64,
+    pub ended_at: i64,
+    /// Active seconds, i.e. the time credited as XP; idle time is left out.
+    pub duration_secs: i64,
+    /// The activity the channel was named after when the session ended.
+    pub game: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::guild::Entity",
+        from = "Column::GuildId",
+        to = "super::guild::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Guild,
+}
+
+impl Related<super::guild::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Guild.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
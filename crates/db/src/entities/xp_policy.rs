@@ -0,0 +1,41 @@
+use sea_orm::entity::prelude::*;
+
+/// Per-guild XP rules and level curve, set with `/config xp`.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "xp_policies")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub guild_id: i64,
+    /// XP earned per second of active voice time.
+    pub xp_per_second: f64,
+    /// Sessions shorter than this earn nothing.
+    pub min_session_secs: i64,
+    pub daily_bonus_xp: i64,
+    /// Seconds after the last daily bonus before the next one can be earned.
+    pub daily_early_secs: i64,
+    /// Seconds after the last daily bonus until the streak breaks.
+    pub daily_late_secs: i64,
+    /// XP from level 1 to 2.
+    pub level_base: i64,
+    /// How much each level up to 100 costs relative to the one before.
+    pub level_growth: f64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::guild::Entity",
+        from = "Column::GuildId",
+        to = "super::guild::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Guild,
+}
+
+impl Related<super::guild::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Guild.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,80 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m00013_create_channel_presets"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ChannelPresets::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ChannelPresets::UserId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ChannelPresets::GuildId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ChannelPresets::Name).string().null())
+                    .col(
+                        ColumnDef::new(ChannelPresets::UserLimit)
+                            .big_integer()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(ChannelPresets::Private)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(ColumnDef::new(ChannelPresets::Bitrate).big_integer().null())
+                    .primary_key(
+                        Index::create()
+                            .col(ChannelPresets::UserId)
+                            .col(ChannelPresets::GuildId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(ChannelPresets::Table, ChannelPresets::GuildId)
+                            .to(Guilds::Table, Guilds::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ChannelPresets::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Guilds {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+pub enum ChannelPresets {
+    Table,
+    UserId,
+    GuildId,
+    Name,
+    UserLimit,
+    Private,
+    Bitrate,
+}
@@ -0,0 +1,83 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m00014_add_settings_to_primary_channels"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite only accepts one ADD COLUMN per ALTER TABLE statement.
+        let columns = [
+            ColumnDef::new(PrimaryChannels::NameTemplate)
+                .string()
+                .null()
+                .to_owned(),
+            ColumnDef::new(PrimaryChannels::UserLimit)
+                .big_integer()
+                .null()
+                .to_owned(),
+            ColumnDef::new(PrimaryChannels::Bitrate)
+                .big_integer()
+                .null()
+                .to_owned(),
+            ColumnDef::new(PrimaryChannels::CategoryId)
+                .big_integer()
+                .null()
+                .to_owned(),
+            ColumnDef::new(PrimaryChannels::Private)
+                .boolean()
+                .not_null()
+                .default(false)
+                .to_owned(),
+        ];
+        for mut column in columns {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(PrimaryChannels::Table)
+                        .add_column(&mut column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite cannot drop columns; recreate the table without the settings columns.
+        // primary_channels is a parent table for temporary_channels, so foreign_keys must
+        // be off for the swap.
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "PRAGMA foreign_keys = OFF;
+                CREATE TABLE primary_channels_new (
+                    id       BIGINT NOT NULL PRIMARY KEY,
+                    guild_id BIGINT NOT NULL,
+                    FOREIGN KEY (guild_id) REFERENCES guilds(id) ON DELETE CASCADE
+                );
+                INSERT INTO primary_channels_new (id, guild_id)
+                    SELECT id, guild_id FROM primary_channels;
+                DROP TABLE primary_channels;
+                ALTER TABLE primary_channels_new RENAME TO primary_channels;
+                PRAGMA foreign_keys = ON;",
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum PrimaryChannels {
+    Table,
+    NameTemplate,
+    UserLimit,
+    Bitrate,
+    CategoryId,
+    Private,
+}
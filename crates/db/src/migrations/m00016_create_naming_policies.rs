@@ -0,0 +1,95 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m00016_create_naming_policies"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(NamingPolicies::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(NamingPolicies::GuildId)
+                            .big_integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(NamingPolicies::Plurality)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(
+                        ColumnDef::new(NamingPolicies::ThresholdPercent)
+                            .big_integer()
+                            .not_null()
+                            .default(50),
+                    )
+                    .col(
+                        ColumnDef::new(NamingPolicies::CountStreaming)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(
+                        ColumnDef::new(NamingPolicies::CountListening)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(
+                        ColumnDef::new(NamingPolicies::CountCompeting)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(
+                        ColumnDef::new(NamingPolicies::IgnoreBots)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(NamingPolicies::Table, NamingPolicies::GuildId)
+                            .to(Guilds::Table, Guilds::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(NamingPolicies::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Guilds {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+pub enum NamingPolicies {
+    Table,
+    GuildId,
+    Plurality,
+    ThresholdPercent,
+    CountStreaming,
+    CountListening,
+    CountCompeting,
+    IgnoreBots,
+}
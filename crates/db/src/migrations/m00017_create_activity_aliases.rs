@@ -0,0 +1,65 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m00017_create_activity_aliases"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ActivityAliases::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ActivityAliases::GuildId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ActivityAliases::Activity)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ActivityAliases::Alias).string().null())
+                    .primary_key(
+                        Index::create()
+                            .col(ActivityAliases::GuildId)
+                            .col(ActivityAliases::Activity),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(ActivityAliases::Table, ActivityAliases::GuildId)
+                            .to(Guilds::Table, Guilds::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ActivityAliases::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Guilds {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+pub enum ActivityAliases {
+    Table,
+    GuildId,
+    Activity,
+    Alias,
+}
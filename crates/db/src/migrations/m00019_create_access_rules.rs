@@ -0,0 +1,70 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m00019_create_access_rules"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(AccessRules::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(AccessRules::UserId).big_integer().not_null())
+                    .col(
+                        ColumnDef::new(AccessRules::GuildId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AccessRules::TargetId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(AccessRules::IsRole).boolean().not_null())
+                    .col(ColumnDef::new(AccessRules::Allowed).boolean().not_null())
+                    .primary_key(
+                        Index::create()
+                            .col(AccessRules::UserId)
+                            .col(AccessRules::GuildId)
+                            .col(AccessRules::TargetId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(AccessRules::Table, AccessRules::GuildId)
+                            .to(Guilds::Table, Guilds::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AccessRules::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Guilds {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+pub enum AccessRules {
+    Table,
+    UserId,
+    GuildId,
+    TargetId,
+    IsRole,
+    Allowed,
+}
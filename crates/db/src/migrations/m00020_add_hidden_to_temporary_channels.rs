@@ -0,0 +1,61 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m00020_add_hidden_to_temporary_channels"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(TemporaryChannels::Table)
+                    .add_column(
+                        ColumnDef::new(TemporaryChannels::Hidden)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite cannot drop columns; recreate the table without hidden.
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "CREATE TABLE temporary_channels_new (
+                    id                 BIGINT NOT NULL PRIMARY KEY,
+                    guild_id           BIGINT NOT NULL,
+                    primary_channel_id BIGINT NOT NULL,
+                    join_channel_id    BIGINT,
+                    owner_id           BIGINT,
+                    ordinal            BIGINT NOT NULL DEFAULT 1,
+                    name_locked        BOOLEAN NOT NULL DEFAULT FALSE,
+                    FOREIGN KEY (guild_id) REFERENCES guilds(id) ON DELETE CASCADE,
+                    FOREIGN KEY (primary_channel_id) REFERENCES primary_channels(id) ON DELETE CASCADE
+                );
+                INSERT INTO temporary_channels_new
+                    SELECT id, guild_id, primary_channel_id, join_channel_id, owner_id, ordinal,
+                           name_locked
+                    FROM temporary_channels;
+                DROP TABLE temporary_channels;
+                ALTER TABLE temporary_channels_new RENAME TO temporary_channels;",
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum TemporaryChannels {
+    Table,
+    Hidden,
+}
@@ -0,0 +1,75 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m00021_create_join_requests"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(JoinRequests::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(JoinRequests::ChannelId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(JoinRequests::RequesterId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(JoinRequests::MessageId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(JoinRequests::ExpiresAt)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(JoinRequests::ChannelId)
+                            .col(JoinRequests::RequesterId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(JoinRequests::Table, JoinRequests::ChannelId)
+                            .to(TemporaryChannels::Table, TemporaryChannels::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(JoinRequests::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TemporaryChannels {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+pub enum JoinRequests {
+    Table,
+    ChannelId,
+    RequesterId,
+    MessageId,
+    ExpiresAt,
+}
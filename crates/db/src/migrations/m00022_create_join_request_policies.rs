@@ -0,0 +1,60 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m00022_create_join_request_policies"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(JoinRequestPolicies::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(JoinRequestPolicies::GuildId)
+                            .big_integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(JoinRequestPolicies::TimeoutSecs)
+                            .big_integer()
+                            .not_null()
+                            .default(120),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(JoinRequestPolicies::Table, JoinRequestPolicies::GuildId)
+                            .to(Guilds::Table, Guilds::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(JoinRequestPolicies::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Guilds {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+pub enum JoinRequestPolicies {
+    Table,
+    GuildId,
+    TimeoutSecs,
+}
@@ -0,0 +1,63 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m00023_add_spam_limits_to_join_request_policies"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite only supports one ADD COLUMN per ALTER TABLE.
+        for (column, default) in [
+            (JoinRequestPolicies::CooldownSecs, 60),
+            (JoinRequestPolicies::MaxDenials, 3),
+            (JoinRequestPolicies::DenialWindowSecs, 3600),
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(JoinRequestPolicies::Table)
+                        .add_column(
+                            ColumnDef::new(column)
+                                .big_integer()
+                                .not_null()
+                                .default(default),
+                        )
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite cannot drop columns; recreate the table without the spam limits.
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "CREATE TABLE join_request_policies_new (
+                    guild_id     BIGINT NOT NULL PRIMARY KEY,
+                    timeout_secs BIGINT NOT NULL DEFAULT 120,
+                    FOREIGN KEY (guild_id) REFERENCES guilds(id) ON DELETE CASCADE
+                );
+                INSERT INTO join_request_policies_new
+                    SELECT guild_id, timeout_secs FROM join_request_policies;
+                DROP TABLE join_request_policies;
+                ALTER TABLE join_request_policies_new RENAME TO join_request_policies;",
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum JoinRequestPolicies {
+    Table,
+    CooldownSecs,
+    MaxDenials,
+    DenialWindowSecs,
+}
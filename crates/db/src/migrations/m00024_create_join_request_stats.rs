@@ -0,0 +1,84 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m00024_create_join_request_stats"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(JoinRequestStats::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(JoinRequestStats::ChannelId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(JoinRequestStats::RequesterId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(JoinRequestStats::LastRequestedAt)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(JoinRequestStats::Denials)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(JoinRequestStats::FirstDeniedAt)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(JoinRequestStats::ChannelId)
+                            .col(JoinRequestStats::RequesterId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(JoinRequestStats::Table, JoinRequestStats::ChannelId)
+                            .to(TemporaryChannels::Table, TemporaryChannels::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(JoinRequestStats::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TemporaryChannels {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+pub enum JoinRequestStats {
+    Table,
+    ChannelId,
+    RequesterId,
+    LastRequestedAt,
+    Denials,
+    FirstDeniedAt,
+}
@@ -0,0 +1,71 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m00025_create_voice_deafens"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // channel_id deliberately has no foreign key: the row must outlive the channel so
+        // the deafen can still be lifted after the channel is gone.
+        manager
+            .create_table(
+                Table::create()
+                    .table(VoiceDeafens::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(VoiceDeafens::GuildId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(VoiceDeafens::UserId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(VoiceDeafens::ChannelId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(VoiceDeafens::GuildId)
+                            .col(VoiceDeafens::UserId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(VoiceDeafens::Table, VoiceDeafens::GuildId)
+                            .to(Guilds::Table, Guilds::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(VoiceDeafens::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Guilds {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+pub enum VoiceDeafens {
+    Table,
+    GuildId,
+    UserId,
+    ChannelId,
+}
@@ -0,0 +1,102 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m00026_add_voice_options_to_channel_settings"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Both per-trigger defaults and per-user presets gain the same two columns.
+        // SQLite only accepts one ADD COLUMN per ALTER TABLE statement.
+        for table in [
+            PrimaryChannels::Table.into_iden(),
+            ChannelPresets::Table.into_iden(),
+        ] {
+            let columns = [
+                ColumnDef::new(Settings::RtcRegion)
+                    .string()
+                    .null()
+                    .to_owned(),
+                ColumnDef::new(Settings::VideoQualityMode)
+                    .big_integer()
+                    .null()
+                    .to_owned(),
+            ];
+            for mut column in columns {
+                manager
+                    .alter_table(
+                        Table::alter()
+                            .table(table.clone())
+                            .add_column(&mut column)
+                            .to_owned(),
+                    )
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite cannot drop columns; recreate both tables without them. primary_channels
+        // is a parent table for temporary_channels, so foreign_keys must be off for the swap.
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "PRAGMA foreign_keys = OFF;
+                CREATE TABLE primary_channels_new (
+                    id            BIGINT NOT NULL PRIMARY KEY,
+                    guild_id      BIGINT NOT NULL,
+                    name_template VARCHAR NULL,
+                    user_limit    BIGINT NULL,
+                    bitrate       BIGINT NULL,
+                    category_id   BIGINT NULL,
+                    private       BOOLEAN NOT NULL DEFAULT FALSE,
+                    FOREIGN KEY (guild_id) REFERENCES guilds(id) ON DELETE CASCADE
+                );
+                INSERT INTO primary_channels_new
+                    SELECT id, guild_id, name_template, user_limit, bitrate, category_id, private
+                    FROM primary_channels;
+                DROP TABLE primary_channels;
+                ALTER TABLE primary_channels_new RENAME TO primary_channels;
+                CREATE TABLE channel_presets_new (
+                    user_id    BIGINT NOT NULL,
+                    guild_id   BIGINT NOT NULL,
+                    name       VARCHAR NULL,
+                    user_limit BIGINT NULL,
+                    private    BOOLEAN NOT NULL DEFAULT FALSE,
+                    bitrate    BIGINT NULL,
+                    PRIMARY KEY (user_id, guild_id),
+                    FOREIGN KEY (guild_id) REFERENCES guilds(id) ON DELETE CASCADE
+                );
+                INSERT INTO channel_presets_new
+                    SELECT user_id, guild_id, name, user_limit, private, bitrate
+                    FROM channel_presets;
+                DROP TABLE channel_presets;
+                ALTER TABLE channel_presets_new RENAME TO channel_presets;
+                PRAGMA foreign_keys = ON;",
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum PrimaryChannels {
+    Table,
+}
+
+#[derive(DeriveIden)]
+enum ChannelPresets {
+    Table,
+}
+
+#[derive(DeriveIden)]
+enum Settings {
+    RtcRegion,
+    VideoQualityMode,
+}
@@ -0,0 +1,66 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m00027_add_idle_to_voice_sessions"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite only accepts one ADD COLUMN per ALTER TABLE statement.
+        let columns = [
+            ColumnDef::new(VoiceSessions::IdleSince)
+                .big_integer()
+                .null()
+                .to_owned(),
+            ColumnDef::new(VoiceSessions::IdleSecs)
+                .big_integer()
+                .not_null()
+                .default(0)
+                .to_owned(),
+        ];
+        for mut column in columns {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(VoiceSessions::Table)
+                        .add_column(&mut column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite cannot drop columns; recreate the table without the idle columns.
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "CREATE TABLE voice_sessions_new (
+                    user_id   BIGINT NOT NULL,
+                    guild_id  BIGINT NOT NULL,
+                    joined_at BIGINT NOT NULL,
+                    PRIMARY KEY (user_id, guild_id),
+                    FOREIGN KEY (guild_id) REFERENCES guilds(id) ON DELETE CASCADE
+                );
+                INSERT INTO voice_sessions_new
+                    SELECT user_id, guild_id, joined_at FROM voice_sessions;
+                DROP TABLE voice_sessions;
+                ALTER TABLE voice_sessions_new RENAME TO voice_sessions;",
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum VoiceSessions {
+    Table,
+    IdleSince,
+    IdleSecs,
+}
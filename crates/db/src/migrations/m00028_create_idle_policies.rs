@@ -0,0 +1,74 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m00028_create_idle_policies"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(IdlePolicies::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(IdlePolicies::GuildId)
+                            .big_integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(IdlePolicies::ThresholdSecs)
+                            .big_integer()
+                            .not_null()
+                            .default(1800),
+                    )
+                    .col(
+                        ColumnDef::new(IdlePolicies::WarningSecs)
+                            .big_integer()
+                            .not_null()
+                            .default(120),
+                    )
+                    .col(
+                        ColumnDef::new(IdlePolicies::Disconnect)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(IdlePolicies::Table, IdlePolicies::GuildId)
+                            .to(Guilds::Table, Guilds::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(IdlePolicies::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Guilds {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+pub enum IdlePolicies {
+    Table,
+    GuildId,
+    ThresholdSecs,
+    WarningSecs,
+    Disconnect,
+}
@@ -0,0 +1,50 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m00029_add_empty_grace_to_guilds"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Guilds::Table)
+                    .add_column(ColumnDef::new(Guilds::EmptyGraceSecs).big_integer().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite cannot drop columns; recreate the table without empty_grace_secs.
+        // guilds is a parent table for several FKs, so foreign_keys must be off for the swap.
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "PRAGMA foreign_keys = OFF;
+                CREATE TABLE guilds_new (
+                    id                    BIGINT NOT NULL PRIMARY KEY,
+                    channel_name_template VARCHAR
+                );
+                INSERT INTO guilds_new (id, channel_name_template)
+                    SELECT id, channel_name_template FROM guilds;
+                DROP TABLE guilds;
+                ALTER TABLE guilds_new RENAME TO guilds;
+                PRAGMA foreign_keys = ON;",
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Guilds {
+    Table,
+    EmptyGraceSecs,
+}
@@ -0,0 +1,64 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m00030_add_delete_at_to_temporary_channels"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(TemporaryChannels::Table)
+                    .add_column(
+                        ColumnDef::new(TemporaryChannels::DeleteAt)
+                            .big_integer()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite cannot drop columns; recreate the table without delete_at.
+        // join_requests and join_request_stats reference it, so foreign_keys must be off.
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "PRAGMA foreign_keys = OFF;
+                CREATE TABLE temporary_channels_new (
+                    id                 BIGINT NOT NULL PRIMARY KEY,
+                    guild_id           BIGINT NOT NULL,
+                    primary_channel_id BIGINT NOT NULL,
+                    join_channel_id    BIGINT,
+                    owner_id           BIGINT,
+                    ordinal            BIGINT NOT NULL DEFAULT 1,
+                    name_locked        BOOLEAN NOT NULL DEFAULT FALSE,
+                    hidden             BOOLEAN NOT NULL DEFAULT FALSE,
+                    FOREIGN KEY (guild_id) REFERENCES guilds(id) ON DELETE CASCADE,
+                    FOREIGN KEY (primary_channel_id) REFERENCES primary_channels(id) ON DELETE CASCADE
+                );
+                INSERT INTO temporary_channels_new
+                    SELECT id, guild_id, primary_channel_id, join_channel_id, owner_id, ordinal,
+                           name_locked, hidden
+                    FROM temporary_channels;
+                DROP TABLE temporary_channels;
+                ALTER TABLE temporary_channels_new RENAME TO temporary_channels;
+                PRAGMA foreign_keys = ON;",
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum TemporaryChannels {
+    Table,
+    DeleteAt,
+}
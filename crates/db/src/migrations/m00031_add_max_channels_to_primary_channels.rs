@@ -0,0 +1,65 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m00031_add_max_channels_to_primary_channels"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(PrimaryChannels::Table)
+                    .add_column(
+                        ColumnDef::new(PrimaryChannels::MaxChannels)
+                            .big_integer()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite cannot drop columns; recreate the table without max_channels.
+        // primary_channels is a parent table for temporary_channels, so foreign_keys
+        // must be off for the swap.
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "PRAGMA foreign_keys = OFF;
+                CREATE TABLE primary_channels_new (
+                    id                 BIGINT NOT NULL PRIMARY KEY,
+                    guild_id           BIGINT NOT NULL,
+                    name_template      VARCHAR NULL,
+                    user_limit         BIGINT NULL,
+                    bitrate            BIGINT NULL,
+                    category_id        BIGINT NULL,
+                    private            BOOLEAN NOT NULL DEFAULT FALSE,
+                    rtc_region         VARCHAR NULL,
+                    video_quality_mode BIGINT NULL,
+                    FOREIGN KEY (guild_id) REFERENCES guilds(id) ON DELETE CASCADE
+                );
+                INSERT INTO primary_channels_new
+                    SELECT id, guild_id, name_template, user_limit, bitrate, category_id, private,
+                           rtc_region, video_quality_mode
+                    FROM primary_channels;
+                DROP TABLE primary_channels;
+                ALTER TABLE primary_channels_new RENAME TO primary_channels;
+                PRAGMA foreign_keys = ON;",
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum PrimaryChannels {
+    Table,
+    MaxChannels,
+}
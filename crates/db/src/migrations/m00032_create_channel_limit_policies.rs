@@ -0,0 +1,72 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m00032_create_channel_limit_policies"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ChannelLimitPolicies::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ChannelLimitPolicies::GuildId)
+                            .big_integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ChannelLimitPolicies::MaxPerGuild)
+                            .big_integer()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(ChannelLimitPolicies::MaxPerUser)
+                            .big_integer()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(ChannelLimitPolicies::MoveWhenFull)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(ChannelLimitPolicies::Table, ChannelLimitPolicies::GuildId)
+                            .to(Guilds::Table, Guilds::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ChannelLimitPolicies::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Guilds {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+pub enum ChannelLimitPolicies {
+    Table,
+    GuildId,
+    MaxPerGuild,
+    MaxPerUser,
+    MoveWhenFull,
+}
@@ -0,0 +1,74 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m00033_create_join_rate_policies"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(JoinRatePolicies::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(JoinRatePolicies::GuildId)
+                            .big_integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(JoinRatePolicies::Burst)
+                            .big_integer()
+                            .not_null()
+                            .default(3),
+                    )
+                    .col(
+                        ColumnDef::new(JoinRatePolicies::RefillSecs)
+                            .big_integer()
+                            .not_null()
+                            .default(60),
+                    )
+                    .col(
+                        ColumnDef::new(JoinRatePolicies::BanSecs)
+                            .big_integer()
+                            .not_null()
+                            .default(300),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(JoinRatePolicies::Table, JoinRatePolicies::GuildId)
+                            .to(Guilds::Table, Guilds::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(JoinRatePolicies::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Guilds {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+pub enum JoinRatePolicies {
+    Table,
+    GuildId,
+    Burst,
+    RefillSecs,
+    BanSecs,
+}
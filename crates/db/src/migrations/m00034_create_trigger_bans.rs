@@ -0,0 +1,61 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m00034_create_trigger_bans"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TriggerBans::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(TriggerBans::GuildId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(TriggerBans::UserId).big_integer().not_null())
+                    .col(ColumnDef::new(TriggerBans::Until).big_integer().not_null())
+                    .primary_key(
+                        Index::create()
+                            .col(TriggerBans::GuildId)
+                            .col(TriggerBans::UserId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(TriggerBans::Table, TriggerBans::GuildId)
+                            .to(Guilds::Table, Guilds::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TriggerBans::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Guilds {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+pub enum TriggerBans {
+    Table,
+    GuildId,
+    UserId,
+    Until,
+}
@@ -0,0 +1,66 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m00036_add_retention_to_guilds"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite only accepts one ADD COLUMN per ALTER TABLE statement.
+        let columns = [
+            ColumnDef::new(Guilds::RemovedAt)
+                .big_integer()
+                .null()
+                .to_owned(),
+            ColumnDef::new(Guilds::RetentionDays)
+                .big_integer()
+                .null()
+                .to_owned(),
+        ];
+        for mut column in columns {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Guilds::Table)
+                        .add_column(&mut column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite cannot drop columns; recreate the table without the retention columns.
+        // guilds is a parent table for several FKs, so foreign_keys must be off for the swap.
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "PRAGMA foreign_keys = OFF;
+                CREATE TABLE guilds_new (
+                    id                    BIGINT NOT NULL PRIMARY KEY,
+                    channel_name_template VARCHAR,
+                    empty_grace_secs      BIGINT
+                );
+                INSERT INTO guilds_new (id, channel_name_template, empty_grace_secs)
+                    SELECT id, channel_name_template, empty_grace_secs FROM guilds;
+                DROP TABLE guilds;
+                ALTER TABLE guilds_new RENAME TO guilds;
+                PRAGMA foreign_keys = ON;",
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Guilds {
+    Table,
+    RemovedAt,
+    RetentionDays,
+}
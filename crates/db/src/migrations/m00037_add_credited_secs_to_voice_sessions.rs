@@ -0,0 +1,58 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m00037_add_credited_secs_to_voice_sessions"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(VoiceSessions::Table)
+                    .add_column(
+                        ColumnDef::new(VoiceSessions::CreditedSecs)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite cannot drop columns; recreate the table without credited_secs.
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "CREATE TABLE voice_sessions_new (
+                    user_id    BIGINT NOT NULL,
+                    guild_id   BIGINT NOT NULL,
+                    joined_at  BIGINT NOT NULL,
+                    idle_since BIGINT NULL,
+                    idle_secs  BIGINT NOT NULL DEFAULT 0,
+                    PRIMARY KEY (user_id, guild_id),
+                    FOREIGN KEY (guild_id) REFERENCES guilds(id) ON DELETE CASCADE
+                );
+                INSERT INTO voice_sessions_new
+                    SELECT user_id, guild_id, joined_at, idle_since, idle_secs
+                    FROM voice_sessions;
+                DROP TABLE voice_sessions;
+                ALTER TABLE voice_sessions_new RENAME TO voice_sessions;",
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum VoiceSessions {
+    Table,
+    CreditedSecs,
+}
@@ -0,0 +1,76 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m00038_add_channel_to_voice_sessions"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite only accepts one ADD COLUMN per ALTER TABLE statement.
+        let columns = [
+            ColumnDef::new(VoiceSessions::ChannelId)
+                .big_integer()
+                .null()
+                .to_owned(),
+            ColumnDef::new(VoiceSessions::StartedAt)
+                .big_integer()
+                .not_null()
+                .default(0)
+                .to_owned(),
+        ];
+        for mut column in columns {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(VoiceSessions::Table)
+                        .add_column(&mut column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        // Open sessions haven't been checkpointed across a restart, so joined_at is
+        // still the best start time there is.
+        manager
+            .get_connection()
+            .execute_unprepared("UPDATE voice_sessions SET started_at = joined_at;")
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite cannot drop columns; recreate the table without channel_id and started_at.
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "CREATE TABLE voice_sessions_new (
+                    user_id       BIGINT NOT NULL,
+                    guild_id      BIGINT NOT NULL,
+                    joined_at     BIGINT NOT NULL,
+                    idle_since    BIGINT NULL,
+                    idle_secs     BIGINT NOT NULL DEFAULT 0,
+                    credited_secs BIGINT NOT NULL DEFAULT 0,
+                    PRIMARY KEY (user_id, guild_id),
+                    FOREIGN KEY (guild_id) REFERENCES guilds(id) ON DELETE CASCADE
+                );
+                INSERT INTO voice_sessions_new
+                    SELECT user_id, guild_id, joined_at, idle_since, idle_secs, credited_secs
+                    FROM voice_sessions;
+                DROP TABLE voice_sessions;
+                ALTER TABLE voice_sessions_new RENAME TO voice_sessions;",
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum VoiceSessions {
+    Table,
+    ChannelId,
+    StartedAt,
+}
@@ -0,0 +1,103 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m00040_create_voice_session_history"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(VoiceSessionHistory::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(VoiceSessionHistory::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(VoiceSessionHistory::UserId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(VoiceSessionHistory::GuildId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    // Channels are gone long before their history, so neither is a foreign key.
+                    .col(ColumnDef::new(VoiceSessionHistory::ChannelId).big_integer())
+                    .col(ColumnDef::new(VoiceSessionHistory::PrimaryChannelId).big_integer())
+                    .col(
+                        ColumnDef::new(VoiceSessionHistory::StartedAt)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(VoiceSessionHistory::EndedAt)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(VoiceSessionHistory::DurationSecs)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(VoiceSessionHistory::Game).string())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(VoiceSessionHistory::Table, VoiceSessionHistory::GuildId)
+                            .to(Guilds::Table, Guilds::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        // `/history` lists a member's sessions newest first.
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_voice_session_history_member")
+                    .table(VoiceSessionHistory::Table)
+                    .col(VoiceSessionHistory::GuildId)
+                    .col(VoiceSessionHistory::UserId)
+                    .col(VoiceSessionHistory::EndedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(VoiceSessionHistory::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Guilds {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+pub enum VoiceSessionHistory {
+    Table,
+    Id,
+    UserId,
+    GuildId,
+    ChannelId,
+    PrimaryChannelId,
+    StartedAt,
+    EndedAt,
+    DurationSecs,
+    Game,
+}
@@ -0,0 +1,55 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m00041_add_history_days_to_guilds"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Guilds::Table)
+                    .add_column(ColumnDef::new(Guilds::HistoryDays).big_integer().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite cannot drop columns; recreate the table without history_days.
+        // guilds is a parent table for several FKs, so foreign_keys must be off for the swap.
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "PRAGMA foreign_keys = OFF;
+                CREATE TABLE guilds_new (
+                    id                    BIGINT NOT NULL PRIMARY KEY,
+                    channel_name_template VARCHAR,
+                    empty_grace_secs      BIGINT,
+                    removed_at            BIGINT,
+                    retention_days        BIGINT
+                );
+                INSERT INTO guilds_new
+                    (id, channel_name_template, empty_grace_secs, removed_at, retention_days)
+                    SELECT id, channel_name_template, empty_grace_secs, removed_at, retention_days
+                    FROM guilds;
+                DROP TABLE guilds;
+                ALTER TABLE guilds_new RENAME TO guilds;
+                PRAGMA foreign_keys = ON;",
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Guilds {
+    Table,
+    HistoryDays,
+}
@@ -0,0 +1,80 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m00042_create_user_daily_stats"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserDailyStats::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(UserDailyStats::GuildId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(UserDailyStats::UserId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(UserDailyStats::Day).big_integer().not_null())
+                    .col(
+                        ColumnDef::new(UserDailyStats::Xp)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(UserDailyStats::VoiceSeconds)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(UserDailyStats::GuildId)
+                            .col(UserDailyStats::UserId)
+                            .col(UserDailyStats::Day),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(UserDailyStats::Table, UserDailyStats::GuildId)
+                            .to(Guilds::Table, Guilds::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(UserDailyStats::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Guilds {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+pub enum UserDailyStats {
+    Table,
+    GuildId,
+    UserId,
+    Day,
+    Xp,
+    VoiceSeconds,
+}
@@ -0,0 +1,102 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m00043_create_xp_policies"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(XpPolicies::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(XpPolicies::GuildId)
+                            .big_integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(XpPolicies::XpPerSecond)
+                            .double()
+                            .not_null()
+                            .default(1.0),
+                    )
+                    .col(
+                        ColumnDef::new(XpPolicies::MinSessionSecs)
+                            .big_integer()
+                            .not_null()
+                            .default(60),
+                    )
+                    .col(
+                        ColumnDef::new(XpPolicies::DailyBonusXp)
+                            .big_integer()
+                            .not_null()
+                            .default(3600),
+                    )
+                    .col(
+                        ColumnDef::new(XpPolicies::DailyEarlySecs)
+                            .big_integer()
+                            .not_null()
+                            .default(22 * 3600),
+                    )
+                    .col(
+                        ColumnDef::new(XpPolicies::DailyLateSecs)
+                            .big_integer()
+                            .not_null()
+                            .default(26 * 3600),
+                    )
+                    .col(
+                        ColumnDef::new(XpPolicies::LevelBase)
+                            .big_integer()
+                            .not_null()
+                            .default(3600),
+                    )
+                    .col(
+                        ColumnDef::new(XpPolicies::LevelGrowth)
+                            .double()
+                            .not_null()
+                            .default(1.047),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(XpPolicies::Table, XpPolicies::GuildId)
+                            .to(Guilds::Table, Guilds::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(XpPolicies::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Guilds {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+pub enum XpPolicies {
+    Table,
+    GuildId,
+    XpPerSecond,
+    MinSessionSecs,
+    DailyBonusXp,
+    DailyEarlySecs,
+    DailyLateSecs,
+    LevelBase,
+    LevelGrowth,
+}
@@ -9,3 +9,36 @@ pub mod m00008_add_fk_to_voice_sessions;
 pub mod m00009_add_channel_name_template_to_guilds;
 pub mod m00010_create_user_achievements;
 pub mod m00011_add_longest_session_to_user_profiles;
+pub mod m00012_add_owner_to_temporary_channels;
+pub mod m00013_create_channel_presets;
+pub mod m00014_add_settings_to_primary_channels;
+pub mod m00015_add_ordinal_to_temporary_channels;
+pub mod m00016_create_naming_policies;
+pub mod m00017_create_activity_aliases;
+pub mod m00018_add_name_locked_to_temporary_channels;
+pub mod m00019_create_access_rules;
+pub mod m00020_add_hidden_to_temporary_channels;
+pub mod m00021_create_join_requests;
+pub mod m00022_create_join_request_policies;
+pub mod m00023_add_spam_limits_to_join_request_policies;
+pub mod m00024_create_join_request_stats;
+pub mod m00025_create_voice_deafens;
+pub mod m00026_add_voice_options_to_channel_settings;
+pub mod m00027_add_idle_to_voice_sessions;
+pub mod m00028_create_idle_policies;
+pub mod m00029_add_empty_grace_to_guilds;
+pub mod m00030_add_delete_at_to_temporary_channels;
+pub mod m00031_add_max_channels_to_primary_channels;
+pub mod m00032_create_channel_limit_policies;
+pub mod m00033_create_join_rate_policies;
+pub mod m00034_create_trigger_bans;
+pub mod m00035_add_fill_existing_to_primary_channels;
+pub mod m00036_add_retention_to_guilds;
+pub mod m00037_add_credited_secs_to_voice_sessions;
+pub mod m00038_add_channel_to_voice_sessions;
+pub mod m00039_add_game_to_temporary_channels;
+pub mod m00040_create_voice_session_history;
+pub mod m00041_add_history_days_to_guilds;
+pub mod m00042_create_user_daily_stats;
+pub mod m00043_create_xp_policies;
+pub mod m00044_add_creator_to_temporary_channels;
@@ -6,6 +6,23 @@ use crate::migrations::{
     m00006_create_voice_sessions, m00007_add_streak_to_user_profiles,
     m00008_add_fk_to_voice_sessions, m00009_add_channel_name_template_to_guilds,
     m00010_create_user_achievements, m00011_add_longest_session_to_user_profiles,
+    m00012_add_owner_to_temporary_channels, m00013_create_channel_presets,
+    m00014_add_settings_to_primary_channels, m00015_add_ordinal_to_temporary_channels,
+    m00016_create_naming_policies, m00017_create_activity_aliases,
+    m00018_add_name_locked_to_temporary_channels, m00019_create_access_rules,
+    m00020_add_hidden_to_temporary_channels, m00021_create_join_requests,
+    m00022_create_join_request_policies, m00023_add_spam_limits_to_join_request_policies,
+    m00024_create_join_request_stats, m00025_create_voice_deafens,
+    m00026_add_voice_options_to_channel_settings, m00027_add_idle_to_voice_sessions,
+    m00028_create_idle_policies, m00029_add_empty_grace_to_guilds,
+    m00030_add_delete_at_to_temporary_channels, m00031_add_max_channels_to_primary_channels,
+    m00032_create_channel_limit_policies, m00033_create_join_rate_policies,
+    m00034_create_trigger_bans, m00035_add_fill_existing_to_primary_channels,
+    m00036_add_retention_to_guilds, m00037_add_credited_secs_to_voice_sessions,
+    m00038_add_channel_to_voice_sessions, m00039_add_game_to_temporary_channels,
+    m00040_create_voice_session_history, m00041_add_history_days_to_guilds,
+    m00042_create_user_daily_stats, m00043_create_xp_policies,
+    m00044_add_creator_to_temporary_channels,
 };
 
 pub struct Migrator;
@@ -25,6 +42,39 @@ impl MigratorTrait for Migrator {
             Box::new(m00009_add_channel_name_template_to_guilds::Migration),
             Box::new(m00010_create_user_achievements::Migration),
             Box::new(m00011_add_longest_session_to_user_profiles::Migration),
+            Box::new(m00012_add_owner_to_temporary_channels::Migration),
+            Box::new(m00013_create_channel_presets::Migration),
+            Box::new(m00014_add_settings_to_primary_channels::Migration),
+            Box::new(m00015_add_ordinal_to_temporary_channels::Migration),
+            Box::new(m00016_create_naming_policies::Migration),
+            Box::new(m00017_create_activity_aliases::Migration),
+            Box::new(m00018_add_name_locked_to_temporary_channels::Migration),
+            Box::new(m00019_create_access_rules::Migration),
+            Box::new(m00020_add_hidden_to_temporary_channels::Migration),
+            Box::new(m00021_create_join_requests::Migration),
+            Box::new(m00022_create_join_request_policies::Migration),
+            Box::new(m00023_add_spam_limits_to_join_request_policies::Migration),
+            Box::new(m00024_create_join_request_stats::Migration),
+            Box::new(m00025_create_voice_deafens::Migration),
+            Box::new(m00026_add_voice_options_to_channel_settings::Migration),
+            Box::new(m00027_add_idle_to_voice_sessions::Migration),
+            Box::new(m00028_create_idle_policies::Migration),
+            Box::new(m00029_add_empty_grace_to_guilds::Migration),
+            Box::new(m00030_add_delete_at_to_temporary_channels::Migration),
+            Box::new(m00031_add_max_channels_to_primary_channels::Migration),
+            Box::new(m00032_create_channel_limit_policies::Migration),
+            Box::new(m00033_create_join_rate_policies::Migration),
+            Box::new(m00034_create_trigger_bans::Migration),
+            Box::new(m00035_add_fill_existing_to_primary_channels::Migration),
+            Box::new(m00036_add_retention_to_guilds::Migration),
+            Box::new(m00037_add_credited_secs_to_voice_sessions::Migration),
+            Box::new(m00038_add_channel_to_voice_sessions::Migration),
+            Box::new(m00039_add_game_to_temporary_channels::Migration),
+            Box::new(m00040_create_voice_session_history::Migration),
+            Box::new(m00041_add_history_days_to_guilds::Migration),
+            Box::new(m00042_create_user_daily_stats::Migration),
+            Box::new(m00043_create_xp_policies::Migration),
+            Box::new(m00044_add_creator_to_temporary_channels::Migration),
         ]
     }
 }
@@ -0,0 +1,108 @@
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+
+use crate::entities::access_rule::{self, Entity as AccessRule};
+use crate::error::DbError;
+
+/// The owner's permit and reject entries for this guild.
+pub async fn list(
+    user_id: i64,
+    guild_id: i64,
+    db: &DatabaseConnection,
+) -> Result<Vec<access_rule::Model>, DbError> {
+    Ok(AccessRule::find()
+        .filter(access_rule::Column::UserId.eq(user_id))
+        .filter(access_rule::Column::GuildId.eq(guild_id))
+        .all(db)
+        .await?)
+}
+
+/// Adds the entry, replacing any earlier entry for the same target (so permitting a
+/// rejected user moves them to the permit list).
+pub async fn set(rule: access_rule::Model, db: &DatabaseConnection) -> Result<(), DbError> {
+    let model = access_rule::ActiveModel {
+        user_id: Set(rule.user_id),
+        guild_id: Set(rule.guild_id),
+        target_id: Set(rule.target_id),
+        is_role: Set(rule.is_role),
+        allowed: Set(rule.allowed),
+    };
+    AccessRule::insert(model)
+        .on_conflict(
+            OnConflict::columns([
+                access_rule::Column::UserId,
+                access_rule::Column::GuildId,
+                access_rule::Column::TargetId,
+            ])
+            .update_columns([access_rule::Column::IsRole, access_rule::Column::Allowed])
+            .to_owned(),
+        )
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// Removes the owner's entry for `target_id`, returning it, or `None` if there was none.
+pub async fn remove(
+    user_id: i64,
+    guild_id: i64,
+    target_id: i64,
+    db: &DatabaseConnection,
+) -> Result<Option<access_rule::Model>, DbError> {
+    let Some(rule) = AccessRule::find_by_id((user_id, guild_id, target_id))
+        .one(db)
+        .await?
+    else {
+        return Ok(None);
+    };
+    AccessRule::delete_by_id((user_id, guild_id, target_id))
+        .exec(db)
+        .await?;
+    Ok(Some(rule))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::test_db;
+
+    fn rule(user_id: i64, target_id: i64, allowed: bool) -> access_rule::Model {
+        access_rule::Model {
+            user_id,
+            guild_id: 1,
+            target_id,
+            is_role: false,
+            allowed,
+        }
+    }
+
+    #[tokio::test]
+    async fn set_replaces_the_entry_for_a_target() {
+        let db = test_db().await;
+        crate::repositories::guild::upsert(1, &db).await.unwrap();
+
+        set(rule(42, 7, false), &db).await.unwrap();
+        set(rule(42, 7, true), &db).await.unwrap();
+        set(rule(42, 8, false), &db).await.unwrap();
+
+        let mut rules = list(42, 1, &db).await.unwrap();
+        rules.sort_by_key(|r| r.target_id);
+        assert_eq!(rules, vec![rule(42, 7, true), rule(42, 8, false)]);
+    }
+
+    #[tokio::test]
+    async fn remove_returns_the_removed_entry() {
+        let db = test_db().await;
+        crate::repositories::guild::upsert(1, &db).await.unwrap();
+
+        set(rule(42, 7, false), &db).await.unwrap();
+        set(rule(42, 8, true), &db).await.unwrap();
+
+        assert_eq!(
+            remove(42, 1, 7, &db).await.unwrap(),
+            Some(rule(42, 7, false))
+        );
+        assert_eq!(remove(42, 1, 7, &db).await.unwrap(), None);
+        assert_eq!(list(42, 1, &db).await.unwrap(), vec![rule(42, 8, true)]);
+    }
+}
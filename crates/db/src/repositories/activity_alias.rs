@@ -0,0 +1,106 @@
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, Set};
+
+use crate::entities::activity_alias::{self, Entity as ActivityAlias};
+use crate::error::DbError;
+
+pub async fn list_by_guild(
+    guild_id: i64,
+    db: &DatabaseConnection,
+) -> Result<Vec<activity_alias::Model>, DbError> {
+    Ok(ActivityAlias::find()
+        .filter(activity_alias::Column::GuildId.eq(guild_id))
+        .order_by_asc(activity_alias::Column::Activity)
+        .all(db)
+        .await?)
+}
+
+/// Maps `activity` to `alias` for this guild, or ignores it when `alias` is `None`.
+/// Activity names are stored lowercased so matching ignores case.
+pub async fn set(
+    guild_id: i64,
+    activity: &str,
+    alias: Option<String>,
+    db: &DatabaseConnection,
+) -> Result<(), DbError> {
+    let model = activity_alias::ActiveModel {
+        guild_id: Set(guild_id),
+        activity: Set(activity.to_lowercase()),
+        alias: Set(alias),
+    };
+    ActivityAlias::insert(model)
+        .on_conflict(
+            OnConflict::columns([
+                activity_alias::Column::GuildId,
+                activity_alias::Column::Activity,
+            ])
+            .update_column(activity_alias::Column::Alias)
+            .to_owned(),
+        )
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// Removes the alias or ignore entry for `activity`. Returns `true` if one existed.
+pub async fn remove(
+    guild_id: i64,
+    activity: &str,
+    db: &DatabaseConnection,
+) -> Result<bool, DbError> {
+    let result = ActivityAlias::delete_many()
+        .filter(activity_alias::Column::GuildId.eq(guild_id))
+        .filter(activity_alias::Column::Activity.eq(activity.to_lowercase()))
+        .exec(db)
+        .await?;
+    Ok(result.rows_affected > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::test_db;
+
+    #[tokio::test]
+    async fn set_stores_the_activity_lowercased_and_overwrites() {
+        let db = test_db().await;
+        crate::repositories::guild::upsert(1, &db).await.unwrap();
+
+        set(1, "Counter-Strike 2", Some("CS".to_owned()), &db)
+            .await
+            .unwrap();
+        set(1, "counter-strike 2", Some("CS2".to_owned()), &db)
+            .await
+            .unwrap();
+
+        let aliases = list_by_guild(1, &db).await.unwrap();
+        assert_eq!(aliases.len(), 1);
+        assert_eq!(aliases[0].activity, "counter-strike 2");
+        assert_eq!(aliases[0].alias.as_deref(), Some("CS2"));
+    }
+
+    #[tokio::test]
+    async fn list_by_guild_only_returns_that_guild() {
+        let db = test_db().await;
+        crate::repositories::guild::upsert(1, &db).await.unwrap();
+        crate::repositories::guild::upsert(2, &db).await.unwrap();
+
+        set(1, "Spotify", None, &db).await.unwrap();
+        set(2, "Chess", Some("♟".to_owned()), &db).await.unwrap();
+
+        let aliases = list_by_guild(1, &db).await.unwrap();
+        assert_eq!(aliases.len(), 1);
+        assert_eq!(aliases[0].alias, None);
+    }
+
+    #[tokio::test]
+    async fn remove_ignores_case_and_reports_whether_it_existed() {
+        let db = test_db().await;
+        crate::repositories::guild::upsert(1, &db).await.unwrap();
+
+        set(1, "Spotify", None, &db).await.unwrap();
+        assert!(remove(1, "SPOTIFY", &db).await.unwrap());
+        assert!(!remove(1, "Spotify", &db).await.unwrap());
+        assert!(list_by_guild(1, &db).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,63 @@
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{DatabaseConnection, EntityTrait, Set};
+
+use crate::entities::channel_limit_policy::{self, Entity as ChannelLimitPolicy};
+use crate::error::DbError;
+
+/// Returns the guild's channel caps, or `None` if it still uses the defaults.
+pub async fn get(
+    guild_id: i64,
+    db: &DatabaseConnection,
+) -> Result<Option<channel_limit_policy::Model>, DbError> {
+    Ok(ChannelLimitPolicy::find_by_id(guild_id).one(db).await?)
+}
+
+/// Inserts or replaces the guild's channel caps.
+pub async fn save(
+    policy: channel_limit_policy::Model,
+    db: &DatabaseConnection,
+) -> Result<(), DbError> {
+    let model = channel_limit_policy::ActiveModel {
+        guild_id: Set(policy.guild_id),
+        max_per_guild: Set(policy.max_per_guild),
+        max_per_user: Set(policy.max_per_user),
+        move_when_full: Set(policy.move_when_full),
+    };
+    ChannelLimitPolicy::insert(model)
+        .on_conflict(
+            OnConflict::column(channel_limit_policy::Column::GuildId)
+                .update_columns([
+                    channel_limit_policy::Column::MaxPerGuild,
+                    channel_limit_policy::Column::MaxPerUser,
+                    channel_limit_policy::Column::MoveWhenFull,
+                ])
+                .to_owned(),
+        )
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::test_db;
+
+    #[tokio::test]
+    async fn save_get_roundtrip_and_overwrite() {
+        let db = test_db().await;
+        crate::repositories::guild::upsert(1, &db).await.unwrap();
+        assert_eq!(get(1, &db).await.unwrap(), None);
+
+        for (max_per_user, move_when_full) in [(Some(2), true), (None, false)] {
+            let policy = channel_limit_policy::Model {
+                guild_id: 1,
+                max_per_guild: Some(50),
+                max_per_user,
+                move_when_full,
+            };
+            save(policy.clone(), &db).await.unwrap();
+            assert_eq!(get(1, &db).await.unwrap(), Some(policy));
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+
+use crate::entities::channel_preset::{self, Entity as ChannelPreset};
+use crate::error::DbError;
+
+pub async fn get(
+    user_id: i64,
+    guild_id: i64,
+    db: &DatabaseConnection,
+) -> Result<Option<channel_preset::Model>, DbError> {
+    Ok(ChannelPreset::find()
+        .filter(channel_preset::Column::UserId.eq(user_id))
+        .filter(channel_preset::Column::GuildId.eq(guild_id))
+        .one(db)
+        .await?)
+}
+
+/// Inserts or replaces the user's preset for this guild.
+pub async fn save(preset: channel_preset::Model, db: &DatabaseConnection) -> Result<(), DbError> {
+    let model = channel_preset::ActiveModel {
+        user_id: Set(preset.user_id),
+        guild_id: Set(preset.guild_id),
+        name: Set(preset.name),
+        user_limit: Set(preset.user_limit),
+        private: Set(preset.private),
+        bitrate: Set(preset.bitrate),
+        rtc_region: Set(preset.rtc_region),
+        video_quality_mode: Set(preset.video_quality_mode),
+    };
+    ChannelPreset::insert(model)
+        .on_conflict(
+            OnConflict::columns([
+                channel_preset::Column::UserId,
+                channel_preset::Column::GuildId,
+            ])
+            .update_columns([
+                channel_preset::Column::Name,
+                channel_preset::Column::UserLimit,
+                channel_preset::Column::Private,
+                channel_preset::Column::Bitrate,
+                channel_preset::Column::RtcRegion,
+                channel_preset::Column::VideoQualityMode,
+            ])
+            .to_owned(),
+        )
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// Deletes the user's preset for this guild. Returns `true` if one existed.
+pub async fn clear(user_id: i64, guild_id: i64, db: &DatabaseConnection) -> Result<bool, DbError> {
+    let result = ChannelPreset::delete_many()
+        .filter(channel_preset::Column::UserId.eq(user_id))
+        .filter(channel_preset::Column::GuildId.eq(guild_id))
+        .exec(db)
+        .await?;
+    Ok(result.rows_affected > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::test_db;
+
+    fn preset(user_id: i64, guild_id: i64, name: &str) -> channel_preset::Model {
+        channel_preset::Model {
+            user_id,
+            guild_id,
+            name: Some(name.to_owned()),
+            user_limit: Some(5),
+            private: true,
+            bitrate: Some(96_000),
+            rtc_region: Some("japan".to_owned()),
+            video_quality_mode: Some(1),
+        }
+    }
+
+    #[tokio::test]
+    async fn save_get_roundtrip() {
+        let db = test_db().await;
+        crate::repositories::guild::upsert(1, &db).await.unwrap();
+
+        assert!(get(42, 1, &db).await.unwrap().is_none());
+        save(preset(42, 1, "Squad"), &db).await.unwrap();
+        assert_eq!(get(42, 1, &db).await.unwrap(), Some(preset(42, 1, "Squad")));
+    }
+
+    #[tokio::test]
+    async fn save_overwrites_the_previous_preset() {
+        let db = test_db().await;
+        crate::repositories::guild::upsert(1, &db).await.unwrap();
+
+        save(preset(42, 1, "Squad"), &db).await.unwrap();
+        save(preset(42, 1, "Raid"), &db).await.unwrap();
+
+        let found = get(42, 1, &db).await.unwrap().unwrap();
+        assert_eq!(found.name.as_deref(), Some("Raid"));
+    }
+
+    #[tokio::test]
+    async fn clear_removes_only_that_users_guild_preset() {
+        let db = test_db().await;
+        crate::repositories::guild::upsert(1, &db).await.unwrap();
+        crate::repositories::guild::upsert(2, &db).await.unwrap();
+
+        save(preset(42, 1, "Squad"), &db).await.unwrap();
+        save(preset(42, 2, "Squad"), &db).await.unwrap();
+
+        assert!(clear(42, 1, &db).await.unwrap());
+        assert!(!clear(42, 1, &db).await.unwrap());
+        assert!(get(42, 1, &db).await.unwrap().is_none());
+        assert!(get(42, 2, &db).await.unwrap().is_some());
+    }
+}
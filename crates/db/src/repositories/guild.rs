@@ -1,5 +1,7 @@
-use sea_orm::sea_query::OnConflict;
-use sea_orm::{DatabaseConnection, DbErr, EntityTrait, PaginatorTrait, Set};
+use sea_orm::sea_query::{Expr, OnConflict};
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, DbErr, EntityTrait, PaginatorTrait, QueryFilter, Set,
+};
 
 use crate::entities::guild::{self, Entity as Guild};
 use crate::error::DbError;
@@ -8,6 +10,10 @@ pub async fn upsert(id: i64, db: &DatabaseConnection) -> Result<(), DbError> {
     let model = guild::ActiveModel {
         id: Set(id),
         channel_name_template: Set(None),
+        empty_grace_secs: Set(None),
+        removed_at: Set(None),
+        retention_days: Set(None),
+        history_days: Set(None),
     };
     match Guild::insert(model)
         .on_conflict(
@@ -45,6 +51,10 @@ pub async fn set_channel_name_template(
     let model = guild::ActiveModel {
         id: Set(id),
         channel_name_template: Set(template),
+        empty_grace_secs: Set(None),
+        removed_at: Set(None),
+        retention_days: Set(None),
+        history_days: Set(None),
     };
     Guild::insert(model)
         .on_conflict(
@@ -57,6 +67,139 @@ pub async fn set_channel_name_template(
     Ok(())
 }
 
+pub async fn empty_grace_secs(id: i64, db: &DatabaseConnection) -> Result<Option<i64>, DbError> {
+    Ok(Guild::find_by_id(id)
+        .one(db)
+        .await?
+        .and_then(|m| m.empty_grace_secs))
+}
+
+pub async fn set_empty_grace_secs(
+    id: i64,
+    secs: Option<i64>,
+    db: &DatabaseConnection,
+) -> Result<(), DbError> {
+    let model = guild::ActiveModel {
+        id: Set(id),
+        channel_name_template: Set(None),
+        empty_grace_secs: Set(secs),
+        removed_at: Set(None),
+        retention_days: Set(None),
+        history_days: Set(None),
+    };
+    Guild::insert(model)
+        .on_conflict(
+            OnConflict::column(guild::Column::Id)
+                .update_column(guild::Column::EmptyGraceSecs)
+                .to_owned(),
+        )
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+pub async fn retention_days(id: i64, db: &DatabaseConnection) -> Result<Option<i64>, DbError> {
+    Ok(Guild::find_by_id(id)
+        .one(db)
+        .await?
+        .and_then(|m| m.retention_days))
+}
+
+pub async fn set_retention_days(
+    id: i64,
+    days: Option<i64>,
+    db: &DatabaseConnection,
+) -> Result<(), DbError> {
+    let model = guild::ActiveModel {
+        id: Set(id),
+        channel_name_template: Set(None),
+        empty_grace_secs: Set(None),
+        removed_at: Set(None),
+        retention_days: Set(days),
+        history_days: Set(None),
+    };
+    Guild::insert(model)
+        .on_conflict(
+            OnConflict::column(guild::Column::Id)
+                .update_column(guild::Column::RetentionDays)
+                .to_owned(),
+        )
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+pub async fn history_days(id: i64, db: &DatabaseConnection) -> Result<Option<i64>, DbError> {
+    Ok(Guild::find_by_id(id)
+        .one(db)
+        .await?
+        .and_then(|m| m.history_days))
+}
+
+pub async fn set_history_days(
+    id: i64,
+    days: Option<i64>,
+    db: &DatabaseConnection,
+) -> Result<(), DbError> {
+    let model = guild::ActiveModel {
+        id: Set(id),
+        channel_name_template: Set(None),
+        empty_grace_secs: Set(None),
+        removed_at: Set(None),
+        retention_days: Set(None),
+        history_days: Set(days),
+    };
+    Guild::insert(model)
+        .on_conflict(
+            OnConflict::column(guild::Column::Id)
+                .update_column(guild::Column::HistoryDays)
+                .to_owned(),
+        )
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// Records when the bot was removed from the guild, or clears it with `None` once the
+/// bot is back. Does nothing for a guild without a row.
+pub async fn set_removed_at(
+    id: i64,
+    removed_at: Option<i64>,
+    db: &DatabaseConnection,
+) -> Result<(), DbError> {
+    Guild::update_many()
+        .col_expr(guild::Column::RemovedAt, Expr::value(removed_at))
+        .filter(guild::Column::Id.eq(id))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// Guilds the bot has been removed from, whose data is awaiting deletion.
+pub async fn list_removed(db: &DatabaseConnection) -> Result<Vec<guild::Model>, DbError> {
+    Ok(Guild::find()
+        .filter(guild::Column::RemovedAt.is_not_null())
+        .all(db)
+        .await?)
+}
+
+/// Guilds the bot is still in, as far as the db knows.
+pub async fn list_present_ids(db: &DatabaseConnection) -> Result<Vec<i64>, DbError> {
+    Ok(Guild::find()
+        .filter(guild::Column::RemovedAt.is_null())
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|m| m.id)
+        .collect())
+}
+
+/// Deletes the guild and, through cascading foreign keys, everything stored for it.
+pub async fn delete(id: i64, db: &DatabaseConnection) -> Result<(), DbError> {
+    Guild::delete_by_id(id).exec(db).await?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -129,4 +272,52 @@ mod tests {
         set_channel_name_template(1, None, &db).await.unwrap();
         assert_eq!(channel_name_template(1, &db).await.unwrap(), None);
     }
+
+    #[tokio::test]
+    async fn set_empty_grace_secs_keeps_the_name_template() {
+        let db = test_db().await;
+        assert_eq!(empty_grace_secs(1, &db).await.unwrap(), None);
+        set_channel_name_template(1, Some("[{game}]".to_string()), &db)
+            .await
+            .unwrap();
+        set_empty_grace_secs(1, Some(90), &db).await.unwrap();
+        assert_eq!(empty_grace_secs(1, &db).await.unwrap(), Some(90));
+        assert_eq!(
+            channel_name_template(1, &db).await.unwrap(),
+            Some("[{game}]".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn removed_guilds_are_listed_until_cleared() {
+        let db = test_db().await;
+        upsert(1, &db).await.unwrap();
+        upsert(2, &db).await.unwrap();
+        set_retention_days(1, Some(7), &db).await.unwrap();
+        set_removed_at(1, Some(1_000), &db).await.unwrap();
+
+        let removed = list_removed(&db).await.unwrap();
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].removed_at, Some(1_000));
+        assert_eq!(removed[0].retention_days, Some(7));
+        assert_eq!(list_present_ids(&db).await.unwrap(), vec![2]);
+
+        set_removed_at(1, None, &db).await.unwrap();
+        assert!(list_removed(&db).await.unwrap().is_empty());
+        assert_eq!(retention_days(1, &db).await.unwrap(), Some(7));
+    }
+
+    #[tokio::test]
+    async fn delete_cascades_to_the_guilds_data() {
+        let db = test_db().await;
+        upsert(1, &db).await.unwrap();
+        crate::repositories::primary_channel::insert(10, 1, &db)
+            .await
+            .unwrap();
+        delete(1, &db).await.unwrap();
+        assert_eq!(count(&db).await.unwrap(), 0);
+        assert!(!crate::repositories::primary_channel::exists(10, &db)
+            .await
+            .unwrap());
+    }
 }
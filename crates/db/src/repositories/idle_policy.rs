@@ -0,0 +1,60 @@
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{DatabaseConnection, EntityTrait, Set};
+
+use crate::entities::idle_policy::{self, Entity as IdlePolicy};
+use crate::error::DbError;
+
+/// Returns the guild's idle policy, or `None` if it still uses the defaults.
+pub async fn get(
+    guild_id: i64,
+    db: &DatabaseConnection,
+) -> Result<Option<idle_policy::Model>, DbError> {
+    Ok(IdlePolicy::find_by_id(guild_id).one(db).await?)
+}
+
+/// Inserts or replaces the guild's idle policy.
+pub async fn save(policy: idle_policy::Model, db: &DatabaseConnection) -> Result<(), DbError> {
+    let model = idle_policy::ActiveModel {
+        guild_id: Set(policy.guild_id),
+        threshold_secs: Set(policy.threshold_secs),
+        warning_secs: Set(policy.warning_secs),
+        disconnect: Set(policy.disconnect),
+    };
+    IdlePolicy::insert(model)
+        .on_conflict(
+            OnConflict::column(idle_policy::Column::GuildId)
+                .update_columns([
+                    idle_policy::Column::ThresholdSecs,
+                    idle_policy::Column::WarningSecs,
+                    idle_policy::Column::Disconnect,
+                ])
+                .to_owned(),
+        )
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::test_db;
+
+    #[tokio::test]
+    async fn save_get_roundtrip_and_overwrite() {
+        let db = test_db().await;
+        crate::repositories::guild::upsert(1, &db).await.unwrap();
+        assert_eq!(get(1, &db).await.unwrap(), None);
+
+        for (threshold_secs, disconnect) in [(600, false), (0, true)] {
+            let policy = idle_policy::Model {
+                guild_id: 1,
+                threshold_secs,
+                warning_secs: 60,
+                disconnect,
+            };
+            save(policy.clone(), &db).await.unwrap();
+            assert_eq!(get(1, &db).await.unwrap(), Some(policy));
+        }
+    }
+}
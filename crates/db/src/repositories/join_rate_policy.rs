@@ -0,0 +1,60 @@
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{DatabaseConnection, EntityTrait, Set};
+
+use crate::entities::join_rate_policy::{self, Entity as JoinRatePolicy};
+use crate::error::DbError;
+
+/// Returns the guild's join-rate policy, or `None` if it still uses the defaults.
+pub async fn get(
+    guild_id: i64,
+    db: &DatabaseConnection,
+) -> Result<Option<join_rate_policy::Model>, DbError> {
+    Ok(JoinRatePolicy::find_by_id(guild_id).one(db).await?)
+}
+
+/// Inserts or replaces the guild's join-rate policy.
+pub async fn save(policy: join_rate_policy::Model, db: &DatabaseConnection) -> Result<(), DbError> {
+    let model = join_rate_policy::ActiveModel {
+        guild_id: Set(policy.guild_id),
+        burst: Set(policy.burst),
+        refill_secs: Set(policy.refill_secs),
+        ban_secs: Set(policy.ban_secs),
+    };
+    JoinRatePolicy::insert(model)
+        .on_conflict(
+            OnConflict::column(join_rate_policy::Column::GuildId)
+                .update_columns([
+                    join_rate_policy::Column::Burst,
+                    join_rate_policy::Column::RefillSecs,
+                    join_rate_policy::Column::BanSecs,
+                ])
+                .to_owned(),
+        )
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::test_db;
+
+    #[tokio::test]
+    async fn save_get_roundtrip_and_overwrite() {
+        let db = test_db().await;
+        crate::repositories::guild::upsert(1, &db).await.unwrap();
+        assert_eq!(get(1, &db).await.unwrap(), None);
+
+        for (burst, ban_secs) in [(5, 600), (0, 0)] {
+            let policy = join_rate_policy::Model {
+                guild_id: 1,
+                burst,
+                refill_secs: 30,
+                ban_secs,
+            };
+            save(policy.clone(), &db).await.unwrap();
+            assert_eq!(get(1, &db).await.unwrap(), Some(policy));
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+
+use crate::entities::join_request::{self, Entity as JoinRequest};
+use crate::error::DbError;
+
+/// Records a pending request, replacing any earlier one from the same requester.
+pub async fn upsert(request: join_request::Model, db: &DatabaseConnection) -> Result<(), DbError> {
+    let model = join_request::ActiveModel {
+        channel_id: Set(request.channel_id),
+        requester_id: Set(request.requester_id),
+        message_id: Set(request.message_id),
+        expires_at: Set(request.expires_at),
+    };
+    JoinRequest::insert(model)
+        .on_conflict(
+            OnConflict::columns([
+                join_request::Column::ChannelId,
+                join_request::Column::RequesterId,
+            ])
+            .update_columns([
+                join_request::Column::MessageId,
+                join_request::Column::ExpiresAt,
+            ])
+            .to_owned(),
+        )
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+pub async fn find(
+    channel_id: i64,
+    requester_id: i64,
+    db: &DatabaseConnection,
+) -> Result<Option<join_request::Model>, DbError> {
+    Ok(JoinRequest::find_by_id((channel_id, requester_id))
+        .one(db)
+        .await?)
+}
+
+/// Removes the request. Returns `true` if it was still pending, so concurrent
+/// responders can tell who resolved it.
+pub async fn delete(
+    channel_id: i64,
+    requester_id: i64,
+    db: &DatabaseConnection,
+) -> Result<bool, DbError> {
+    let result = JoinRequest::delete_by_id((channel_id, requester_id))
+        .exec(db)
+        .await?;
+    Ok(result.rows_affected > 0)
+}
+
+/// Requests whose `expires_at` is at or before `now`.
+pub async fn list_expired(
+    now: i64,
+    db: &DatabaseConnection,
+) -> Result<Vec<join_request::Model>, DbError> {
+    Ok(JoinRequest::find()
+        .filter(join_request::Column::ExpiresAt.lte(now))
+        .all(db)
+        .await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::test_db;
+
+    async fn seed(db: &DatabaseConnection) {
+        crate::repositories::guild::upsert(1, db).await.unwrap();
+        crate::repositories::primary_channel::insert(10, 1, db)
+            .await
+            .unwrap();
+        crate::repositories::temporary_channel::insert(100, 1, 10, 42, 1, db)
+            .await
+            .unwrap();
+    }
+
+    fn request(requester_id: i64, message_id: i64, expires_at: i64) -> join_request::Model {
+        join_request::Model {
+            channel_id: 100,
+            requester_id,
+            message_id,
+            expires_at,
+        }
+    }
+
+    #[tokio::test]
+    async fn upsert_replaces_the_previous_prompt() {
+        let db = test_db().await;
+        seed(&db).await;
+
+        upsert(request(7, 1000, 50), &db).await.unwrap();
+        upsert(request(7, 1001, 60), &db).await.unwrap();
+        assert_eq!(find(100, 7, &db).await.unwrap(), Some(request(7, 1001, 60)));
+    }
+
+    #[tokio::test]
+    async fn delete_reports_whether_the_request_was_pending() {
+        let db = test_db().await;
+        seed(&db).await;
+
+        upsert(request(7, 1000, 50), &db).await.unwrap();
+        assert!(delete(100, 7, &db).await.unwrap());
+        assert!(!delete(100, 7, &db).await.unwrap());
+        assert!(find(100, 7, &db).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn list_expired_includes_the_boundary() {
+        let db = test_db().await;
+        seed(&db).await;
+
+        upsert(request(7, 1000, 50), &db).await.unwrap();
+        upsert(request(8, 1001, 51), &db).await.unwrap();
+        let expired = list_expired(50, &db).await.unwrap();
+        assert_eq!(expired, vec![request(7, 1000, 50)]);
+    }
+
+    #[tokio::test]
+    async fn requests_go_away_with_their_channel() {
+        let db = test_db().await;
+        seed(&db).await;
+
+        upsert(request(7, 1000, 50), &db).await.unwrap();
+        crate::repositories::temporary_channel::delete(100, &db)
+            .await
+            .unwrap();
+        assert!(find(100, 7, &db).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,70 @@
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{DatabaseConnection, EntityTrait, Set};
+
+use crate::entities::join_request_policy::{self, Entity as JoinRequestPolicy};
+use crate::error::DbError;
+
+/// Returns the guild's join-request policy, or `None` if it still uses the defaults.
+pub async fn get(
+    guild_id: i64,
+    db: &DatabaseConnection,
+) -> Result<Option<join_request_policy::Model>, DbError> {
+    Ok(JoinRequestPolicy::find_by_id(guild_id).one(db).await?)
+}
+
+/// Inserts or replaces the guild's join-request policy.
+pub async fn save(
+    policy: join_request_policy::Model,
+    db: &DatabaseConnection,
+) -> Result<(), DbError> {
+    let model = join_request_policy::ActiveModel {
+        guild_id: Set(policy.guild_id),
+        timeout_secs: Set(policy.timeout_secs),
+        cooldown_secs: Set(policy.cooldown_secs),
+        max_denials: Set(policy.max_denials),
+        denial_window_secs: Set(policy.denial_window_secs),
+    };
+    JoinRequestPolicy::insert(model)
+        .on_conflict(
+            OnConflict::column(join_request_policy::Column::GuildId)
+                .update_columns([
+                    join_request_policy::Column::TimeoutSecs,
+                    join_request_policy::Column::CooldownSecs,
+                    join_request_policy::Column::MaxDenials,
+                    join_request_policy::Column::DenialWindowSecs,
+                ])
+                .to_owned(),
+        )
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::test_db;
+
+    #[tokio::test]
+    async fn save_get_roundtrip_and_overwrite() {
+        let db = test_db().await;
+        crate::repositories::guild::upsert(1, &db).await.unwrap();
+        assert!(get(1, &db).await.unwrap().is_none());
+
+        for timeout_secs in [300, 600] {
+            save(
+                join_request_policy::Model {
+                    guild_id: 1,
+                    timeout_secs,
+                    cooldown_secs: 30,
+                    max_denials: 2,
+                    denial_window_secs: 900,
+                },
+                &db,
+            )
+            .await
+            .unwrap();
+        }
+        assert_eq!(get(1, &db).await.unwrap().unwrap().timeout_secs, 600);
+    }
+}
@@ -0,0 +1,139 @@
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{DatabaseConnection, EntityTrait, Set};
+
+use crate::entities::join_request_stat::{self, Entity as JoinRequestStat};
+use crate::error::DbError;
+
+pub async fn get(
+    channel_id: i64,
+    requester_id: i64,
+    db: &DatabaseConnection,
+) -> Result<Option<join_request_stat::Model>, DbError> {
+    Ok(JoinRequestStat::find_by_id((channel_id, requester_id))
+        .one(db)
+        .await?)
+}
+
+/// Notes that a prompt was posted for the requester at `now`.
+pub async fn record_request(
+    channel_id: i64,
+    requester_id: i64,
+    now: i64,
+    db: &DatabaseConnection,
+) -> Result<(), DbError> {
+    let model = join_request_stat::ActiveModel {
+        channel_id: Set(channel_id),
+        requester_id: Set(requester_id),
+        last_requested_at: Set(now),
+        denials: Set(0),
+        first_denied_at: Set(0),
+    };
+    JoinRequestStat::insert(model)
+        .on_conflict(
+            OnConflict::columns([
+                join_request_stat::Column::ChannelId,
+                join_request_stat::Column::RequesterId,
+            ])
+            .update_column(join_request_stat::Column::LastRequestedAt)
+            .to_owned(),
+        )
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// Counts a denial at `now`. Denials older than `window_secs` are forgotten: the count
+/// restarts at 1 once the current window has run out. Returns the updated count.
+pub async fn record_denial(
+    channel_id: i64,
+    requester_id: i64,
+    now: i64,
+    window_secs: i64,
+    db: &DatabaseConnection,
+) -> Result<i64, DbError> {
+    let existing = get(channel_id, requester_id, db).await?;
+    let (denials, first_denied_at) = match &existing {
+        Some(stat) if stat.denials > 0 && now - stat.first_denied_at < window_secs => {
+            (stat.denials + 1, stat.first_denied_at)
+        }
+        _ => (1, now),
+    };
+    let model = join_request_stat::ActiveModel {
+        channel_id: Set(channel_id),
+        requester_id: Set(requester_id),
+        last_requested_at: Set(existing.map_or(0, |s| s.last_requested_at)),
+        denials: Set(denials),
+        first_denied_at: Set(first_denied_at),
+    };
+    JoinRequestStat::insert(model)
+        .on_conflict(
+            OnConflict::columns([
+                join_request_stat::Column::ChannelId,
+                join_request_stat::Column::RequesterId,
+            ])
+            .update_columns([
+                join_request_stat::Column::Denials,
+                join_request_stat::Column::FirstDeniedAt,
+            ])
+            .to_owned(),
+        )
+        .exec(db)
+        .await?;
+    Ok(denials)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::test_db;
+
+    async fn seed(db: &DatabaseConnection) {
+        crate::repositories::guild::upsert(1, db).await.unwrap();
+        crate::repositories::primary_channel::insert(10, 1, db)
+            .await
+            .unwrap();
+        crate::repositories::temporary_channel::insert(100, 1, 10, 42, 1, db)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn record_request_keeps_denial_counters() {
+        let db = test_db().await;
+        seed(&db).await;
+
+        record_request(100, 7, 1_000, &db).await.unwrap();
+        record_denial(100, 7, 1_010, 3600, &db).await.unwrap();
+        record_request(100, 7, 1_100, &db).await.unwrap();
+
+        let stat = get(100, 7, &db).await.unwrap().unwrap();
+        assert_eq!(stat.last_requested_at, 1_100);
+        assert_eq!(stat.denials, 1);
+        assert_eq!(stat.first_denied_at, 1_010);
+    }
+
+    #[tokio::test]
+    async fn denials_accumulate_within_the_window_and_reset_after_it() {
+        let db = test_db().await;
+        seed(&db).await;
+
+        assert_eq!(record_denial(100, 7, 1_000, 600, &db).await.unwrap(), 1);
+        assert_eq!(record_denial(100, 7, 1_300, 600, &db).await.unwrap(), 2);
+        assert_eq!(record_denial(100, 7, 1_599, 600, &db).await.unwrap(), 3);
+        // The window opened at 1_000, so at 1_600 it has run out.
+        assert_eq!(record_denial(100, 7, 1_600, 600, &db).await.unwrap(), 1);
+        assert_eq!(
+            get(100, 7, &db).await.unwrap().unwrap().first_denied_at,
+            1_600
+        );
+    }
+
+    #[tokio::test]
+    async fn counters_are_per_requester() {
+        let db = test_db().await;
+        seed(&db).await;
+
+        record_denial(100, 7, 1_000, 600, &db).await.unwrap();
+        assert!(get(100, 8, &db).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,310 @@
+//! Ranked, paginated views over members' stats, for `/ranking`.
+//!
+//! All-time rankings read the lifetime totals in `user_profiles`; windowed ones sum
+//! `user_daily_stats` or take the longest session from `voice_session_history`. Every
+//! query sorts, offsets and limits in SQL, so a page costs the same however many
+//! members a guild has.
+
+use sea_orm::sea_query::{Alias, Expr, SimpleExpr};
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, EntityTrait, FromQueryResult, Order, PaginatorTrait,
+    QueryFilter, QueryOrder, QuerySelect, Select,
+};
+
+use crate::entities::user_daily_stat::{self, Entity as UserDailyStat};
+use crate::entities::user_profile::{self, Entity as UserProfile};
+use crate::entities::voice_session_history::{self, Entity as VoiceSessionHistory};
+use crate::error::DbError;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Metric {
+    Xp,
+    VoiceSeconds,
+    LongestSession,
+    /// Current daily streaks. `since` is the oldest daily bonus that keeps a streak
+    /// alive rather than a window, and is required.
+    Streak,
+}
+
+/// A member's place-worthy value for the metric.
+#[derive(Clone, Debug, PartialEq, Eq, FromQueryResult)]
+pub struct Entry {
+    pub user_id: i64,
+    pub value: i64,
+}
+
+/// Members with a non-zero value for `metric` since `since` (unix seconds; `None` for
+/// all time).
+pub async fn count(
+    guild_id: i64,
+    metric: Metric,
+    since: Option<i64>,
+    db: &DatabaseConnection,
+) -> Result<u64, DbError> {
+    match source(guild_id, metric, since) {
+        Source::Profiles(select) => Ok(select.count(db).await?),
+        Source::DailyStats(select) => Ok(select.count(db).await?),
+        Source::History(select) => Ok(select.count(db).await?),
+    }
+}
+
+/// One page of the ranking, highest value first; ties go to the lowest user id so
+/// pages don't shift between clicks.
+pub async fn page(
+    guild_id: i64,
+    metric: Metric,
+    since: Option<i64>,
+    page: u64,
+    page_size: u64,
+    db: &DatabaseConnection,
+) -> Result<Vec<Entry>, DbError> {
+    let value = Expr::col(Alias::new("value"));
+    let (offset, limit) = (page * page_size, page_size);
+    Ok(match source(guild_id, metric, since) {
+        Source::Profiles(select) => {
+            select
+                .order_by(value, Order::Desc)
+                .order_by_asc(user_profile::Column::UserId)
+                .offset(offset)
+                .limit(limit)
+                .into_model::<Entry>()
+                .all(db)
+                .await?
+        }
+        Source::DailyStats(select) => {
+            select
+                .order_by(value, Order::Desc)
+                .order_by_asc(user_daily_stat::Column::UserId)
+                .offset(offset)
+                .limit(limit)
+                .into_model::<Entry>()
+                .all(db)
+                .await?
+        }
+        Source::History(select) => {
+            select
+                .order_by(value, Order::Desc)
+                .order_by_asc(voice_session_history::Column::UserId)
+                .offset(offset)
+                .limit(limit)
+                .into_model::<Entry>()
+                .all(db)
+                .await?
+        }
+    })
+}
+
+enum Source {
+    Profiles(Select<UserProfile>),
+    DailyStats(Select<UserDailyStat>),
+    History(Select<VoiceSessionHistory>),
+}
+
+/// The `user_id, value` query behind a ranking, unsorted.
+fn source(guild_id: i64, metric: Metric, since: Option<i64>) -> Source {
+    match (metric, since) {
+        (Metric::Streak, since) => Source::Profiles(profiles(
+            guild_id,
+            user_profile::Column::Streak,
+            Some(user_profile::Column::LastDailyAt.gte(since.unwrap_or(i64::MAX))),
+        )),
+        (Metric::Xp, None) => Source::Profiles(profiles(guild_id, user_profile::Column::Xp, None)),
+        (Metric::VoiceSeconds, None) => Source::Profiles(profiles(
+            guild_id,
+            user_profile::Column::TotalVoiceSeconds,
+            None,
+        )),
+        (Metric::LongestSession, None) => Source::Profiles(profiles(
+            guild_id,
+            user_profile::Column::LongestSessionSeconds,
+            None,
+        )),
+        (Metric::Xp, Some(since)) => {
+            Source::DailyStats(daily_stats(guild_id, user_daily_stat::Column::Xp, since))
+        }
+        (Metric::VoiceSeconds, Some(since)) => Source::DailyStats(daily_stats(
+            guild_id,
+            user_daily_stat::Column::VoiceSeconds,
+            since,
+        )),
+        (Metric::LongestSession, Some(since)) => Source::History(
+            VoiceSessionHistory::find()
+                .select_only()
+                .column(voice_session_history::Column::UserId)
+                .column_as(voice_session_history::Column::DurationSecs.max(), "value")
+                .filter(voice_session_history::Column::GuildId.eq(guild_id))
+                .filter(voice_session_history::Column::EndedAt.gte(since))
+                .filter(voice_session_history::Column::DurationSecs.gt(0))
+                .group_by(voice_session_history::Column::UserId),
+        ),
+    }
+}
+
+fn profiles(
+    guild_id: i64,
+    column: user_profile::Column,
+    filter: Option<SimpleExpr>,
+) -> Select<UserProfile> {
+    let select = UserProfile::find()
+        .select_only()
+        .column(user_profile::Column::UserId)
+        .column_as(column, "value")
+        .filter(user_profile::Column::GuildId.eq(guild_id))
+        .filter(column.gt(0));
+    match filter {
+        Some(filter) => select.filter(filter),
+        None => select,
+    }
+}
+
+/// Sums `column` over the days from the one `since` falls on.
+fn daily_stats(
+    guild_id: i64,
+    column: user_daily_stat::Column,
+    since: i64,
+) -> Select<UserDailyStat> {
+    UserDailyStat::find()
+        .select_only()
+        .column(user_daily_stat::Column::UserId)
+        .column_as(column.sum(), "value")
+        .filter(user_daily_stat::Column::GuildId.eq(guild_id))
+        .filter(user_daily_stat::Column::Day.gte(super::user_daily_stat::day_of(since)))
+        .filter(column.gt(0))
+        .group_by(user_daily_stat::Column::UserId)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::test_db;
+
+    const DAY: i64 = 24 * 60 * 60;
+
+    async fn seed(db: &DatabaseConnection) {
+        crate::repositories::guild::upsert(1, db).await.unwrap();
+        // Member 1 earned the most, long ago; members 2 and 3 less, recently.
+        crate::repositories::user_profile::add_xp(1, 1, 1_000, 1_000, 0, db)
+            .await
+            .unwrap();
+        crate::repositories::user_profile::add_xp(2, 1, 300, 300, 10 * DAY, db)
+            .await
+            .unwrap();
+        crate::repositories::user_profile::add_xp(3, 1, 300, 300, 10 * DAY, db)
+            .await
+            .unwrap();
+        crate::repositories::user_profile::add_xp(3, 1, 100, 0, 11 * DAY, db)
+            .await
+            .unwrap();
+    }
+
+    fn users(entries: &[Entry]) -> Vec<i64> {
+        entries.iter().map(|e| e.user_id).collect()
+    }
+
+    #[tokio::test]
+    async fn all_time_ranks_lifetime_totals() {
+        let db = test_db().await;
+        seed(&db).await;
+        let top = page(1, Metric::Xp, None, 0, 10, &db).await.unwrap();
+        assert_eq!(users(&top), vec![1, 3, 2]);
+        assert_eq!(top[0].value, 1_000);
+        assert_eq!(count(1, Metric::Xp, None, &db).await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn windows_only_count_recent_days() {
+        let db = test_db().await;
+        seed(&db).await;
+        let top = page(1, Metric::Xp, Some(10 * DAY + 5), 0, 10, &db)
+            .await
+            .unwrap();
+        assert_eq!(
+            top,
+            vec![
+                Entry {
+                    user_id: 3,
+                    value: 400
+                },
+                Entry {
+                    user_id: 2,
+                    value: 300
+                },
+            ]
+        );
+        assert_eq!(
+            count(1, Metric::VoiceSeconds, Some(11 * DAY), &db)
+                .await
+                .unwrap(),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn pages_are_cut_in_sql_with_stable_ties() {
+        let db = test_db().await;
+        seed(&db).await;
+        let second = page(1, Metric::VoiceSeconds, None, 1, 1, &db)
+            .await
+            .unwrap();
+        // Members 2 and 3 tie on voice time; the lower id comes first.
+        assert_eq!(users(&second), vec![2]);
+        let third = page(1, Metric::VoiceSeconds, None, 2, 1, &db)
+            .await
+            .unwrap();
+        assert_eq!(users(&third), vec![3]);
+    }
+
+    #[tokio::test]
+    async fn windowed_longest_session_reads_history() {
+        let db = test_db().await;
+        seed(&db).await;
+        for (user_id, started_at, ended_at) in [(1, 0, 5_000), (2, 10 * DAY, 10 * DAY + 600)] {
+            let session = crate::entities::voice_session::Model {
+                user_id,
+                guild_id: 1,
+                joined_at: started_at,
+                idle_since: None,
+                idle_secs: 0,
+                credited_secs: 0,
+                channel_id: None,
+                started_at,
+            };
+            crate::repositories::voice_session_history::record(
+                &session,
+                None,
+                None,
+                ended_at,
+                ended_at - started_at,
+                &db,
+            )
+            .await
+            .unwrap();
+        }
+        let top = page(1, Metric::LongestSession, Some(DAY), 0, 10, &db)
+            .await
+            .unwrap();
+        assert_eq!(
+            top,
+            vec![Entry {
+                user_id: 2,
+                value: 600
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn streaks_only_count_while_alive() {
+        let db = test_db().await;
+        seed(&db).await;
+        crate::repositories::user_profile::set_daily_state(1, 1, 100, 4, &db)
+            .await
+            .unwrap();
+        crate::repositories::user_profile::set_daily_state(2, 1, 5_000, 2, &db)
+            .await
+            .unwrap();
+        let top = page(1, Metric::Streak, Some(1_000), 0, 10, &db)
+            .await
+            .unwrap();
+        assert_eq!(users(&top), vec![2]);
+    }
+}
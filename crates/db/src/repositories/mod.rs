@@ -1,6 +1,22 @@
+pub mod access_rule;
+pub mod activity_alias;
+pub mod channel_limit_policy;
+pub mod channel_preset;
 pub mod guild;
+pub mod idle_policy;
+pub mod join_rate_policy;
+pub mod join_request;
+pub mod join_request_policy;
+pub mod join_request_stat;
+pub mod leaderboard;
+pub mod naming_policy;
 pub mod primary_channel;
 pub mod temporary_channel;
+pub mod trigger_ban;
 pub mod user_achievement;
+pub mod user_daily_stat;
 pub mod user_profile;
+pub mod voice_deafen;
 pub mod voice_session;
+pub mod voice_session_history;
+pub mod xp_policy;
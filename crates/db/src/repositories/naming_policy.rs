@@ -0,0 +1,80 @@
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{DatabaseConnection, EntityTrait, Set};
+
+use crate::entities::naming_policy::{self, Entity as NamingPolicy};
+use crate::error::DbError;
+
+/// Returns the guild's naming policy, or `None` if it still uses the defaults.
+pub async fn get(
+    guild_id: i64,
+    db: &DatabaseConnection,
+) -> Result<Option<naming_policy::Model>, DbError> {
+    Ok(NamingPolicy::find_by_id(guild_id).one(db).await?)
+}
+
+/// Inserts or replaces the guild's naming policy.
+pub async fn save(policy: naming_policy::Model, db: &DatabaseConnection) -> Result<(), DbError> {
+    let model = naming_policy::ActiveModel {
+        guild_id: Set(policy.guild_id),
+        plurality: Set(policy.plurality),
+        threshold_percent: Set(policy.threshold_percent),
+        count_streaming: Set(policy.count_streaming),
+        count_listening: Set(policy.count_listening),
+        count_competing: Set(policy.count_competing),
+        ignore_bots: Set(policy.ignore_bots),
+    };
+    NamingPolicy::insert(model)
+        .on_conflict(
+            OnConflict::column(naming_policy::Column::GuildId)
+                .update_columns([
+                    naming_policy::Column::Plurality,
+                    naming_policy::Column::ThresholdPercent,
+                    naming_policy::Column::CountStreaming,
+                    naming_policy::Column::CountListening,
+                    naming_policy::Column::CountCompeting,
+                    naming_policy::Column::IgnoreBots,
+                ])
+                .to_owned(),
+        )
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::test_db;
+
+    fn policy(guild_id: i64, threshold_percent: i64) -> naming_policy::Model {
+        naming_policy::Model {
+            guild_id,
+            plurality: false,
+            threshold_percent,
+            count_streaming: true,
+            count_listening: false,
+            count_competing: true,
+            ignore_bots: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn get_returns_none_until_saved() {
+        let db = test_db().await;
+        crate::repositories::guild::upsert(1, &db).await.unwrap();
+
+        assert!(get(1, &db).await.unwrap().is_none());
+        save(policy(1, 60), &db).await.unwrap();
+        assert_eq!(get(1, &db).await.unwrap(), Some(policy(1, 60)));
+    }
+
+    #[tokio::test]
+    async fn save_overwrites_the_previous_policy() {
+        let db = test_db().await;
+        crate::repositories::guild::upsert(1, &db).await.unwrap();
+
+        save(policy(1, 60), &db).await.unwrap();
+        save(policy(1, 75), &db).await.unwrap();
+        assert_eq!(get(1, &db).await.unwrap().unwrap().threshold_percent, 75);
+    }
+}
@@ -1,4 +1,7 @@
-use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter, Set};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
+    Set,
+};
 
 use crate::entities::primary_channel::{self, Entity as PrimaryChannel};
 use crate::error::DbError;
@@ -7,6 +10,15 @@ pub async fn insert(id: i64, guild_id: i64, db: &DatabaseConnection) -> Result<(
     let model = primary_channel::ActiveModel {
         id: Set(id),
         guild_id: Set(guild_id),
+        name_template: Set(None),
+        user_limit: Set(None),
+        bitrate: Set(None),
+        category_id: Set(None),
+        private: Set(false),
+        rtc_region: Set(None),
+        video_quality_mode: Set(None),
+        max_channels: Set(None),
+        fill_existing: Set(false),
     };
     PrimaryChannel::insert(model).exec(db).await?;
     Ok(())
@@ -17,6 +29,35 @@ pub async fn delete(id: i64, db: &DatabaseConnection) -> Result<(), DbError> {
     Ok(())
 }
 
+pub async fn find(
+    id: i64,
+    db: &DatabaseConnection,
+) -> Result<Option<primary_channel::Model>, DbError> {
+    Ok(PrimaryChannel::find_by_id(id).one(db).await?)
+}
+
+/// Overwrites the trigger's per-trigger channel defaults with those in `settings`.
+pub async fn update_settings(
+    settings: primary_channel::Model,
+    db: &DatabaseConnection,
+) -> Result<(), DbError> {
+    let model = primary_channel::ActiveModel {
+        id: Set(settings.id),
+        name_template: Set(settings.name_template),
+        user_limit: Set(settings.user_limit),
+        bitrate: Set(settings.bitrate),
+        category_id: Set(settings.category_id),
+        private: Set(settings.private),
+        rtc_region: Set(settings.rtc_region),
+        video_quality_mode: Set(settings.video_quality_mode),
+        max_channels: Set(settings.max_channels),
+        fill_existing: Set(settings.fill_existing),
+        ..Default::default()
+    };
+    model.update(db).await?;
+    Ok(())
+}
+
 pub async fn exists(id: i64, db: &DatabaseConnection) -> Result<bool, DbError> {
     Ok(PrimaryChannel::find_by_id(id).one(db).await?.is_some())
 }
@@ -55,6 +96,44 @@ mod tests {
         assert!(!exists(100, &db).await.unwrap());
     }
 
+    #[tokio::test]
+    async fn new_triggers_have_no_settings() {
+        let db = test_db().await;
+        crate::repositories::guild::upsert(1, &db).await.unwrap();
+        insert(100, 1, &db).await.unwrap();
+
+        let found = find(100, &db).await.unwrap().unwrap();
+        assert_eq!(found.name_template, None);
+        assert_eq!(found.user_limit, None);
+        assert_eq!(found.bitrate, None);
+        assert_eq!(found.category_id, None);
+        assert!(!found.private);
+    }
+
+    #[tokio::test]
+    async fn update_settings_persists_every_field() {
+        let db = test_db().await;
+        crate::repositories::guild::upsert(1, &db).await.unwrap();
+        insert(100, 1, &db).await.unwrap();
+
+        let settings = primary_channel::Model {
+            id: 100,
+            guild_id: 1,
+            name_template: Some("Squad {game}".to_owned()),
+            user_limit: Some(4),
+            bitrate: Some(64_000),
+            category_id: Some(555),
+            private: true,
+            rtc_region: Some("rotterdam".to_owned()),
+            video_quality_mode: Some(2),
+            max_channels: Some(3),
+            fill_existing: true,
+        };
+        update_settings(settings.clone(), &db).await.unwrap();
+
+        assert_eq!(find(100, &db).await.unwrap(), Some(settings));
+    }
+
     #[tokio::test]
     async fn list_by_guild_filters_by_guild() {
         let db = test_db().await;
@@ -10,6 +10,8 @@ pub async fn insert(
     id: i64,
     guild_id: i64,
     primary_channel_id: i64,
+    owner_id: i64,
+    ordinal: i64,
     db: &DatabaseConnection,
 ) -> Result<(), DbError> {
     let model = temporary_channel::ActiveModel {
@@ -17,6 +19,13 @@ pub async fn insert(
         guild_id: Set(guild_id),
         primary_channel_id: Set(primary_channel_id),
         join_channel_id: Set(None),
+        owner_id: Set(Some(owner_id)),
+        ordinal: Set(ordinal),
+        name_locked: Set(false),
+        hidden: Set(false),
+        delete_at: Set(None),
+        game: Set(None),
+        creator_id: Set(Some(owner_id)),
     };
     TemporaryChannel::insert(model).exec(db).await?;
     Ok(())
@@ -53,11 +62,95 @@ pub async fn set_join_channel(
     Ok(())
 }
 
-pub async fn delete(id: i64, db: &DatabaseConnection) -> Result<(), DbError> {
-    TemporaryChannel::delete_by_id(id).exec(db).await?;
+/// Records `owner_id` as the channel's owner, or clears ownership with `None`.
+pub async fn set_owner(
+    id: i64,
+    owner_id: Option<i64>,
+    db: &DatabaseConnection,
+) -> Result<(), DbError> {
+    let model = temporary_channel::ActiveModel {
+        id: Set(id),
+        owner_id: Set(owner_id),
+        ..Default::default()
+    };
+    model.update(db).await?;
+    Ok(())
+}
+
+/// Stops (`true`) or resumes (`false`) automatic naming for the channel.
+pub async fn set_name_locked(
+    id: i64,
+    name_locked: bool,
+    db: &DatabaseConnection,
+) -> Result<(), DbError> {
+    let model = temporary_channel::ActiveModel {
+        id: Set(id),
+        name_locked: Set(name_locked),
+        ..Default::default()
+    };
+    model.update(db).await?;
+    Ok(())
+}
+
+/// Records whether the channel is hidden from @everyone.
+pub async fn set_hidden(id: i64, hidden: bool, db: &DatabaseConnection) -> Result<(), DbError> {
+    let model = temporary_channel::ActiveModel {
+        id: Set(id),
+        hidden: Set(hidden),
+        ..Default::default()
+    };
+    model.update(db).await?;
+    Ok(())
+}
+
+/// Schedules deletion of the now-empty channel at `delete_at`, or cancels it with `None`.
+pub async fn set_delete_at(
+    id: i64,
+    delete_at: Option<i64>,
+    db: &DatabaseConnection,
+) -> Result<(), DbError> {
+    let model = temporary_channel::ActiveModel {
+        id: Set(id),
+        delete_at: Set(delete_at),
+        ..Default::default()
+    };
+    model.update(db).await?;
     Ok(())
 }
 
+/// Records the activity the channel was just automatically named after.
+pub async fn set_game(
+    id: i64,
+    game: Option<String>,
+    db: &DatabaseConnection,
+) -> Result<(), DbError> {
+    let model = temporary_channel::ActiveModel {
+        id: Set(id),
+        game: Set(game),
+        ..Default::default()
+    };
+    model.update(db).await?;
+    Ok(())
+}
+
+/// Channels whose scheduled deletion is at or before `now`.
+pub async fn list_due(
+    now: i64,
+    db: &DatabaseConnection,
+) -> Result<Vec<temporary_channel::Model>, DbError> {
+    Ok(TemporaryChannel::find()
+        .filter(temporary_channel::Column::DeleteAt.lte(now))
+        .all(db)
+        .await?)
+}
+
+/// Returns `true` if the row existed, so callers racing on the same channel (e.g. the
+/// bot's own deletion and the resulting ChannelDelete event) count it only once.
+pub async fn delete(id: i64, db: &DatabaseConnection) -> Result<bool, DbError> {
+    let result = TemporaryChannel::delete_by_id(id).exec(db).await?;
+    Ok(result.rows_affected > 0)
+}
+
 pub async fn exists(id: i64, db: &DatabaseConnection) -> Result<bool, DbError> {
     Ok(TemporaryChannel::find_by_id(id).one(db).await?.is_some())
 }
@@ -90,6 +183,29 @@ pub async fn list_by_primary_channel(
         .await?)
 }
 
+/// Lowest ordinal not held by a live channel from `primary_channel_id`, so numbers
+/// freed by deleted channels are reused ("Squad 1" comes back before "Squad 4").
+pub async fn next_ordinal(
+    primary_channel_id: i64,
+    db: &DatabaseConnection,
+) -> Result<i64, DbError> {
+    let mut taken: Vec<i64> = list_by_primary_channel(primary_channel_id, db)
+        .await?
+        .into_iter()
+        .map(|c| c.ordinal)
+        .collect();
+    taken.sort_unstable();
+    let mut next = 1;
+    for ordinal in taken {
+        if ordinal == next {
+            next += 1;
+        } else if ordinal > next {
+            break;
+        }
+    }
+    Ok(next)
+}
+
 pub async fn count_by_guild(guild_id: i64, db: &DatabaseConnection) -> Result<u64, DbError> {
     Ok(TemporaryChannel::find()
         .filter(temporary_channel::Column::GuildId.eq(guild_id))
@@ -97,6 +213,30 @@ pub async fn count_by_guild(guild_id: i64, db: &DatabaseConnection) -> Result<u6
         .await?)
 }
 
+pub async fn count_by_primary_channel(
+    primary_channel_id: i64,
+    db: &DatabaseConnection,
+) -> Result<u64, DbError> {
+    Ok(TemporaryChannel::find()
+        .filter(temporary_channel::Column::PrimaryChannelId.eq(primary_channel_id))
+        .count(db)
+        .await?)
+}
+
+/// Live channels `creator_id` created in the guild, for the per-member cap. Handing a
+/// channel over doesn't take it off its creator's count.
+pub async fn count_by_creator(
+    guild_id: i64,
+    creator_id: i64,
+    db: &DatabaseConnection,
+) -> Result<u64, DbError> {
+    Ok(TemporaryChannel::find()
+        .filter(temporary_channel::Column::GuildId.eq(guild_id))
+        .filter(temporary_channel::Column::CreatorId.eq(creator_id))
+        .count(db)
+        .await?)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -118,23 +258,26 @@ mod tests {
         seed(&db, 1, 10).await;
 
         assert!(find(100, &db).await.unwrap().is_none());
-        insert(100, 1, 10, &db).await.unwrap();
+        insert(100, 1, 10, 42, 1, &db).await.unwrap();
 
         let found = find(100, &db).await.unwrap().unwrap();
         assert_eq!(found.guild_id, 1);
         assert_eq!(found.primary_channel_id, 10);
         assert_eq!(found.join_channel_id, None);
+        assert_eq!(found.owner_id, Some(42));
+        assert_eq!(found.ordinal, 1);
         assert!(exists(100, &db).await.unwrap());
 
-        delete(100, &db).await.unwrap();
+        assert!(delete(100, &db).await.unwrap());
         assert!(find(100, &db).await.unwrap().is_none());
+        assert!(!delete(100, &db).await.unwrap());
     }
 
     #[tokio::test]
     async fn set_and_clear_join_channel() {
         let db = test_db().await;
         seed(&db, 1, 10).await;
-        insert(100, 1, 10, &db).await.unwrap();
+        insert(100, 1, 10, 42, 1, &db).await.unwrap();
 
         set_join_channel(100, Some(999), &db).await.unwrap();
         assert_eq!(
@@ -146,11 +289,71 @@ mod tests {
         assert_eq!(find(100, &db).await.unwrap().unwrap().join_channel_id, None);
     }
 
+    #[tokio::test]
+    async fn set_owner_transfers_and_clears_ownership() {
+        let db = test_db().await;
+        seed(&db, 1, 10).await;
+        insert(100, 1, 10, 42, 1, &db).await.unwrap();
+
+        set_owner(100, Some(7), &db).await.unwrap();
+        assert_eq!(find(100, &db).await.unwrap().unwrap().owner_id, Some(7));
+
+        set_owner(100, None, &db).await.unwrap();
+        assert_eq!(find(100, &db).await.unwrap().unwrap().owner_id, None);
+    }
+
+    #[tokio::test]
+    async fn name_lock_defaults_off_and_toggles() {
+        let db = test_db().await;
+        seed(&db, 1, 10).await;
+        insert(100, 1, 10, 42, 1, &db).await.unwrap();
+        assert!(!find(100, &db).await.unwrap().unwrap().name_locked);
+
+        set_name_locked(100, true, &db).await.unwrap();
+        assert!(find(100, &db).await.unwrap().unwrap().name_locked);
+
+        set_name_locked(100, false, &db).await.unwrap();
+        assert!(!find(100, &db).await.unwrap().unwrap().name_locked);
+    }
+
+    #[tokio::test]
+    async fn set_hidden_toggles_the_flag() {
+        let db = test_db().await;
+        seed(&db, 1, 10).await;
+        insert(100, 1, 10, 42, 1, &db).await.unwrap();
+        assert!(!find(100, &db).await.unwrap().unwrap().hidden);
+
+        set_hidden(100, true, &db).await.unwrap();
+        assert!(find(100, &db).await.unwrap().unwrap().hidden);
+    }
+
+    #[tokio::test]
+    async fn list_due_returns_only_scheduled_channels_past_their_deadline() {
+        let db = test_db().await;
+        seed(&db, 1, 10).await;
+        insert(100, 1, 10, 42, 1, &db).await.unwrap();
+        insert(101, 1, 10, 42, 2, &db).await.unwrap();
+        insert(102, 1, 10, 42, 3, &db).await.unwrap();
+        set_delete_at(100, Some(1_000), &db).await.unwrap();
+        set_delete_at(101, Some(2_000), &db).await.unwrap();
+
+        let due: Vec<i64> = list_due(1_000, &db)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|c| c.id)
+            .collect();
+        assert_eq!(due, vec![100]);
+
+        set_delete_at(100, None, &db).await.unwrap();
+        assert!(list_due(1_500, &db).await.unwrap().is_empty());
+    }
+
     #[tokio::test]
     async fn find_by_join_channel_looks_up_the_companion_channel() {
         let db = test_db().await;
         seed(&db, 1, 10).await;
-        insert(100, 1, 10, &db).await.unwrap();
+        insert(100, 1, 10, 42, 1, &db).await.unwrap();
         set_join_channel(100, Some(999), &db).await.unwrap();
 
         let found = find_by_join_channel(999, &db).await.unwrap().unwrap();
@@ -166,9 +369,9 @@ mod tests {
         seed(&db, 1, 11).await;
         seed(&db, 2, 20).await;
 
-        insert(100, 1, 10, &db).await.unwrap();
-        insert(101, 1, 11, &db).await.unwrap();
-        insert(200, 2, 20, &db).await.unwrap();
+        insert(100, 1, 10, 42, 1, &db).await.unwrap();
+        insert(101, 1, 11, 42, 1, &db).await.unwrap();
+        insert(200, 2, 20, 42, 1, &db).await.unwrap();
 
         assert_eq!(count_all(&db).await.unwrap(), 3);
         assert_eq!(list_all(&db).await.unwrap().len(), 3);
@@ -179,5 +382,41 @@ mod tests {
         let from_primary_10 = list_by_primary_channel(10, &db).await.unwrap();
         assert_eq!(from_primary_10.len(), 1);
         assert_eq!(from_primary_10[0].id, 100);
+        assert_eq!(count_by_primary_channel(10, &db).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn count_by_creator_is_scoped_to_the_guild_and_ignores_transfers() {
+        let db = test_db().await;
+        seed(&db, 1, 10).await;
+        seed(&db, 2, 20).await;
+        insert(100, 1, 10, 42, 1, &db).await.unwrap();
+        insert(101, 1, 10, 42, 2, &db).await.unwrap();
+        insert(200, 2, 20, 42, 1, &db).await.unwrap();
+        assert_eq!(count_by_creator(1, 42, &db).await.unwrap(), 2);
+
+        set_owner(101, Some(7), &db).await.unwrap();
+        assert_eq!(count_by_creator(1, 42, &db).await.unwrap(), 2);
+        assert_eq!(count_by_creator(1, 7, &db).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn next_ordinal_fills_the_lowest_gap() {
+        let db = test_db().await;
+        seed(&db, 1, 10).await;
+        seed(&db, 1, 11).await;
+
+        assert_eq!(next_ordinal(10, &db).await.unwrap(), 1);
+
+        insert(100, 1, 10, 42, 1, &db).await.unwrap();
+        insert(101, 1, 10, 42, 2, &db).await.unwrap();
+        insert(102, 1, 10, 42, 3, &db).await.unwrap();
+        assert_eq!(next_ordinal(10, &db).await.unwrap(), 4);
+
+        delete(101, &db).await.unwrap();
+        assert_eq!(next_ordinal(10, &db).await.unwrap(), 2);
+
+        // Other triggers number independently.
+        assert_eq!(next_ordinal(11, &db).await.unwrap(), 1);
     }
 }
@@ -0,0 +1,102 @@
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+
+use crate::entities::trigger_ban::{self, Entity as TriggerBan};
+use crate::error::DbError;
+
+/// Bans `user_id` from creating channels until `until`, replacing any earlier ban.
+pub async fn ban(
+    guild_id: i64,
+    user_id: i64,
+    until: i64,
+    db: &DatabaseConnection,
+) -> Result<(), DbError> {
+    let model = trigger_ban::ActiveModel {
+        guild_id: Set(guild_id),
+        user_id: Set(user_id),
+        until: Set(until),
+    };
+    TriggerBan::insert(model)
+        .on_conflict(
+            OnConflict::columns([trigger_ban::Column::GuildId, trigger_ban::Column::UserId])
+                .update_column(trigger_ban::Column::Until)
+                .to_owned(),
+        )
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// When `user_id`'s ban ends, if they're banned at `now`.
+pub async fn active_until(
+    guild_id: i64,
+    user_id: i64,
+    now: i64,
+    db: &DatabaseConnection,
+) -> Result<Option<i64>, DbError> {
+    Ok(TriggerBan::find_by_id((guild_id, user_id))
+        .one(db)
+        .await?
+        .map(|ban| ban.until)
+        .filter(|&until| until > now))
+}
+
+/// Returns `true` if a ban was removed.
+pub async fn lift(guild_id: i64, user_id: i64, db: &DatabaseConnection) -> Result<bool, DbError> {
+    let result = TriggerBan::delete_by_id((guild_id, user_id))
+        .exec(db)
+        .await?;
+    Ok(result.rows_affected > 0)
+}
+
+/// Deletes bans that ended by `now`. Returns how many were removed.
+pub async fn delete_expired(now: i64, db: &DatabaseConnection) -> Result<u64, DbError> {
+    let result = TriggerBan::delete_many()
+        .filter(trigger_ban::Column::Until.lte(now))
+        .exec(db)
+        .await?;
+    Ok(result.rows_affected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::test_db;
+
+    #[tokio::test]
+    async fn ban_is_active_until_it_expires() {
+        let db = test_db().await;
+        crate::repositories::guild::upsert(1, &db).await.unwrap();
+        assert_eq!(active_until(1, 7, 0, &db).await.unwrap(), None);
+
+        ban(1, 7, 1_000, &db).await.unwrap();
+        assert_eq!(active_until(1, 7, 999, &db).await.unwrap(), Some(1_000));
+        assert_eq!(active_until(1, 7, 1_000, &db).await.unwrap(), None);
+        assert_eq!(active_until(2, 7, 999, &db).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn ban_extends_and_lift_removes() {
+        let db = test_db().await;
+        crate::repositories::guild::upsert(1, &db).await.unwrap();
+        ban(1, 7, 1_000, &db).await.unwrap();
+        ban(1, 7, 2_000, &db).await.unwrap();
+        assert_eq!(active_until(1, 7, 1_500, &db).await.unwrap(), Some(2_000));
+
+        assert!(lift(1, 7, &db).await.unwrap());
+        assert!(!lift(1, 7, &db).await.unwrap());
+        assert_eq!(active_until(1, 7, 1_500, &db).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn delete_expired_keeps_running_bans() {
+        let db = test_db().await;
+        crate::repositories::guild::upsert(1, &db).await.unwrap();
+        ban(1, 7, 1_000, &db).await.unwrap();
+        ban(1, 8, 2_000, &db).await.unwrap();
+
+        assert_eq!(delete_expired(1_000, &db).await.unwrap(), 1);
+        assert!(!lift(1, 7, &db).await.unwrap());
+        assert_eq!(active_until(1, 8, 1_000, &db).await.unwrap(), Some(2_000));
+    }
+}
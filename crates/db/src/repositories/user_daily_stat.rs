@@ -0,0 +1,89 @@
+use sea_orm::sea_query::{Expr, OnConflict};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+
+use crate::entities::user_daily_stat::{self, Entity as UserDailyStat};
+use crate::error::DbError;
+
+const DAY_SECS: i64 = 24 * 60 * 60;
+
+/// The UTC day `at` falls on, as days since the Unix epoch.
+pub fn day_of(at: i64) -> i64 {
+    at.div_euclid(DAY_SECS)
+}
+
+/// Adds to the member's totals for the day `at` falls on.
+pub async fn add(
+    user_id: i64,
+    guild_id: i64,
+    at: i64,
+    xp_delta: i64,
+    seconds_delta: i64,
+    db: &DatabaseConnection,
+) -> Result<(), DbError> {
+    let model = user_daily_stat::ActiveModel {
+        guild_id: Set(guild_id),
+        user_id: Set(user_id),
+        day: Set(day_of(at)),
+        xp: Set(xp_delta),
+        voice_seconds: Set(seconds_delta),
+    };
+    UserDailyStat::insert(model)
+        .on_conflict(
+            OnConflict::columns([
+                user_daily_stat::Column::GuildId,
+                user_daily_stat::Column::UserId,
+                user_daily_stat::Column::Day,
+            ])
+            .value(
+                user_daily_stat::Column::Xp,
+                Expr::col((UserDailyStat, user_daily_stat::Column::Xp)).add(xp_delta),
+            )
+            .value(
+                user_daily_stat::Column::VoiceSeconds,
+                Expr::col((UserDailyStat, user_daily_stat::Column::VoiceSeconds))
+                    .add(seconds_delta),
+            )
+            .to_owned(),
+        )
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// Deletes every guild's totals for days before `day`, returning how many.
+pub async fn delete_before(day: i64, db: &DatabaseConnection) -> Result<u64, DbError> {
+    let result = UserDailyStat::delete_many()
+        .filter(user_daily_stat::Column::Day.lt(day))
+        .exec(db)
+        .await?;
+    Ok(result.rows_affected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::test_db;
+
+    #[test]
+    fn days_start_at_midnight_utc() {
+        assert_eq!(day_of(0), 0);
+        assert_eq!(day_of(DAY_SECS - 1), 0);
+        assert_eq!(day_of(DAY_SECS), 1);
+    }
+
+    #[tokio::test]
+    async fn add_accumulates_per_day() {
+        let db = test_db().await;
+        crate::repositories::guild::upsert(1, &db).await.unwrap();
+        add(42, 1, 10, 100, 100, &db).await.unwrap();
+        add(42, 1, 20, 50, 0, &db).await.unwrap();
+        add(42, 1, DAY_SECS, 7, 7, &db).await.unwrap();
+
+        let rows = UserDailyStat::find().all(&db).await.unwrap();
+        assert_eq!(rows.len(), 2);
+        let first = rows.iter().find(|r| r.day == 0).unwrap();
+        assert_eq!((first.xp, first.voice_seconds), (150, 100));
+
+        assert_eq!(delete_before(1, &db).await.unwrap(), 1);
+    }
+}
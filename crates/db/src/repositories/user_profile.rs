@@ -43,14 +43,18 @@ pub async fn get(
         .await?)
 }
 
+/// Adds to the member's lifetime totals, and to their totals for the day `at` falls on
+/// (see [`super::user_daily_stat`]) for windowed rankings.
 pub async fn add_xp(
     user_id: i64,
     guild_id: i64,
     xp_delta: i64,
     seconds_delta: i64,
+    at: i64,
     db: &DatabaseConnection,
 ) -> Result<(), DbError> {
     upsert(user_id, guild_id, db).await?;
+    super::user_daily_stat::add(user_id, guild_id, at, xp_delta, seconds_delta, db).await?;
     UserProfile::update_many()
         .col_expr(
             user_profile::Column::Xp,
@@ -158,8 +162,8 @@ mod tests {
         let db = test_db().await;
         seed_guild(&db, 1).await;
 
-        add_xp(42, 1, 100, 100, &db).await.unwrap();
-        add_xp(42, 1, 50, 50, &db).await.unwrap();
+        add_xp(42, 1, 100, 100, 1_000, &db).await.unwrap();
+        add_xp(42, 1, 50, 50, 1_000, &db).await.unwrap();
 
         let profile = get(42, 1, &db).await.unwrap().unwrap();
         assert_eq!(profile.xp, 150);
@@ -203,9 +207,9 @@ mod tests {
         seed_guild(&db, 1).await;
         seed_guild(&db, 2).await;
 
-        add_xp(1, 1, 0, 100, &db).await.unwrap();
-        add_xp(2, 1, 0, 250, &db).await.unwrap();
-        add_xp(3, 2, 0, 999, &db).await.unwrap();
+        add_xp(1, 1, 0, 100, 1_000, &db).await.unwrap();
+        add_xp(2, 1, 0, 250, 1_000, &db).await.unwrap();
+        add_xp(3, 2, 0, 999, 1_000, &db).await.unwrap();
 
         assert_eq!(total_voice_seconds_by_guild(1, &db).await.unwrap(), 350);
         assert_eq!(total_voice_seconds_by_guild(2, &db).await.unwrap(), 999);
@@ -216,9 +220,9 @@ mod tests {
         let db = test_db().await;
         seed_guild(&db, 1).await;
 
-        add_xp(1, 1, 50, 0, &db).await.unwrap();
-        add_xp(2, 1, 200, 0, &db).await.unwrap();
-        add_xp(3, 1, 100, 0, &db).await.unwrap();
+        add_xp(1, 1, 50, 0, 1_000, &db).await.unwrap();
+        add_xp(2, 1, 200, 0, 1_000, &db).await.unwrap();
+        add_xp(3, 1, 100, 0, 1_000, &db).await.unwrap();
 
         let ranked = list_top_by_guild(1, &db).await.unwrap();
         let ids: Vec<i64> = ranked.iter().map(|p| p.user_id).collect();
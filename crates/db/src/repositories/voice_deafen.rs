@@ -0,0 +1,71 @@
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{DatabaseConnection, EntityTrait, Set};
+
+use crate::entities::voice_deafen::{self, Entity as VoiceDeafen};
+use crate::error::DbError;
+
+/// Records that `user_id` was deafened inside `channel_id`, replacing any earlier entry.
+pub async fn set(
+    guild_id: i64,
+    user_id: i64,
+    channel_id: i64,
+    db: &DatabaseConnection,
+) -> Result<(), DbError> {
+    let model = voice_deafen::ActiveModel {
+        guild_id: Set(guild_id),
+        user_id: Set(user_id),
+        channel_id: Set(channel_id),
+    };
+    VoiceDeafen::insert(model)
+        .on_conflict(
+            OnConflict::columns([voice_deafen::Column::GuildId, voice_deafen::Column::UserId])
+                .update_column(voice_deafen::Column::ChannelId)
+                .to_owned(),
+        )
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+pub async fn find(
+    guild_id: i64,
+    user_id: i64,
+    db: &DatabaseConnection,
+) -> Result<Option<voice_deafen::Model>, DbError> {
+    Ok(VoiceDeafen::find_by_id((guild_id, user_id)).one(db).await?)
+}
+
+/// Returns `true` if an entry was removed.
+pub async fn delete(guild_id: i64, user_id: i64, db: &DatabaseConnection) -> Result<bool, DbError> {
+    let result = VoiceDeafen::delete_by_id((guild_id, user_id))
+        .exec(db)
+        .await?;
+    Ok(result.rows_affected > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::test_db;
+
+    #[tokio::test]
+    async fn set_replaces_the_channel() {
+        let db = test_db().await;
+        crate::repositories::guild::upsert(1, &db).await.unwrap();
+
+        set(1, 7, 100, &db).await.unwrap();
+        set(1, 7, 200, &db).await.unwrap();
+        assert_eq!(find(1, 7, &db).await.unwrap().unwrap().channel_id, 200);
+    }
+
+    #[tokio::test]
+    async fn delete_reports_whether_an_entry_existed() {
+        let db = test_db().await;
+        crate::repositories::guild::upsert(1, &db).await.unwrap();
+
+        set(1, 7, 100, &db).await.unwrap();
+        assert!(delete(1, 7, &db).await.unwrap());
+        assert!(!delete(1, 7, &db).await.unwrap());
+        assert!(find(1, 7, &db).await.unwrap().is_none());
+    }
+}
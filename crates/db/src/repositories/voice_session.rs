@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 
-use sea_orm::sea_query::OnConflict;
+use sea_orm::sea_query::{Expr, OnConflict};
 use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, Set};
 
 use crate::entities::voice_session::{self, Entity as VoiceSession};
@@ -9,6 +9,7 @@ use crate::error::DbError;
 pub async fn start(
     user_id: i64,
     guild_id: i64,
+    channel_id: i64,
     joined_at: i64,
     db: &DatabaseConnection,
 ) -> Result<(), DbError> {
@@ -16,6 +17,11 @@ pub async fn start(
         user_id: Set(user_id),
         guild_id: Set(guild_id),
         joined_at: Set(joined_at),
+        idle_since: Set(None),
+        idle_secs: Set(0),
+        credited_secs: Set(0),
+        channel_id: Set(Some(channel_id)),
+        started_at: Set(joined_at),
     };
     match VoiceSession::insert(model)
         .on_conflict(
@@ -34,11 +40,12 @@ pub async fn start(
     }
 }
 
+/// Closes the session, returning it as it stood so the caller can credit it.
 pub async fn end(
     user_id: i64,
     guild_id: i64,
     db: &DatabaseConnection,
-) -> Result<Option<i64>, DbError> {
+) -> Result<Option<voice_session::Model>, DbError> {
     let session = VoiceSession::find()
         .filter(voice_session::Column::UserId.eq(user_id))
         .filter(voice_session::Column::GuildId.eq(guild_id))
@@ -48,7 +55,6 @@ pub async fn end(
     let Some(session) = session else {
         return Ok(None);
     };
-    let joined_at = session.joined_at;
 
     VoiceSession::delete_many()
         .filter(voice_session::Column::UserId.eq(user_id))
@@ -56,7 +62,79 @@ pub async fn end(
         .exec(db)
         .await?;
 
-    Ok(Some(joined_at))
+    Ok(Some(session))
+}
+
+/// Marks the open session as idle from `now`, or folds the finished idle stretch into
+/// `idle_secs` when `idle` is false. Only the part of the stretch since `joined_at` is
+/// folded in; a checkpoint already accounted for the rest. Does nothing without an open
+/// session or when the state doesn't change.
+pub async fn set_idle(
+    user_id: i64,
+    guild_id: i64,
+    idle: bool,
+    now: i64,
+    db: &DatabaseConnection,
+) -> Result<(), DbError> {
+    let Some(session) = VoiceSession::find_by_id((user_id, guild_id))
+        .one(db)
+        .await?
+    else {
+        return Ok(());
+    };
+    let model = match (idle, session.idle_since) {
+        (true, None) => voice_session::ActiveModel {
+            user_id: Set(user_id),
+            guild_id: Set(guild_id),
+            idle_since: Set(Some(now)),
+            ..Default::default()
+        },
+        (false, Some(since)) => voice_session::ActiveModel {
+            user_id: Set(user_id),
+            guild_id: Set(guild_id),
+            idle_since: Set(None),
+            idle_secs: Set(session.idle_secs + (now - since.max(session.joined_at)).max(0)),
+            ..Default::default()
+        },
+        _ => return Ok(()),
+    };
+    VoiceSession::update(model).exec(db).await?;
+    Ok(())
+}
+
+/// Moves the session's start to `now` after `credited` seconds of it were credited.
+/// An ongoing idle stretch keeps its `idle_since`, so the idle sweeper still sees when
+/// the member went idle. Returns `false`, changing nothing, if the session ended or
+/// changed since `session` was read, so the caller must not credit it.
+pub async fn checkpoint(
+    session: &voice_session::Model,
+    credited: i64,
+    now: i64,
+    db: &DatabaseConnection,
+) -> Result<bool, DbError> {
+    let idle_since = match session.idle_since {
+        Some(since) => voice_session::Column::IdleSince.eq(since),
+        None => voice_session::Column::IdleSince.is_null(),
+    };
+    let result = VoiceSession::update_many()
+        .col_expr(voice_session::Column::JoinedAt, Expr::value(now))
+        .col_expr(voice_session::Column::IdleSecs, Expr::value(0))
+        .col_expr(
+            voice_session::Column::CreditedSecs,
+            Expr::value(session.credited_secs + credited),
+        )
+        .filter(voice_session::Column::UserId.eq(session.user_id))
+        .filter(voice_session::Column::GuildId.eq(session.guild_id))
+        .filter(voice_session::Column::JoinedAt.eq(session.joined_at))
+        .filter(voice_session::Column::IdleSecs.eq(session.idle_secs))
+        .filter(idle_since)
+        .exec(db)
+        .await?;
+    Ok(result.rows_affected > 0)
+}
+
+pub async fn list_all(db: &DatabaseConnection) -> Result<Vec<voice_session::Model>, DbError> {
+    Ok(VoiceSession::find().all(db).await?)
 }
 
 pub async fn list_by_guild(
@@ -104,8 +182,8 @@ mod tests {
         let db = test_db().await;
         seed_guild(&db, 1).await;
 
-        start(42, 1, 1_000, &db).await.unwrap();
-        let joined_at = end(42, 1, &db).await.unwrap();
+        start(42, 1, 100, 1_000, &db).await.unwrap();
+        let joined_at = end(42, 1, &db).await.unwrap().map(|s| s.joined_at);
         assert_eq!(joined_at, Some(1_000));
 
         // Session was removed by `end`.
@@ -117,10 +195,65 @@ mod tests {
         let db = test_db().await;
         seed_guild(&db, 1).await;
 
-        start(42, 1, 1_000, &db).await.unwrap();
-        start(42, 1, 2_000, &db).await.unwrap(); // reconnect race — should be a no-op
+        start(42, 1, 100, 1_000, &db).await.unwrap();
+        start(42, 1, 100, 2_000, &db).await.unwrap(); // reconnect race — should be a no-op
 
-        assert_eq!(end(42, 1, &db).await.unwrap(), Some(1_000));
+        assert_eq!(
+            end(42, 1, &db).await.unwrap().map(|s| s.joined_at),
+            Some(1_000)
+        );
+    }
+
+    #[tokio::test]
+    async fn set_idle_accumulates_finished_idle_stretches() {
+        let db = test_db().await;
+        seed_guild(&db, 1).await;
+        start(42, 1, 100, 1_000, &db).await.unwrap();
+
+        set_idle(42, 1, true, 1_100, &db).await.unwrap();
+        // Repeating the current state keeps the original start.
+        set_idle(42, 1, true, 1_150, &db).await.unwrap();
+        set_idle(42, 1, false, 1_400, &db).await.unwrap();
+        set_idle(42, 1, true, 1_500, &db).await.unwrap();
+
+        let session = end(42, 1, &db).await.unwrap().unwrap();
+        assert_eq!(session.idle_secs, 300);
+        assert_eq!(session.idle_since, Some(1_500));
+    }
+
+    #[tokio::test]
+    async fn checkpoint_restarts_the_session_and_keeps_idle_since() {
+        let db = test_db().await;
+        seed_guild(&db, 1).await;
+        start(42, 1, 100, 1_000, &db).await.unwrap();
+        set_idle(42, 1, true, 1_100, &db).await.unwrap();
+        set_idle(42, 1, false, 1_200, &db).await.unwrap();
+        set_idle(42, 1, true, 1_500, &db).await.unwrap();
+
+        let session = list_all(&db).await.unwrap().remove(0);
+        assert!(checkpoint(&session, 400, 1_600, &db).await.unwrap());
+        // A stale copy no longer matches, so it can't be credited twice.
+        assert!(!checkpoint(&session, 400, 1_600, &db).await.unwrap());
+
+        let session = list_all(&db).await.unwrap().remove(0);
+        assert_eq!(session.joined_at, 1_600);
+        assert_eq!(session.started_at, 1_000);
+        assert_eq!(session.idle_secs, 0);
+        assert_eq!(session.idle_since, Some(1_500));
+        assert_eq!(session.credited_secs, 400);
+
+        // Only the idle time after the checkpoint is still unaccounted for.
+        set_idle(42, 1, false, 1_700, &db).await.unwrap();
+        let session = end(42, 1, &db).await.unwrap().unwrap();
+        assert_eq!(session.idle_secs, 100);
+    }
+
+    #[tokio::test]
+    async fn set_idle_without_a_session_is_a_no_op() {
+        let db = test_db().await;
+        seed_guild(&db, 1).await;
+        set_idle(42, 1, true, 1_000, &db).await.unwrap();
+        assert!(list_by_guild(1, &db).await.unwrap().is_empty());
     }
 
     #[tokio::test]
@@ -128,9 +261,9 @@ mod tests {
         let db = test_db().await;
         seed_guild(&db, 1).await;
 
-        start(1, 1, 1_000, &db).await.unwrap();
-        start(2, 1, 1_000, &db).await.unwrap();
-        start(3, 1, 1_000, &db).await.unwrap();
+        start(1, 1, 100, 1_000, &db).await.unwrap();
+        start(2, 1, 100, 1_000, &db).await.unwrap();
+        start(3, 1, 100, 1_000, &db).await.unwrap();
 
         delete_orphaned(1, &[1, 3], &db).await.unwrap();
 
@@ -0,0 +1,138 @@
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
+    QuerySelect, Set,
+};
+
+use crate::entities::voice_session::Model as VoiceSession;
+use crate::entities::voice_session_history::{self, Entity as VoiceSessionHistory};
+use crate::error::DbError;
+
+/// Keeps the finished `session`, which lasted `duration_secs` of active time.
+pub async fn record(
+    session: &VoiceSession,
+    primary_channel_id: Option<i64>,
+    game: Option<String>,
+    ended_at: i64,
+    duration_secs: i64,
+    db: &DatabaseConnection,
+) -> Result<(), DbError> {
+    let model = voice_session_history::ActiveModel {
+        user_id: Set(session.user_id),
+        guild_id: Set(session.guild_id),
+        channel_id: Set(session.channel_id),
+        primary_channel_id: Set(primary_channel_id),
+        started_at: Set(session.started_at),
+        ended_at: Set(ended_at),
+        duration_secs: Set(duration_secs),
+        game: Set(game),
+        ..Default::default()
+    };
+    VoiceSessionHistory::insert(model).exec(db).await?;
+    Ok(())
+}
+
+pub async fn count_by_member(
+    guild_id: i64,
+    user_id: i64,
+    db: &DatabaseConnection,
+) -> Result<u64, DbError> {
+    Ok(VoiceSessionHistory::find()
+        .filter(voice_session_history::Column::GuildId.eq(guild_id))
+        .filter(voice_session_history::Column::UserId.eq(user_id))
+        .count(db)
+        .await?)
+}
+
+/// One page of the member's sessions, newest first.
+pub async fn list_by_member(
+    guild_id: i64,
+    user_id: i64,
+    page: u64,
+    page_size: u64,
+    db: &DatabaseConnection,
+) -> Result<Vec<voice_session_history::Model>, DbError> {
+    Ok(VoiceSessionHistory::find()
+        .filter(voice_session_history::Column::GuildId.eq(guild_id))
+        .filter(voice_session_history::Column::UserId.eq(user_id))
+        .order_by_desc(voice_session_history::Column::EndedAt)
+        .order_by_desc(voice_session_history::Column::Id)
+        .offset(page * page_size)
+        .limit(page_size)
+        .all(db)
+        .await?)
+}
+
+/// Deletes the guild's sessions that ended before `cutoff`, returning how many.
+pub async fn delete_ended_before(
+    guild_id: i64,
+    cutoff: i64,
+    db: &DatabaseConnection,
+) -> Result<u64, DbError> {
+    let result = VoiceSessionHistory::delete_many()
+        .filter(voice_session_history::Column::GuildId.eq(guild_id))
+        .filter(voice_session_history::Column::EndedAt.lt(cutoff))
+        .exec(db)
+        .await?;
+    Ok(result.rows_affected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::test_db;
+
+    async fn seed(db: &DatabaseConnection, user_id: i64, started_at: i64, ended_at: i64) {
+        let session = VoiceSession {
+            user_id,
+            guild_id: 1,
+            joined_at: started_at,
+            idle_since: None,
+            idle_secs: 0,
+            credited_secs: 0,
+            channel_id: Some(100),
+            started_at,
+        };
+        record(
+            &session,
+            Some(10),
+            Some("Chess".to_owned()),
+            ended_at,
+            ended_at - started_at,
+            db,
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn pages_list_a_members_sessions_newest_first() {
+        let db = test_db().await;
+        crate::repositories::guild::upsert(1, &db).await.unwrap();
+        for i in 0..5 {
+            seed(&db, 42, i * 1_000, i * 1_000 + 600).await;
+        }
+        seed(&db, 7, 0, 600).await;
+
+        assert_eq!(count_by_member(1, 42, &db).await.unwrap(), 5);
+        let first = list_by_member(1, 42, 0, 2, &db).await.unwrap();
+        assert_eq!(
+            first.iter().map(|s| s.ended_at).collect::<Vec<_>>(),
+            vec![4_600, 3_600]
+        );
+        let last = list_by_member(1, 42, 2, 2, &db).await.unwrap();
+        assert_eq!(last.len(), 1);
+        assert_eq!(last[0].game.as_deref(), Some("Chess"));
+        assert_eq!(last[0].duration_secs, 600);
+    }
+
+    #[tokio::test]
+    async fn delete_ended_before_keeps_newer_sessions() {
+        let db = test_db().await;
+        crate::repositories::guild::upsert(1, &db).await.unwrap();
+        seed(&db, 42, 0, 600).await;
+        seed(&db, 42, 1_000, 1_600).await;
+
+        assert_eq!(delete_ended_before(1, 1_000, &db).await.unwrap(), 1);
+        assert_eq!(count_by_member(1, 42, &db).await.unwrap(), 1);
+    }
+}
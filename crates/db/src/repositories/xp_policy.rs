@@ -0,0 +1,72 @@
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{DatabaseConnection, EntityTrait, Set};
+
+use crate::entities::xp_policy::{self, Entity as XpPolicy};
+use crate::error::DbError;
+
+/// Returns the guild's XP policy, or `None` if it still uses the defaults.
+pub async fn get(
+    guild_id: i64,
+    db: &DatabaseConnection,
+) -> Result<Option<xp_policy::Model>, DbError> {
+    Ok(XpPolicy::find_by_id(guild_id).one(db).await?)
+}
+
+/// Inserts or replaces the guild's XP policy.
+pub async fn save(policy: xp_policy::Model, db: &DatabaseConnection) -> Result<(), DbError> {
+    let model = xp_policy::ActiveModel {
+        guild_id: Set(policy.guild_id),
+        xp_per_second: Set(policy.xp_per_second),
+        min_session_secs: Set(policy.min_session_secs),
+        daily_bonus_xp: Set(policy.daily_bonus_xp),
+        daily_early_secs: Set(policy.daily_early_secs),
+        daily_late_secs: Set(policy.daily_late_secs),
+        level_base: Set(policy.level_base),
+        level_growth: Set(policy.level_growth),
+    };
+    XpPolicy::insert(model)
+        .on_conflict(
+            OnConflict::column(xp_policy::Column::GuildId)
+                .update_columns([
+                    xp_policy::Column::XpPerSecond,
+                    xp_policy::Column::MinSessionSecs,
+                    xp_policy::Column::DailyBonusXp,
+                    xp_policy::Column::DailyEarlySecs,
+                    xp_policy::Column::DailyLateSecs,
+                    xp_policy::Column::LevelBase,
+                    xp_policy::Column::LevelGrowth,
+                ])
+                .to_owned(),
+        )
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::test_db;
+
+    #[tokio::test]
+    async fn save_get_roundtrip_and_overwrite() {
+        let db = test_db().await;
+        crate::repositories::guild::upsert(1, &db).await.unwrap();
+        assert_eq!(get(1, &db).await.unwrap(), None);
+
+        for (xp_per_second, level_growth) in [(0.5, 1.1), (2.0, 1.047)] {
+            let policy = xp_policy::Model {
+                guild_id: 1,
+                xp_per_second,
+                min_session_secs: 60,
+                daily_bonus_xp: 3600,
+                daily_early_secs: 20 * 3600,
+                daily_late_secs: 28 * 3600,
+                level_base: 1800,
+                level_growth,
+            };
+            save(policy.clone(), &db).await.unwrap();
+            assert_eq!(get(1, &db).await.unwrap(), Some(policy));
+        }
+    }
+}